@@ -0,0 +1,2 @@
+; Comments (#, including the shebang line)
+(comment) @comment
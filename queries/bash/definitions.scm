@@ -0,0 +1,20 @@
+; Function definitions (both `name() {}` and `function name {}`)
+(function_definition
+  name: (word) @name) @definition.function
+
+; Exported variables
+(declaration_command
+  "export"
+  (variable_assignment
+    name: (variable_name) @name)) @definition.export
+
+; Read-only variables
+(declaration_command
+  "readonly"
+  (variable_assignment
+    name: (variable_name) @name)) @definition.readonly
+
+; Top-level variable assignments
+(program
+  (variable_assignment
+    name: (variable_name) @name) @definition.variable)
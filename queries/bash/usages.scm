@@ -0,0 +1,6 @@
+; Variable references and assignments
+(variable_name) @usage
+
+; Commands (including calls to shell functions)
+(command_name
+  (word) @usage)
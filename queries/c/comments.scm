@@ -0,0 +1,2 @@
+; Comments (// and /* */)
+(comment) @comment
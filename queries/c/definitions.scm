@@ -0,0 +1,66 @@
+; Function definitions
+(function_definition
+  declarator: (function_declarator
+    declarator: (identifier) @name)) @definition.function
+
+; Function definitions returning pointers (int *f(void))
+(function_definition
+  declarator: (pointer_declarator
+    declarator: (function_declarator
+      declarator: (identifier) @name))) @definition.function
+
+; Function prototypes (typically in headers)
+(declaration
+  declarator: (function_declarator
+    declarator: (identifier) @name)) @definition.function
+
+(declaration
+  declarator: (pointer_declarator
+    declarator: (function_declarator
+      declarator: (identifier) @name))) @definition.function
+
+; Struct and union definitions (with a body)
+(struct_specifier
+  name: (type_identifier) @name
+  body: (field_declaration_list)) @definition.struct
+
+(union_specifier
+  name: (type_identifier) @name
+  body: (field_declaration_list)) @definition.struct
+
+; Enum definitions (with a body)
+(enum_specifier
+  name: (type_identifier) @name
+  body: (enumerator_list)) @definition.enum
+
+; Typedefs
+(type_definition
+  declarator: (type_identifier) @name) @definition.type_alias
+
+(type_definition
+  declarator: (pointer_declarator
+    declarator: (type_identifier) @name)) @definition.type_alias
+
+; Function pointer typedefs (typedef void (*handler_t)(int))
+(type_definition
+  declarator: (function_declarator
+    declarator: (parenthesized_declarator
+      (pointer_declarator
+        declarator: (type_identifier) @name)))) @definition.type_alias
+
+; Macros
+(preproc_def
+  name: (identifier) @name) @definition.macro
+
+(preproc_function_def
+  name: (identifier) @name) @definition.macro
+
+; Global variables
+(translation_unit
+  (declaration
+    declarator: (init_declarator
+      declarator: (identifier) @name)) @definition.variable)
+
+(translation_unit
+  (declaration
+    declarator: (identifier) @name) @definition.variable)
@@ -0,0 +1,9 @@
+; Identifiers (general usage)
+(identifier) @usage
+
+; Type identifiers
+(type_identifier) @usage
+
+; Field access expressions
+(field_expression
+  field: (field_identifier) @usage)
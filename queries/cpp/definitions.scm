@@ -0,0 +1,124 @@
+; Function definitions
+(function_definition
+  declarator: (function_declarator
+    declarator: (identifier) @name)) @definition.function
+
+; Function definitions returning pointers or references
+(function_definition
+  declarator: (pointer_declarator
+    declarator: (function_declarator
+      declarator: (identifier) @name))) @definition.function
+
+(function_definition
+  declarator: (reference_declarator
+    (function_declarator
+      declarator: (identifier) @name))) @definition.function
+
+; Function prototypes (typically in headers)
+(declaration
+  declarator: (function_declarator
+    declarator: (identifier) @name)) @definition.function
+
+(declaration
+  declarator: (pointer_declarator
+    declarator: (function_declarator
+      declarator: (identifier) @name))) @definition.function
+
+; Methods defined inside a class body
+(field_declaration_list
+  (function_definition
+    declarator: (function_declarator
+      declarator: (field_identifier) @name)) @definition.method)
+
+; Method declarations inside a class body
+(field_declaration
+  declarator: (function_declarator
+    declarator: (field_identifier) @name)) @definition.method
+
+; Constructors declared or defined inside a class body
+(field_declaration_list
+  (declaration
+    declarator: (function_declarator
+      declarator: (identifier) @name)) @definition.constructor)
+
+(field_declaration_list
+  (function_definition
+    declarator: (function_declarator
+      declarator: (identifier) @name)) @definition.constructor)
+
+; Constructors defined outside the class (Foo::Foo() {})
+(function_definition
+  declarator: (function_declarator
+    declarator: (qualified_identifier
+      scope: (namespace_identifier) @_scope
+      name: (identifier) @name))
+  (#eq? @_scope @name)) @definition.constructor
+
+; Methods defined outside the class (void Foo::bar() {})
+(function_definition
+  declarator: (function_declarator
+    declarator: (qualified_identifier
+      scope: (namespace_identifier) @_scope
+      name: (identifier) @name))
+  (#not-eq? @_scope @name)) @definition.method
+
+(function_definition
+  declarator: (pointer_declarator
+    declarator: (function_declarator
+      declarator: (qualified_identifier
+        name: (identifier) @name)))) @definition.method
+
+(function_definition
+  declarator: (reference_declarator
+    (function_declarator
+      declarator: (qualified_identifier
+        name: (identifier) @name)))) @definition.method
+
+; Class definitions (with a body)
+(class_specifier
+  name: (type_identifier) @name
+  body: (field_declaration_list)) @definition.class
+
+; Struct and union definitions (with a body)
+(struct_specifier
+  name: (type_identifier) @name
+  body: (field_declaration_list)) @definition.struct
+
+(union_specifier
+  name: (type_identifier) @name
+  body: (field_declaration_list)) @definition.struct
+
+; Enum and enum class definitions (with a body)
+(enum_specifier
+  name: (type_identifier) @name
+  body: (enumerator_list)) @definition.enum
+
+; Namespaces
+(namespace_definition
+  name: (namespace_identifier) @name) @definition.namespace
+
+; Typedefs and alias declarations (using Id = int;)
+(type_definition
+  declarator: (type_identifier) @name) @definition.type_alias
+
+(alias_declaration
+  name: (type_identifier) @name) @definition.type_alias
+
+; Macros
+(preproc_def
+  name: (identifier) @name) @definition.macro
+
+(preproc_function_def
+  name: (identifier) @name) @definition.macro
+
+; Global variables
+(translation_unit
+  (declaration
+    declarator: (init_declarator
+      declarator: (identifier) @name)) @definition.variable)
+
+(namespace_definition
+  body: (declaration_list
+    (declaration
+      declarator: (init_declarator
+        declarator: (identifier) @name)) @definition.variable))
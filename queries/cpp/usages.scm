@@ -0,0 +1,12 @@
+; Identifiers (general usage)
+(identifier) @usage
+
+; Type identifiers
+(type_identifier) @usage
+
+; Namespace identifiers (std::, app::)
+(namespace_identifier) @usage
+
+; Field access expressions
+(field_expression
+  field: (field_identifier) @usage)
@@ -0,0 +1,2 @@
+; Comments (//, /* */ and /// XML documentation)
+(comment) @comment
@@ -0,0 +1,53 @@
+; Class definitions
+(class_declaration
+  name: (identifier) @name) @definition.class
+
+; Record definitions (record and record struct)
+(record_declaration
+  name: (identifier) @name) @definition.record
+
+; Struct definitions
+(struct_declaration
+  name: (identifier) @name) @definition.struct
+
+; Interface definitions
+(interface_declaration
+  name: (identifier) @name) @definition.interface
+
+; Enum definitions
+(enum_declaration
+  name: (identifier) @name) @definition.enum
+
+; Delegate definitions
+(delegate_declaration
+  name: (identifier) @name) @definition.delegate
+
+; Method definitions
+(method_declaration
+  name: (identifier) @name) @definition.method
+
+; Constructor definitions
+(constructor_declaration
+  name: (identifier) @name) @definition.constructor
+
+; Property definitions
+(property_declaration
+  name: (identifier) @name) @definition.property
+
+; Field and event definitions
+(field_declaration
+  (variable_declaration
+    (variable_declarator
+      name: (identifier) @name))) @definition.field
+
+(event_field_declaration
+  (variable_declaration
+    (variable_declarator
+      name: (identifier) @name))) @definition.field
+
+; Namespace definitions (block and file-scoped; name may be qualified)
+(namespace_declaration
+  name: (_) @name) @definition.namespace
+
+(file_scoped_namespace_declaration
+  name: (_) @name) @definition.namespace
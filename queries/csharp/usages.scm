@@ -0,0 +1,2 @@
+; Identifiers (general usage, including type names)
+(identifier) @usage
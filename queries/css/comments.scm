@@ -0,0 +1,5 @@
+; Block comments (/* */)
+(comment) @comment
+
+; Non-standard line comments (//)
+(js_comment) @comment
@@ -0,0 +1,19 @@
+; <script lang="..."> bodies use the declared language
+(script_element
+  (start_tag
+    (attribute
+      (attribute_name) @_attr
+      (quoted_attribute_value
+        (attribute_value) @injection.language)))
+  (raw_text) @injection.content
+  (#eq? @_attr "lang"))
+
+; Other <script> bodies are JavaScript
+(script_element
+  (raw_text) @injection.content
+  (#set! injection.language "javascript"))
+
+; <style> bodies are CSS
+(style_element
+  (raw_text) @injection.content
+  (#set! injection.language "css"))
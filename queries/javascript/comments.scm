@@ -0,0 +1,5 @@
+; Comments (// and /* */)
+(comment) @comment
+
+; Legacy HTML-style comments (<!-- -->)
+(html_comment) @comment
@@ -0,0 +1,2 @@
+; Comments (JSON with comments, e.g. tsconfig.json)
+(comment) @comment
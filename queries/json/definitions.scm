@@ -0,0 +1,3 @@
+; Object members (reported under their dotted key path)
+(pair
+  key: (string) @name) @definition.key
@@ -0,0 +1,4 @@
+; Object keys
+(pair
+  key: (string
+    (string_content) @usage))
@@ -0,0 +1,5 @@
+; Line comments (//)
+(line_comment) @comment
+
+; Block and KDoc comments (/* */, /** */)
+(block_comment) @comment
@@ -0,0 +1,75 @@
+; Interface definitions (including fun interfaces)
+(class_declaration
+  "interface"
+  name: (identifier) @name) @definition.interface
+
+; Enum class definitions
+(class_declaration
+  (modifiers
+    (class_modifier "enum"))
+  name: (identifier) @name) @definition.enum
+
+; Class definitions (including data, sealed and abstract classes)
+(class_declaration
+  name: (identifier) @name) @definition.class
+
+; Object declarations
+(object_declaration
+  name: (identifier) @name) @definition.object
+
+; Named companion objects
+(companion_object
+  name: (identifier) @name) @definition.object
+
+; Unnamed companion objects, named after the keyword like constructors
+(companion_object
+  "companion" @name
+  !name) @definition.object
+
+; Type alias definitions
+(type_alias
+  type: (identifier) @name) @definition.type_alias
+
+; Top-level function definitions
+(source_file
+  (function_declaration
+    name: (identifier) @name) @definition.function)
+
+; Method definitions (in classes, objects and interfaces)
+(function_declaration
+  name: (identifier) @name) @definition.method
+
+; Secondary constructors
+(secondary_constructor
+  "constructor" @name) @definition.constructor
+
+; Compile-time constants (const val), top-level or in objects
+(source_file
+  (property_declaration
+    (modifiers
+      (property_modifier "const"))
+    (variable_declaration
+      (identifier) @name)) @definition.constant)
+
+(class_body
+  (property_declaration
+    (modifiers
+      (property_modifier "const"))
+    (variable_declaration
+      (identifier) @name)) @definition.constant)
+
+; Top-level and member properties (val and var)
+(source_file
+  (property_declaration
+    (variable_declaration
+      (identifier) @name)) @definition.property)
+
+(class_body
+  (property_declaration
+    (variable_declaration
+      (identifier) @name)) @definition.property)
+
+; Properties declared in the primary constructor
+(class_parameter
+  ["val" "var"]
+  (identifier) @name) @definition.property
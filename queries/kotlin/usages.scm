@@ -0,0 +1,2 @@
+; Identifiers (variables, functions, members and type names)
+(identifier) @usage
@@ -0,0 +1,3 @@
+; Markdown has no comment syntax of its own.
+; find_in_comments performs a full-text search on Markdown files instead,
+; so this query intentionally contains no patterns.
@@ -0,0 +1,5 @@
+; Fenced code blocks use the language from their info string
+(fenced_code_block
+  (info_string
+    (language) @injection.language)
+  (code_fence_content) @injection.content)
@@ -0,0 +1,2 @@
+; Comments (//, #, /* */ and /** */ doc blocks)
+(comment) @comment
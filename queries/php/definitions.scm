@@ -0,0 +1,46 @@
+; Namespace definitions
+(namespace_definition
+  name: (namespace_name) @name) @definition.namespace
+
+; Class definitions
+(class_declaration
+  name: (name) @name) @definition.class
+
+; Interface definitions
+(interface_declaration
+  name: (name) @name) @definition.interface
+
+; Trait definitions
+(trait_declaration
+  name: (name) @name) @definition.trait
+
+; Enum definitions
+(enum_declaration
+  name: (name) @name) @definition.enum
+
+; Constructor definitions
+((method_declaration
+  name: (name) @name) @definition.constructor
+  (#eq? @name "__construct"))
+
+; Static method definitions
+(method_declaration
+  (static_modifier)
+  name: (name) @name) @definition.static_method
+
+; Method definitions
+(method_declaration
+  name: (name) @name) @definition.method
+
+; Function definitions
+(function_definition
+  name: (name) @name) @definition.function
+
+; Constant definitions (class and top-level)
+(const_element
+  (name) @name) @definition.constant
+
+; Property definitions
+(property_element
+  name: (variable_name
+    (name) @name)) @definition.property
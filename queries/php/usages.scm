@@ -0,0 +1,2 @@
+; Names (functions, classes, methods, constants and variables)
+(name) @usage
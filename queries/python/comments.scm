@@ -0,0 +1,22 @@
+; Line comments (#)
+(comment) @comment
+
+; Module docstring
+(module
+  .
+  (expression_statement
+    (string) @comment.doc))
+
+; Class docstring
+(class_definition
+  body: (block
+    .
+    (expression_statement
+      (string) @comment.doc)))
+
+; Function/method docstring
+(function_definition
+  body: (block
+    .
+    (expression_statement
+      (string) @comment.doc)))
@@ -0,0 +1,2 @@
+; Comments (# and =begin/=end)
+(comment) @comment
@@ -0,0 +1,31 @@
+; Module definitions
+(module
+  name: (_) @name) @definition.module
+
+; Class definitions
+(class
+  name: (_) @name) @definition.class
+
+; Top-level method definitions
+(program
+  (method
+    name: (_) @name) @definition.function)
+
+; Singleton methods inside `class << self`
+(singleton_class
+  value: (self)
+  body: (body_statement
+    (method
+      name: (_) @name) @definition.static_method))
+
+; Singleton method definitions (def self.name)
+(singleton_method
+  name: (_) @name) @definition.static_method
+
+; Instance method definitions
+(method
+  name: (_) @name) @definition.method
+
+; Constant definitions
+(assignment
+  left: (constant) @name) @definition.constant
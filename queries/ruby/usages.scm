@@ -0,0 +1,5 @@
+; Identifiers (locals and method calls)
+(identifier) @usage
+
+; Constants (class, module and constant references)
+(constant) @usage
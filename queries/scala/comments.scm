@@ -0,0 +1,5 @@
+; Line comments (//)
+(comment) @comment
+
+; Block and Scaladoc comments (/* */, /** */)
+(block_comment) @comment
@@ -0,0 +1,68 @@
+; Class definitions (including case classes)
+(class_definition
+  name: (identifier) @name) @definition.class
+
+; Trait definitions
+(trait_definition
+  name: (identifier) @name) @definition.trait
+
+; Enum definitions
+(enum_definition
+  name: (identifier) @name) @definition.enum
+
+; Object definitions (including companion and case objects)
+(object_definition
+  name: (identifier) @name) @definition.object
+
+; Type alias definitions
+(type_definition
+  name: (type_identifier) @name) @definition.type_alias
+
+; Auxiliary constructors (def this(...))
+(template_body
+  (function_definition
+    name: (identifier) @name) @definition.constructor
+  (#eq? @name "this"))
+
+; Top-level function definitions
+(compilation_unit
+  (function_definition
+    name: (identifier) @name) @definition.function)
+
+; Method definitions (in classes, objects and traits)
+(template_body
+  (function_definition
+    name: (identifier) @name) @definition.method)
+
+(template_body
+  (function_declaration
+    name: (identifier) @name) @definition.method)
+
+; Top-level and member values and variables
+(compilation_unit
+  [(val_definition
+    pattern: (identifier) @name)
+   (var_definition
+    pattern: (identifier) @name)] @definition.property)
+
+(template_body
+  [(val_definition
+    pattern: (identifier) @name)
+   (var_definition
+    pattern: (identifier) @name)
+   (val_declaration
+    name: (identifier) @name)
+   (var_declaration
+    name: (identifier) @name)] @definition.property)
+
+; Values and variables declared in the class parameters
+(class_parameter
+  ["val" "var"]
+  name: (identifier) @name) @definition.property
+
+; Case class parameters, which are values without a val keyword
+(class_definition
+  "case"
+  class_parameters: (class_parameters
+    (class_parameter
+      name: (identifier) @name) @definition.property))
@@ -0,0 +1,5 @@
+; Identifiers (variables, functions and members)
+(identifier) @usage
+
+; Type names
+(type_identifier) @usage
@@ -0,0 +1,5 @@
+; Line comments (--)
+(comment) @comment
+
+; Block comments (/* */)
+(marginalia) @comment
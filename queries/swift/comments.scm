@@ -0,0 +1,4 @@
+; Comments (//, /* */ and /// documentation)
+(comment) @comment
+
+(multiline_comment) @comment
@@ -0,0 +1,65 @@
+; Class definitions
+(class_declaration
+  declaration_kind: "class"
+  name: (type_identifier) @name) @definition.class
+
+; Struct definitions
+(class_declaration
+  declaration_kind: "struct"
+  name: (type_identifier) @name) @definition.struct
+
+; Enum definitions
+(class_declaration
+  declaration_kind: "enum"
+  name: (type_identifier) @name) @definition.enum
+
+; Actor definitions
+(class_declaration
+  declaration_kind: "actor"
+  name: (type_identifier) @name) @definition.class
+
+; Extensions (like Rust impl blocks, named after the extended type)
+(class_declaration
+  declaration_kind: "extension"
+  name: (user_type
+    (type_identifier) @name)) @definition.extension
+
+; Protocol definitions
+(protocol_declaration
+  name: (type_identifier) @name) @definition.protocol
+
+; Type alias definitions
+(typealias_declaration
+  name: (type_identifier) @name) @definition.type_alias
+
+; Top-level function definitions
+(source_file
+  (function_declaration
+    name: (simple_identifier) @name) @definition.function)
+
+; Static and class methods
+((function_declaration
+  (modifiers
+    (property_modifier) @_modifier)
+  name: (simple_identifier) @name) @definition.static_method
+  (#match? @_modifier "^(static|class)$"))
+
+; Method definitions
+(function_declaration
+  name: (simple_identifier) @name) @definition.method
+
+(protocol_function_declaration
+  name: (simple_identifier) @name) @definition.method
+
+; Initializers
+(init_declaration
+  "init" @name) @definition.constructor
+
+; Property definitions (top-level, stored and computed)
+(property_declaration
+  name: (pattern
+    bound_identifier: (simple_identifier) @name)) @definition.property
+
+(protocol_property_declaration
+  name: (pattern
+    bound_identifier: (simple_identifier) @name)) @definition.property
@@ -0,0 +1,5 @@
+; Identifiers (variables, functions and members)
+(simple_identifier) @usage
+
+; Type names
+(type_identifier) @usage
@@ -0,0 +1,13 @@
+; Tables ([name] and [[name]])
+(table
+  .
+  [(bare_key) (dotted_key) (quoted_key)] @name) @definition.table
+
+(table_array_element
+  .
+  [(bare_key) (dotted_key) (quoted_key)] @name) @definition.table
+
+; Key/value pairs (reported under their dotted key path)
+(pair
+  .
+  [(bare_key) (dotted_key) (quoted_key)] @name) @definition.key
@@ -0,0 +1,5 @@
+; Keys in table headers and key/value pairs
+(bare_key) @usage
+
+; Quoted keys ("key" = value)
+(quoted_key) @usage
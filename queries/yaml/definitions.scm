@@ -0,0 +1,6 @@
+; Mapping entries (reported under their dotted key path)
+(block_mapping_pair
+  key: (_) @name) @definition.key
+
+(flow_pair
+  key: (_) @name) @definition.key
@@ -0,0 +1,19 @@
+; Mapping keys
+(block_mapping_pair
+  key: (flow_node
+    (plain_scalar
+      (string_scalar) @usage)))
+
+(flow_pair
+  key: (flow_node
+    (plain_scalar
+      (string_scalar) @usage)))
+
+; Quoted mapping keys ("key": value)
+(block_mapping_pair
+  key: (flow_node
+    [(double_quote_scalar) (single_quote_scalar)] @usage))
+
+(flow_pair
+  key: (flow_node
+    [(double_quote_scalar) (single_quote_scalar)] @usage))
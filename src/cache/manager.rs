@@ -35,7 +35,6 @@ impl CacheManager {
     }
 
     /// Clear all caches
-    #[allow(dead_code)]
     pub fn clear(&self) {
         self.parser_cache.clear();
         self.file_cache.clear();
@@ -1,11 +1,34 @@
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::Path;
 
+use anyhow::{Context, Result};
 use glob::Pattern;
 
 /// Default directories to exclude from search
 const DEFAULT_EXCLUDE_DIRS: &[&str] = &["dist", "build", ".next", "out", "coverage"];
 
+/// Files that tools reading a given path never return
+const DEFAULT_DENY_FILES: &[&str] = &[
+    // Environment files
+    ".env",
+    ".env.*",
+    // Private keys and certificate stores
+    "*.pem",
+    "*.key",
+    "id_rsa*",
+    "id_ed25519*",
+    "id_ecdsa*",
+    "*.p12",
+    "*.pfx",
+    // Registry and service credentials
+    ".npmrc",
+    ".pypirc",
+    ".netrc",
+    "credentials*.json",
+];
+
 /// CodeScope configuration
 #[derive(Debug, Default, Clone, Deserialize)]
 pub struct CodeScopeConfig {
@@ -16,24 +39,167 @@ pub struct CodeScopeConfig {
     /// Glob patterns to exclude from search (e.g., "**/*.test.ts", "**/__mocks__/**")
     #[serde(default)]
     pub exclude_patterns: Vec<String>,
+
+    /// Custom lint rules checked by the `lint` tool
+    #[serde(default)]
+    pub rules: Vec<LintRule>,
+
+    /// Directory of runtime-loaded language plugins, relative to the workspace root
+    ///
+    /// Only loaded if the server allows plugins, since they run native code.
+    #[serde(default)]
+    pub language_plugins: Option<String>,
+
+    /// Additional mappings from files to languages
+    #[serde(default)]
+    pub file_types: FileTypeConfig,
+
+    /// Additional workspace roots searched alongside this one
+    #[serde(default)]
+    pub roots: Vec<RootConfig>,
+
+    /// Glob patterns of files that tools reading a given path refuse, in
+    /// addition to environment files, private keys and credential files
+    /// (`.env`, `*.pem`, `id_rsa*`, `.npmrc`, ...). Patterns without a `/`
+    /// match file names; others match paths relative to the root.
+    #[serde(default)]
+    pub deny_files: Vec<String>,
+
+    /// Masking of secrets in returned code and comments
+    #[serde(default)]
+    pub redaction: RedactionConfig,
+}
+
+/// Masking of secrets in returned code and comments
+///
+/// AWS keys, JWTs, private key blocks, GitHub and Slack tokens, quoted
+/// password assignments and high-entropy string literals are detected.
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct RedactionConfig {
+    /// Mask secrets in `symbol_definition` and `get_symbol_at_location` code,
+    /// `get_code_at_location` snippets, `find_in_comments` content and the
+    /// results of `ast_query`, `find_pattern`, `lint` and `apply_fixes`
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Additional regexes of secrets; if a pattern has a `secret` group, only
+    /// that group is masked (e.g., "internal-(?P<secret>\\d{6})")
+    #[serde(default)]
+    pub patterns: Vec<String>,
+}
+
+/// An additional workspace root
+#[derive(Debug, Clone, Deserialize)]
+pub struct RootConfig {
+    /// Root name used by the `roots` tool parameter (default: the directory name)
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// Root directory, relative to the workspace root declaring it
+    pub path: String,
+}
+
+/// Additional mappings from files to languages
+///
+/// Languages are named like the language filter (e.g., "typescript", "sql").
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct FileTypeConfig {
+    /// File extensions without the dot (e.g., {"pgsql": "sql", "blade.php": "html"})
+    #[serde(default)]
+    pub extensions: HashMap<String, String>,
+
+    /// Glob patterns relative to the workspace root, checked in order before
+    /// file names and extensions
+    #[serde(default)]
+    pub globs: Vec<GlobMapping>,
+
+    /// Interpreters in the `#!` line of extensionless scripts (e.g., {"pypy": "python"})
+    #[serde(default)]
+    pub interpreters: HashMap<String, String>,
+}
+
+/// A glob pattern mapped to a language
+#[derive(Debug, Clone, Deserialize)]
+pub struct GlobMapping {
+    /// Glob pattern (e.g., "config/*.conf")
+    pub pattern: String,
+
+    /// Language name
+    pub language: String,
+}
+
+/// A custom lint rule
+///
+/// Each rule matches code with either a raw tree-sitter `query` or a code
+/// `pattern` with metavariables (see `find_pattern`), but not both.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LintRule {
+    /// Unique rule id reported with each diagnostic (e.g., "no-date-now")
+    pub id: String,
+
+    /// Language the rule applies to (e.g., "typescript", "rust")
+    pub language: String,
+
+    /// Tree-sitter query; the `@match` capture (or the whole match) is reported
+    #[serde(default)]
+    pub query: Option<String>,
+
+    /// Code pattern with metavariables (e.g., "Date.now()")
+    #[serde(default)]
+    pub pattern: Option<String>,
+
+    /// Regex constraints on pattern metavariables (e.g., {"X": "^self\\."})
+    #[serde(default)]
+    pub constraints: HashMap<String, String>,
+
+    /// Message reported for each match
+    pub message: String,
+
+    /// Severity reported for each match
+    #[serde(default)]
+    pub severity: Severity,
+
+    /// Glob patterns limiting the rule to matching paths (e.g., "**/domain/**")
+    #[serde(default)]
+    pub paths: Vec<String>,
+
+    /// Rewrite template for the reported code (e.g., "$X.expect(\"TODO\")")
+    ///
+    /// `$NAME` is replaced by the text of the pattern metavariable or query
+    /// capture `NAME`; query captures must be named in upper case to be used.
+    #[serde(default)]
+    pub fix: Option<String>,
+}
+
+/// Severity of a lint diagnostic
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    #[default]
+    Warning,
+    Info,
+    Hint,
 }
 
 impl CodeScopeConfig {
     /// Load configuration from .mcp.json in the workspace root
-    /// Falls back to default configuration if file doesn't exist or is invalid
-    pub fn load(workspace_root: &Path) -> Self {
+    ///
+    /// Falls back to the default configuration if the file doesn't exist.
+    /// An unreadable or invalid file is an error rather than the defaults,
+    /// which would drop the root's `deny_files` and `redaction` settings.
+    pub fn load(workspace_root: &Path) -> Result<Self> {
         let config_path = workspace_root.join(".mcp.json");
-        if config_path.exists() {
-            if let Ok(content) = std::fs::read_to_string(&config_path) {
-                if let Ok(config) = serde_json::from_str::<CodeScopeConfig>(&content) {
-                    tracing::info!("Loaded config from .mcp.json: {:?}", config);
-                    return config;
-                } else {
-                    tracing::warn!("Failed to parse .mcp.json, using defaults");
-                }
-            }
+        if !config_path.exists() {
+            return Ok(Self::default_config());
         }
-        Self::default_config()
+
+        let content = std::fs::read_to_string(&config_path)
+            .with_context(|| format!("Failed to read {}", config_path.display()))?;
+        let config = serde_json::from_str::<CodeScopeConfig>(&content)
+            .with_context(|| format!("Failed to parse {}", config_path.display()))?;
+        tracing::info!("Loaded config from .mcp.json: {:?}", config);
+        Ok(config)
     }
 
     /// Get the default configuration
@@ -41,6 +207,12 @@ impl CodeScopeConfig {
         Self {
             exclude_dirs: DEFAULT_EXCLUDE_DIRS.iter().map(|s| s.to_string()).collect(),
             exclude_patterns: Vec::new(),
+            rules: Vec::new(),
+            language_plugins: None,
+            file_types: FileTypeConfig::default(),
+            roots: Vec::new(),
+            deny_files: Vec::new(),
+            redaction: RedactionConfig::default(),
         }
     }
 
@@ -88,12 +260,39 @@ impl CodeScopeConfig {
 
         false
     }
+
+    /// Check if a file, given relative to the workspace root, is on the deny list
+    pub fn is_denied_file(&self, relative_path: &Path) -> bool {
+        let file_name = relative_path
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+        let relative_str = relative_path.to_string_lossy();
+
+        DEFAULT_DENY_FILES
+            .iter()
+            .copied()
+            .chain(self.deny_files.iter().map(String::as_str))
+            .any(|pattern_str| match Pattern::new(pattern_str) {
+                Ok(pattern) if pattern_str.contains('/') => pattern.matches(&relative_str),
+                Ok(pattern) => pattern.matches(&file_name),
+                Err(e) => {
+                    tracing::warn!(
+                        "Invalid deny pattern '{}': {}. Pattern will be ignored.",
+                        pattern_str,
+                        e
+                    );
+                    false
+                }
+            })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::path::PathBuf;
+    use tempfile::TempDir;
 
     #[test]
     fn test_default_config() {
@@ -138,4 +337,103 @@ mod tests {
         let regular_path = PathBuf::from("/project/src/utils.ts");
         assert!(!config.should_exclude(&regular_path, None));
     }
+
+    #[test]
+    fn test_load() {
+        let dir = TempDir::new().unwrap();
+        let config = CodeScopeConfig::load(dir.path()).unwrap();
+        assert_eq!(
+            config.exclude_dirs,
+            CodeScopeConfig::default_config().exclude_dirs
+        );
+
+        std::fs::write(dir.path().join(".mcp.json"), r#"{"deny_files": ["*.db"]}"#).unwrap();
+        let config = CodeScopeConfig::load(dir.path()).unwrap();
+        assert_eq!(config.deny_files, ["*.db"]);
+
+        std::fs::write(dir.path().join(".mcp.json"), r#"{"deny_files": "*.db"}"#).unwrap();
+        let error = CodeScopeConfig::load(dir.path()).unwrap_err();
+        assert!(
+            format!("{:#}", error).contains("Failed to parse"),
+            "{:#}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_is_denied_file() {
+        let config = CodeScopeConfig {
+            deny_files: vec!["secrets/**".to_string(), "*.jks".to_string()],
+            ..CodeScopeConfig::default_config()
+        };
+
+        assert!(config.is_denied_file(Path::new(".env")));
+        assert!(config.is_denied_file(Path::new("app/.env.production")));
+        assert!(config.is_denied_file(Path::new("certs/server.pem")));
+        assert!(config.is_denied_file(Path::new("secrets/db/password.txt")));
+        assert!(config.is_denied_file(Path::new("keystore.jks")));
+        assert!(config.is_denied_file(Path::new("deploy/id_rsa")));
+        assert!(config.is_denied_file(Path::new("home/.ssh/id_ed25519.pub")));
+        assert!(config.is_denied_file(Path::new("id_ecdsa")));
+        assert!(config.is_denied_file(Path::new("certs/client.p12")));
+        assert!(config.is_denied_file(Path::new("certs/client.pfx")));
+        assert!(config.is_denied_file(Path::new(".npmrc")));
+        assert!(config.is_denied_file(Path::new("home/.pypirc")));
+        assert!(config.is_denied_file(Path::new(".netrc")));
+        assert!(config.is_denied_file(Path::new("credentials.json")));
+        assert!(config.is_denied_file(Path::new("gcp/credentials-prod.json")));
+
+        assert!(!config.is_denied_file(Path::new("src/env.ts")));
+        assert!(!config.is_denied_file(Path::new(".envrc")));
+        assert!(!config.is_denied_file(Path::new("docs/secrets.md")));
+        assert!(!config.is_denied_file(Path::new("src/credentials.ts")));
+        assert!(!config.is_denied_file(Path::new(".npmignore")));
+    }
+
+    #[test]
+    fn test_parse_rules() {
+        let json = r#"{
+            "rules": [
+                {
+                    "id": "no-date-now",
+                    "language": "typescript",
+                    "pattern": "Date.now()",
+                    "message": "Inject a clock instead",
+                    "severity": "error",
+                    "paths": ["**/domain/**"]
+                },
+                {
+                    "id": "no-unwrap",
+                    "language": "rust",
+                    "query": "(call_expression) @match",
+                    "message": "Avoid unwrap"
+                }
+            ]
+        }"#;
+        let config: CodeScopeConfig = serde_json::from_str(json).unwrap();
+
+        assert_eq!(config.rules.len(), 2);
+        assert_eq!(config.rules[0].id, "no-date-now");
+        assert_eq!(config.rules[0].severity, Severity::Error);
+        assert_eq!(config.rules[0].paths, vec!["**/domain/**"]);
+        assert_eq!(config.rules[1].severity, Severity::Warning);
+        assert!(config.rules[1].pattern.is_none());
+        assert!(config.rules[1].fix.is_none());
+    }
+
+    #[test]
+    fn test_parse_roots() {
+        let json = r#"{
+            "roots": [
+                { "name": "shared", "path": "../shared-lib" },
+                { "path": "../docs" }
+            ]
+        }"#;
+        let config: CodeScopeConfig = serde_json::from_str(json).unwrap();
+
+        assert_eq!(config.roots.len(), 2);
+        assert_eq!(config.roots[0].name.as_deref(), Some("shared"));
+        assert_eq!(config.roots[1].path, "../docs");
+        assert!(config.roots[1].name.is_none());
+    }
 }
@@ -0,0 +1,34 @@
+use crate::symbol::types::SymbolKind;
+
+use super::traits::SymbolKindMapping;
+
+define_language! {
+    name: BashLanguage,
+    id: Bash,
+    display_name: "Bash",
+    extensions: ["sh", "bash"],
+    file_names: [".bashrc", ".bash_profile", ".profile"],
+    tree_sitter_language: tree_sitter_bash::LANGUAGE,
+    query_dir: "bash",
+    mappings: BASH_DEFINITION_MAPPINGS,
+}
+
+/// Definition mappings for shell scripts
+const BASH_DEFINITION_MAPPINGS: &[SymbolKindMapping] = &[
+    SymbolKindMapping {
+        capture_name: "definition.function",
+        kind: SymbolKind::Function,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.export",
+        kind: SymbolKind::Variable,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.readonly",
+        kind: SymbolKind::Const,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.variable",
+        kind: SymbolKind::Variable,
+    },
+];
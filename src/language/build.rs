@@ -0,0 +1,469 @@
+// Dockerfile and Makefile support
+//
+// Neither format has a tree-sitter grammar in the build. Both are
+// line-oriented, so definitions come from a line scanner: Make targets,
+// variables and `define` blocks, and Dockerfile `FROM ... AS name` stages,
+// `ARG`s and `ENV`s. Everything else goes through the Bash grammar and
+// queries, which is close enough for the shell commands of recipes and `RUN`
+// instructions: usages, comments and AST queries work as in a shell script.
+//
+// Known limitations:
+// - Targets or variables named through expansions (`$(NAME)_dir:`) and
+//   pattern rules (`%.o: %.c`) are not reported.
+// - Dockerfile parser directives (`# escape=`) are ignored; `\` is always the
+//   line continuation.
+
+use anyhow::Result;
+use tree_sitter::{Language, Query};
+
+use super::bash::BashLanguage;
+use super::traits::{LanguageId, LanguageSupport, QueryKind, ScannedDefinition, SymbolKindMapping};
+use crate::symbol::types::SymbolKind;
+
+/// Define a language parsed with the Bash grammar whose definitions come
+/// from a line scanner
+macro_rules! line_scanned_language {
+    (
+        name: $name:ident,
+        id: $id:ident,
+        display_name: $display:literal,
+        extensions: [$($ext:literal),+ $(,)?],
+        file_names: [$($file_name:literal),* $(,)?],
+        scanner: $scanner:path $(,)?
+    ) => {
+        pub struct $name {
+            bash: BashLanguage,
+        }
+
+        impl $name {
+            pub fn new() -> Result<Self> {
+                Ok(Self {
+                    bash: BashLanguage::new()?,
+                })
+            }
+        }
+
+        impl LanguageSupport for $name {
+            fn id(&self) -> LanguageId {
+                LanguageId::$id
+            }
+
+            fn name(&self) -> &'static str {
+                $display
+            }
+
+            fn file_extensions(&self) -> &[&'static str] {
+                &[$($ext),+]
+            }
+
+            fn file_names(&self) -> &[&'static str] {
+                &[$($file_name),*]
+            }
+
+            fn tree_sitter_language(&self) -> &Language {
+                self.bash.tree_sitter_language()
+            }
+
+            fn definitions_query(&self) -> &Query {
+                self.bash.definitions_query()
+            }
+
+            fn usages_query(&self) -> &Query {
+                self.bash.usages_query()
+            }
+
+            fn comments_query(&self) -> &Query {
+                self.bash.comments_query()
+            }
+
+            fn query_source(&self, kind: QueryKind) -> Option<&str> {
+                match kind {
+                    // Definitions are scanned, so there is no query to extend
+                    QueryKind::Definitions => None,
+                    _ => self.bash.query_source(kind),
+                }
+            }
+
+            fn definition_mappings(&self) -> &[SymbolKindMapping] {
+                &[]
+            }
+
+            fn scan_definitions(&self, source: &str) -> Option<Vec<ScannedDefinition>> {
+                Some($scanner(source))
+            }
+        }
+    };
+}
+
+line_scanned_language! {
+    name: DockerfileLanguage,
+    id: Dockerfile,
+    display_name: "Dockerfile",
+    extensions: ["dockerfile"],
+    file_names: ["Dockerfile", "Containerfile"],
+    scanner: scan_dockerfile,
+}
+
+line_scanned_language! {
+    name: MakefileLanguage,
+    id: Makefile,
+    display_name: "Makefile",
+    extensions: ["mk", "mak"],
+    file_names: ["Makefile", "makefile", "GNUmakefile"],
+    scanner: scan_makefile,
+}
+
+/// A source line without its line terminator
+struct Line<'a> {
+    /// Byte offset of the line in the source
+    start: usize,
+    text: &'a str,
+}
+
+fn split_lines(source: &str) -> Vec<Line<'_>> {
+    let mut offset = 0;
+    source
+        .split_inclusive('\n')
+        .map(|raw| {
+            let line = Line {
+                start: offset,
+                text: raw.trim_end_matches(['\n', '\r']),
+            };
+            offset += raw.len();
+            line
+        })
+        .collect()
+}
+
+/// Last row of the logical line starting at `row`, following `\` continuations
+fn continuation_end(lines: &[Line], row: usize) -> usize {
+    let mut end = row;
+    while end + 1 < lines.len() && lines[end].text.ends_with('\\') {
+        end += 1;
+    }
+    end
+}
+
+fn definition(
+    lines: &[Line],
+    name: &str,
+    kind: SymbolKind,
+    start_row: usize,
+    end_row: usize,
+) -> ScannedDefinition {
+    let end = &lines[end_row];
+    ScannedDefinition {
+        name: name.to_string(),
+        kind,
+        range: lines[start_row].start..end.start + end.text.len(),
+        start_row,
+        end_row,
+    }
+}
+
+/// Strip a leading keyword followed by whitespace
+fn strip_keyword<'a>(text: &'a str, keyword: &str) -> Option<&'a str> {
+    let rest = text.strip_prefix(keyword)?;
+    rest.starts_with(char::is_whitespace)
+        .then(|| rest.trim_start())
+}
+
+/// Whether a Make target or variable name is literal
+fn is_plain_make_name(name: &str) -> bool {
+    !name.is_empty() && !name.contains(['$', '%', '(', ')'])
+}
+
+/// Find Make targets, variables and `define` blocks
+///
+/// A rule's range covers its tab-indented recipe. Special targets such as
+/// `.PHONY` are skipped.
+fn scan_makefile(source: &str) -> Vec<ScannedDefinition> {
+    let lines = split_lines(source);
+    let mut definitions = Vec::new();
+    let mut row = 0;
+
+    while row < lines.len() {
+        let start_row = row;
+        let mut end_row = continuation_end(&lines, row);
+        row = end_row + 1;
+
+        let text = lines[start_row].text;
+        // Recipe lines belong to the rule above them
+        if text.starts_with('\t') {
+            continue;
+        }
+        let trimmed = text.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let statement = strip_keyword(trimmed, "export")
+            .or_else(|| strip_keyword(trimmed, "override"))
+            .unwrap_or(trimmed);
+
+        if let Some(rest) = strip_keyword(statement, "define") {
+            while end_row + 1 < lines.len() && lines[end_row].text.trim() != "endef" {
+                end_row += 1;
+            }
+            row = end_row + 1;
+
+            let name = rest
+                .split_whitespace()
+                .next()
+                .unwrap_or("")
+                .trim_end_matches([':', '?', '!', '+', '=']);
+            if is_plain_make_name(name) {
+                definitions.push(definition(
+                    &lines,
+                    name,
+                    SymbolKind::Variable,
+                    start_row,
+                    end_row,
+                ));
+            }
+            continue;
+        }
+
+        if let Some(eq) = statement.find('=') {
+            let before = &statement[..eq];
+            let name = before.trim_end_matches([':', '?', '!', '+']).trim();
+            // `target: VAR = value` is a rule setting a variable for its
+            // target, and `VAR += value` appends to a variable defined elsewhere
+            if !name.contains(':') {
+                if !before.ends_with('+')
+                    && is_plain_make_name(name)
+                    && !name.contains(char::is_whitespace)
+                {
+                    definitions.push(definition(
+                        &lines,
+                        name,
+                        SymbolKind::Variable,
+                        start_row,
+                        end_row,
+                    ));
+                }
+                continue;
+            }
+        }
+
+        let Some(colon) = statement.find(':') else {
+            continue;
+        };
+        while end_row + 1 < lines.len() && lines[end_row + 1].text.starts_with('\t') {
+            end_row = continuation_end(&lines, end_row + 1);
+        }
+        row = end_row + 1;
+
+        for target in statement[..colon].split_whitespace() {
+            if !target.starts_with('.') && is_plain_make_name(target) {
+                definitions.push(definition(
+                    &lines,
+                    target,
+                    SymbolKind::Target,
+                    start_row,
+                    end_row,
+                ));
+            }
+        }
+    }
+
+    definitions
+}
+
+/// Find Dockerfile build stages, `ARG`s and `ENV`s
+///
+/// A stage's range runs from its `FROM` to the last instruction before the
+/// next `FROM`.
+fn scan_dockerfile(source: &str) -> Vec<ScannedDefinition> {
+    let lines = split_lines(source);
+    let mut definitions = Vec::new();
+    // Name and first row of the current stage, and its last non-blank row
+    let mut stage: Option<(String, usize)> = None;
+    let mut stage_end = 0;
+    let mut row = 0;
+
+    while row < lines.len() {
+        let start_row = row;
+        let end_row = continuation_end(&lines, row);
+        row = end_row + 1;
+
+        let trimmed = lines[start_row].text.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        // Arguments of the instruction, joined across continuations
+        let instruction = lines[start_row..=end_row]
+            .iter()
+            .map(|line| line.text.trim().trim_end_matches('\\'))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mut words = instruction.split_whitespace();
+        let keyword = words.next().unwrap_or("").to_ascii_uppercase();
+
+        match keyword.as_str() {
+            "FROM" => {
+                if let Some((name, stage_start)) = stage.take() {
+                    definitions.push(definition(
+                        &lines,
+                        &name,
+                        SymbolKind::Stage,
+                        stage_start,
+                        stage_end,
+                    ));
+                }
+
+                let mut args = words.skip_while(|word| word.starts_with("--"));
+                let _image = args.next();
+                let is_named = args
+                    .next()
+                    .is_some_and(|word| word.eq_ignore_ascii_case("as"));
+                stage = args
+                    .next()
+                    .filter(|_| is_named)
+                    .map(|name| (name.to_string(), start_row));
+            }
+            "ARG" => {
+                for word in words {
+                    let name = word.split('=').next().unwrap_or("");
+                    definitions.push(definition(
+                        &lines,
+                        name,
+                        SymbolKind::Variable,
+                        start_row,
+                        end_row,
+                    ));
+                }
+            }
+            "ENV" => {
+                let words: Vec<&str> = words.collect();
+                // `ENV KEY=value ...`, or the legacy `ENV KEY value`
+                let names: Vec<&str> = if words.first().is_some_and(|word| word.contains('=')) {
+                    words
+                        .iter()
+                        .filter_map(|word| word.split_once('=').map(|(name, _)| name))
+                        .collect()
+                } else {
+                    words.first().copied().into_iter().collect()
+                };
+                for name in names {
+                    definitions.push(definition(
+                        &lines,
+                        name,
+                        SymbolKind::Variable,
+                        start_row,
+                        end_row,
+                    ));
+                }
+            }
+            _ => {}
+        }
+
+        stage_end = end_row;
+    }
+
+    if let Some((name, stage_start)) = stage {
+        definitions.push(definition(
+            &lines,
+            &name,
+            SymbolKind::Stage,
+            stage_start,
+            stage_end,
+        ));
+    }
+
+    definitions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(definitions: &[ScannedDefinition]) -> Vec<(&str, SymbolKind, usize, usize)> {
+        definitions
+            .iter()
+            .map(|d| (d.name.as_str(), d.kind, d.start_row, d.end_row))
+            .collect()
+    }
+
+    #[test]
+    fn test_scan_makefile() {
+        let source = "\
+CC := gcc
+export PREFIX ?= /usr/local
+CFLAGS += -O2
+SRCS = main.c \\
+       util.c
+
+.PHONY: all clean
+
+all build: app
+\t@echo done
+
+app: $(SRCS)
+\t$(CC) $(CFLAGS) -o $@ $^
+\tstrip $@
+
+%.o: %.c
+\t$(CC) -c $<
+
+debug: CFLAGS = -g
+
+define HELP
+Usage: make all
+endef
+";
+        let definitions = scan_makefile(source);
+        assert_eq!(
+            names(&definitions),
+            vec![
+                ("CC", SymbolKind::Variable, 0, 0),
+                ("PREFIX", SymbolKind::Variable, 1, 1),
+                ("SRCS", SymbolKind::Variable, 3, 4),
+                ("all", SymbolKind::Target, 8, 9),
+                ("build", SymbolKind::Target, 8, 9),
+                ("app", SymbolKind::Target, 11, 13),
+                ("debug", SymbolKind::Target, 18, 18),
+                ("HELP", SymbolKind::Variable, 20, 22),
+            ]
+        );
+
+        let app = &definitions[5];
+        assert!(source[app.range.clone()].starts_with("app: $(SRCS)"));
+        assert!(source[app.range.clone()].ends_with("strip $@"));
+    }
+
+    #[test]
+    fn test_scan_dockerfile() {
+        let source = "\
+# syntax=docker/dockerfile:1
+ARG NODE_VERSION=20
+FROM --platform=$BUILDPLATFORM node:${NODE_VERSION} AS build
+ENV NODE_ENV=production CI=true
+RUN npm ci && \\
+    npm run build
+
+from nginx:alpine as runtime
+ENV PORT 8080
+COPY --from=build /app/dist /usr/share/nginx/html
+
+FROM runtime
+";
+        let definitions = scan_dockerfile(source);
+        assert_eq!(
+            names(&definitions),
+            vec![
+                ("NODE_VERSION", SymbolKind::Variable, 1, 1),
+                ("NODE_ENV", SymbolKind::Variable, 3, 3),
+                ("CI", SymbolKind::Variable, 3, 3),
+                ("build", SymbolKind::Stage, 2, 5),
+                ("PORT", SymbolKind::Variable, 8, 8),
+                ("runtime", SymbolKind::Stage, 7, 9),
+            ]
+        );
+
+        let build = &definitions[3];
+        assert!(source[build.range.clone()].starts_with("FROM --platform"));
+        assert!(source[build.range.clone()].ends_with("npm run build"));
+    }
+}
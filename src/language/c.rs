@@ -0,0 +1,41 @@
+use crate::symbol::types::SymbolKind;
+
+use super::traits::SymbolKindMapping;
+
+define_language! {
+    name: CLanguage,
+    id: C,
+    display_name: "C",
+    extensions: ["c"],
+    tree_sitter_language: tree_sitter_c::LANGUAGE,
+    query_dir: "c",
+    mappings: C_DEFINITION_MAPPINGS,
+}
+
+/// Definition mappings for C
+const C_DEFINITION_MAPPINGS: &[SymbolKindMapping] = &[
+    SymbolKindMapping {
+        capture_name: "definition.function",
+        kind: SymbolKind::Function,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.struct",
+        kind: SymbolKind::Struct,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.enum",
+        kind: SymbolKind::Enum,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.type_alias",
+        kind: SymbolKind::TypeAlias,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.macro",
+        kind: SymbolKind::Macro,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.variable",
+        kind: SymbolKind::Variable,
+    },
+];
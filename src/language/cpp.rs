@@ -0,0 +1,59 @@
+use crate::symbol::types::SymbolKind;
+
+use super::traits::SymbolKindMapping;
+
+// `.h` headers are parsed as C++: its grammar also accepts C declarations,
+// while the C grammar cannot parse classes or namespaces.
+define_language! {
+    name: CppLanguage,
+    id: Cpp,
+    display_name: "Cpp",
+    extensions: ["cpp", "cc", "cxx", "hpp", "hh", "hxx", "h"],
+    tree_sitter_language: tree_sitter_cpp::LANGUAGE,
+    query_dir: "cpp",
+    mappings: CPP_DEFINITION_MAPPINGS,
+}
+
+/// Definition mappings for C++
+const CPP_DEFINITION_MAPPINGS: &[SymbolKindMapping] = &[
+    SymbolKindMapping {
+        capture_name: "definition.function",
+        kind: SymbolKind::Function,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.method",
+        kind: SymbolKind::Method,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.constructor",
+        kind: SymbolKind::Constructor,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.class",
+        kind: SymbolKind::Class,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.struct",
+        kind: SymbolKind::Struct,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.enum",
+        kind: SymbolKind::Enum,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.namespace",
+        kind: SymbolKind::Namespace,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.type_alias",
+        kind: SymbolKind::TypeAlias,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.macro",
+        kind: SymbolKind::Macro,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.variable",
+        kind: SymbolKind::Variable,
+    },
+];
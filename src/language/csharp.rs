@@ -0,0 +1,61 @@
+use crate::symbol::types::SymbolKind;
+
+use super::traits::SymbolKindMapping;
+
+define_language! {
+    name: CSharpLanguage,
+    id: CSharp,
+    display_name: "CSharp",
+    extensions: ["cs"],
+    tree_sitter_language: tree_sitter_c_sharp::LANGUAGE,
+    query_dir: "csharp",
+    mappings: CSHARP_DEFINITION_MAPPINGS,
+}
+
+/// Definition mappings for C#
+const CSHARP_DEFINITION_MAPPINGS: &[SymbolKindMapping] = &[
+    SymbolKindMapping {
+        capture_name: "definition.class",
+        kind: SymbolKind::Class,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.record",
+        kind: SymbolKind::Class,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.struct",
+        kind: SymbolKind::Struct,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.interface",
+        kind: SymbolKind::Interface,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.enum",
+        kind: SymbolKind::Enum,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.delegate",
+        kind: SymbolKind::TypeAlias,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.method",
+        kind: SymbolKind::Method,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.constructor",
+        kind: SymbolKind::Constructor,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.property",
+        kind: SymbolKind::Variable,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.field",
+        kind: SymbolKind::Variable,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.namespace",
+        kind: SymbolKind::Namespace,
+    },
+];
@@ -23,10 +23,11 @@ define_language! {
     tree_sitter_language: tree_sitter_html::LANGUAGE,
     query_dir: "html",
     mappings: HTML_DEFINITION_MAPPINGS,
+    injections: true,
 }
 
 /// Definition mappings for HTML
-const HTML_DEFINITION_MAPPINGS: &[SymbolKindMapping] = &[
+pub(super) const HTML_DEFINITION_MAPPINGS: &[SymbolKindMapping] = &[
     SymbolKindMapping {
         capture_name: "definition.element",
         kind: SymbolKind::HtmlElement,
@@ -6,7 +6,7 @@ define_language! {
     name: JavaScriptLanguage,
     id: JavaScript,
     display_name: "JavaScript",
-    extensions: ["js", "mjs", "cjs"],
+    extensions: ["js", "mjs", "cjs", "es6"],
     tree_sitter_language: tree_sitter_javascript::LANGUAGE,
     query_dir: "javascript",
     mappings: JAVASCRIPT_DEFINITION_MAPPINGS,
@@ -0,0 +1,19 @@
+use crate::symbol::types::SymbolKind;
+
+use super::traits::SymbolKindMapping;
+
+define_language! {
+    name: JsonLanguage,
+    id: Json,
+    display_name: "Json",
+    extensions: ["json", "jsonc"],
+    tree_sitter_language: tree_sitter_json::LANGUAGE,
+    query_dir: "json",
+    mappings: JSON_DEFINITION_MAPPINGS,
+}
+
+/// Definition mappings for JSON (keys are named by their dotted path)
+const JSON_DEFINITION_MAPPINGS: &[SymbolKindMapping] = &[SymbolKindMapping {
+    capture_name: "definition.key",
+    kind: SymbolKind::Key,
+}];
@@ -0,0 +1,57 @@
+use crate::symbol::types::SymbolKind;
+
+use super::traits::SymbolKindMapping;
+
+define_language! {
+    name: KotlinLanguage,
+    id: Kotlin,
+    display_name: "Kotlin",
+    extensions: ["kt", "kts"],
+    tree_sitter_language: tree_sitter_kotlin_ng::LANGUAGE,
+    query_dir: "kotlin",
+    mappings: KOTLIN_DEFINITION_MAPPINGS,
+}
+
+/// Definition mappings for Kotlin
+const KOTLIN_DEFINITION_MAPPINGS: &[SymbolKindMapping] = &[
+    SymbolKindMapping {
+        capture_name: "definition.class",
+        kind: SymbolKind::Class,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.interface",
+        kind: SymbolKind::Interface,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.enum",
+        kind: SymbolKind::Enum,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.object",
+        kind: SymbolKind::Object,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.type_alias",
+        kind: SymbolKind::TypeAlias,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.function",
+        kind: SymbolKind::Function,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.method",
+        kind: SymbolKind::Method,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.constructor",
+        kind: SymbolKind::Constructor,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.constant",
+        kind: SymbolKind::Const,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.property",
+        kind: SymbolKind::Variable,
+    },
+];
@@ -1,9 +1,14 @@
+use tree_sitter::{Language, Query};
+
 /// Macro to define a language implementation with reduced boilerplate.
 ///
 /// This macro generates:
-/// - A struct with `language`, `definitions_query`, and `usages_query` fields
-/// - A `new()` constructor that loads the tree-sitter grammar and queries
-/// - A `LanguageSupport` trait implementation
+/// - A struct holding the tree-sitter grammar
+/// - Constants holding the embedded query sources, exposed through `query_source`
+///   so workspace overrides can extend them
+/// - A `new()` constructor that loads the tree-sitter grammar
+/// - A `LanguageSupport` trait implementation whose queries are compiled on
+///   first use and shared by every instance (see [`BuiltinQueries`])
 ///
 /// # Usage
 ///
@@ -33,6 +38,35 @@
 /// }
 /// ```
 ///
+/// For languages recognised by exact file name (e.g., Ruby's `Rakefile`):
+/// ```ignore
+/// define_language! {
+///     name: RubyLanguage,
+///     id: Ruby,
+///     display_name: "Ruby",
+///     extensions: ["rb"],
+///     file_names: ["Rakefile", "Gemfile"],
+///     tree_sitter_language: tree_sitter_ruby::LANGUAGE,
+///     query_dir: "ruby",
+///     mappings: RUBY_DEFINITION_MAPPINGS,
+/// }
+/// ```
+///
+/// For languages that embed other languages (e.g., `<script>` in HTML), with
+/// an `injections.scm` query in the query directory:
+/// ```ignore
+/// define_language! {
+///     name: HtmlLanguage,
+///     id: Html,
+///     display_name: "Html",
+///     extensions: ["html", "htm"],
+///     tree_sitter_language: tree_sitter_html::LANGUAGE,
+///     query_dir: "html",
+///     mappings: HTML_DEFINITION_MAPPINGS,
+///     injections: true,
+/// }
+/// ```
+///
 /// For languages with a shared mappings constant (e.g., TypeScript and TSX):
 /// ```ignore
 /// define_language! {
@@ -47,12 +81,37 @@
 /// ```
 #[macro_export]
 macro_rules! define_language {
-    // Base case without uses_separate_docs
+    // Base case without file_names or uses_separate_docs
+    (
+        name: $name:ident,
+        id: $id:ident,
+        display_name: $display:literal,
+        extensions: [$($ext:literal),+ $(,)?],
+        tree_sitter_language: $ts_lang:expr,
+        query_dir: $query_dir:literal,
+        mappings: $mappings:expr $(,)?
+    ) => {
+        $crate::define_language! {
+            name: $name,
+            id: $id,
+            display_name: $display,
+            extensions: [$($ext),+],
+            file_names: [],
+            tree_sitter_language: $ts_lang,
+            query_dir: $query_dir,
+            mappings: $mappings,
+            uses_separate_docs: false,
+            injections: false,
+        }
+    };
+
+    // Case with file_names
     (
         name: $name:ident,
         id: $id:ident,
         display_name: $display:literal,
         extensions: [$($ext:literal),+ $(,)?],
+        file_names: [$($file_name:literal),* $(,)?],
         tree_sitter_language: $ts_lang:expr,
         query_dir: $query_dir:literal,
         mappings: $mappings:expr $(,)?
@@ -62,14 +121,16 @@ macro_rules! define_language {
             id: $id,
             display_name: $display,
             extensions: [$($ext),+],
+            file_names: [$($file_name),*],
             tree_sitter_language: $ts_lang,
             query_dir: $query_dir,
             mappings: $mappings,
             uses_separate_docs: false,
+            injections: false,
         }
     };
 
-    // Full case with uses_separate_docs
+    // Case with uses_separate_docs
     (
         name: $name:ident,
         id: $id:ident,
@@ -80,32 +141,99 @@ macro_rules! define_language {
         mappings: $mappings:expr,
         uses_separate_docs: $sep_docs:literal $(,)?
     ) => {
-        pub struct $name {
-            language: ::tree_sitter::Language,
-            definitions_query: ::tree_sitter::Query,
-            usages_query: ::tree_sitter::Query,
+        $crate::define_language! {
+            name: $name,
+            id: $id,
+            display_name: $display,
+            extensions: [$($ext),+],
+            file_names: [],
+            tree_sitter_language: $ts_lang,
+            query_dir: $query_dir,
+            mappings: $mappings,
+            uses_separate_docs: $sep_docs,
+            injections: false,
         }
+    };
 
-        impl $name {
-            pub fn new() -> ::anyhow::Result<Self> {
-                use ::anyhow::Context;
+    // Case with injections
+    (
+        name: $name:ident,
+        id: $id:ident,
+        display_name: $display:literal,
+        extensions: [$($ext:literal),+ $(,)?],
+        tree_sitter_language: $ts_lang:expr,
+        query_dir: $query_dir:literal,
+        mappings: $mappings:expr,
+        injections: true $(,)?
+    ) => {
+        $crate::define_language! {
+            name: $name,
+            id: $id,
+            display_name: $display,
+            extensions: [$($ext),+],
+            file_names: [],
+            tree_sitter_language: $ts_lang,
+            query_dir: $query_dir,
+            mappings: $mappings,
+            uses_separate_docs: false,
+            injections: true,
+        }
+    };
 
-                let language: ::tree_sitter::Language = $ts_lang.into();
+    // Injections query source, present only when the language declares one
+    (@injections_source $query_dir:literal, true) => {
+        Some(include_str!(concat!("../../queries/", $query_dir, "/injections.scm")))
+    };
+    (@injections_source $query_dir:literal, false) => {
+        None
+    };
 
-                let definitions_query_src =
-                    include_str!(concat!("../../queries/", $query_dir, "/definitions.scm"));
-                let usages_query_src =
-                    include_str!(concat!("../../queries/", $query_dir, "/usages.scm"));
+    // Full case
+    (
+        name: $name:ident,
+        id: $id:ident,
+        display_name: $display:literal,
+        extensions: [$($ext:literal),+ $(,)?],
+        file_names: [$($file_name:literal),* $(,)?],
+        tree_sitter_language: $ts_lang:expr,
+        query_dir: $query_dir:literal,
+        mappings: $mappings:expr,
+        uses_separate_docs: $sep_docs:literal,
+        injections: $injections:tt $(,)?
+    ) => {
+        pub struct $name {
+            language: ::tree_sitter::Language,
+        }
 
-                let definitions_query = ::tree_sitter::Query::new(&language, definitions_query_src)
-                    .context(concat!("Failed to parse ", $display, " definitions query"))?;
-                let usages_query = ::tree_sitter::Query::new(&language, usages_query_src)
-                    .context(concat!("Failed to parse ", $display, " usages query"))?;
+        impl $name {
+            const DEFINITIONS_QUERY: &'static str =
+                include_str!(concat!("../../queries/", $query_dir, "/definitions.scm"));
+            const USAGES_QUERY: &'static str =
+                include_str!(concat!("../../queries/", $query_dir, "/usages.scm"));
+            const COMMENTS_QUERY: &'static str =
+                include_str!(concat!("../../queries/", $query_dir, "/comments.scm"));
+            const INJECTIONS_QUERY: Option<&'static str> =
+                $crate::define_language!(@injections_source $query_dir, $injections);
 
+            pub fn new() -> ::anyhow::Result<Self> {
                 Ok(Self {
-                    language,
-                    definitions_query,
-                    usages_query,
+                    language: $ts_lang.into(),
+                })
+            }
+
+            /// Queries of the language, compiled once per process on first use
+            fn queries(&self) -> &'static $crate::language::macros::BuiltinQueries {
+                static QUERIES: ::std::sync::OnceLock<$crate::language::macros::BuiltinQueries> =
+                    ::std::sync::OnceLock::new();
+                QUERIES.get_or_init(|| {
+                    $crate::language::macros::BuiltinQueries::compile(
+                        &self.language,
+                        $display,
+                        Self::DEFINITIONS_QUERY,
+                        Self::USAGES_QUERY,
+                        Self::COMMENTS_QUERY,
+                        Self::INJECTIONS_QUERY,
+                    )
                 })
             }
         }
@@ -123,16 +251,39 @@ macro_rules! define_language {
                 &[$($ext),+]
             }
 
+            fn file_names(&self) -> &[&'static str] {
+                &[$($file_name),*]
+            }
+
             fn tree_sitter_language(&self) -> &::tree_sitter::Language {
                 &self.language
             }
 
             fn definitions_query(&self) -> &::tree_sitter::Query {
-                &self.definitions_query
+                &self.queries().definitions
             }
 
             fn usages_query(&self) -> &::tree_sitter::Query {
-                &self.usages_query
+                &self.queries().usages
+            }
+
+            fn comments_query(&self) -> &::tree_sitter::Query {
+                &self.queries().comments
+            }
+
+            fn injections_query(&self) -> Option<&::tree_sitter::Query> {
+                self.queries().injections.as_ref()
+            }
+
+            fn query_source(&self, kind: $crate::language::traits::QueryKind) -> Option<&str> {
+                use $crate::language::traits::QueryKind;
+
+                match kind {
+                    QueryKind::Definitions => Some(Self::DEFINITIONS_QUERY),
+                    QueryKind::Usages => Some(Self::USAGES_QUERY),
+                    QueryKind::Comments => Some(Self::COMMENTS_QUERY),
+                    QueryKind::Injections => Self::INJECTIONS_QUERY,
+                }
             }
 
             fn definition_mappings(&self) -> &[$crate::language::traits::SymbolKindMapping] {
@@ -145,3 +296,45 @@ macro_rules! define_language {
         }
     };
 }
+
+/// Compiled queries of a [`define_language!`] language
+///
+/// Compiling every language's queries takes seconds, and registries are
+/// created per workspace root, so each language compiles its queries on
+/// first use and keeps them in a process-wide static.
+pub struct BuiltinQueries {
+    pub definitions: Query,
+    pub usages: Query,
+    pub comments: Query,
+    pub injections: Option<Query>,
+}
+
+impl BuiltinQueries {
+    /// Compile a language's embedded queries
+    ///
+    /// # Panics
+    ///
+    /// Panics if an embedded query is malformed, which is a programming error
+    /// caught by the registry tests.
+    pub fn compile(
+        language: &Language,
+        display_name: &str,
+        definitions: &str,
+        usages: &str,
+        comments: &str,
+        injections: Option<&str>,
+    ) -> Self {
+        let compile = |kind: &str, source: &str| {
+            Query::new(language, source).unwrap_or_else(|e| {
+                panic!("Failed to parse {} {} query: {}", display_name, kind, e)
+            })
+        };
+
+        Self {
+            definitions: compile("definitions", definitions),
+            usages: compile("usages", usages),
+            comments: compile("comments", comments),
+            injections: injections.map(|source| compile("injections", source)),
+        }
+    }
+}
@@ -16,10 +16,11 @@ define_language! {
     name: MarkdownLanguage,
     id: Markdown,
     display_name: "Markdown",
-    extensions: ["md", "mdc"],
+    extensions: ["md", "mdc", "markdown"],
     tree_sitter_language: tree_sitter_md::LANGUAGE,
     query_dir: "markdown",
     mappings: MARKDOWN_DEFINITION_MAPPINGS,
+    injections: true,
 }
 
 /// Definition mappings for Markdown
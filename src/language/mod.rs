@@ -1,28 +1,60 @@
 #[macro_use]
 mod macros;
 
+mod bash;
+mod build;
+mod c;
+mod cpp;
+mod csharp;
 mod css;
 mod go;
 mod html;
 mod java;
 mod javascript;
+mod json;
+mod kotlin;
 mod markdown;
+mod overrides;
+mod php;
+mod plugin;
 mod python;
 mod registry;
+mod ruby;
 mod rust;
+mod scala;
+mod sfc;
 mod sql;
+mod swift;
+mod toml;
 mod traits;
 mod typescript;
+mod yaml;
 
+pub use bash::BashLanguage;
+pub use build::{DockerfileLanguage, MakefileLanguage};
+pub use c::CLanguage;
+pub use cpp::CppLanguage;
+pub use csharp::CSharpLanguage;
 pub use css::CssLanguage;
 pub use go::GoLanguage;
 pub use html::HtmlLanguage;
 pub use java::JavaLanguage;
 pub use javascript::{JavaScriptLanguage, JavaScriptReactLanguage};
+pub use json::JsonLanguage;
+pub use kotlin::KotlinLanguage;
 pub use markdown::MarkdownLanguage;
+pub use overrides::{QueryOverrideLanguage, QUERY_OVERRIDE_DIR};
+pub use php::PhpLanguage;
+pub use plugin::{PluginLanguage, PluginManifest};
 pub use python::PythonLanguage;
 pub use registry::LanguageRegistry;
+pub use ruby::RubyLanguage;
 pub use rust::RustLanguage;
+pub use scala::ScalaLanguage;
+pub use sfc::{AstroLanguage, SvelteLanguage, VueLanguage};
 pub use sql::SqlLanguage;
-pub use traits::{LanguageId, LanguageSupport, SymbolKindMapping};
+pub use swift::SwiftLanguage;
+pub use toml::TomlLanguage;
+pub use traits::{LanguageId, LanguageSupport, QueryKind, ScannedDefinition, SymbolKindMapping};
 pub use typescript::{TypeScriptLanguage, TypeScriptReactLanguage};
+pub use yaml::YamlLanguage;
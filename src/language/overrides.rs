@@ -0,0 +1,350 @@
+//! Workspace query overrides
+//!
+//! A workspace can change the queries of any registered language by adding
+//! files under `.codescope/queries/<language>/`, named like the built-in
+//! files (`definitions.scm`, `usages.scm`, `comments.scm`, `injections.scm`).
+//! A file whose first line is `; extends` is appended to the built-in query;
+//! any other file replaces it.
+//!
+//! ```text
+//! ; extends
+//! ; Express routes: app.get("/users", ...)
+//! (call_expression
+//!   function: (member_expression property: (property_identifier) @_method)
+//!   arguments: (arguments . (string (string_fragment) @name))
+//!   (#match? @_method "^(get|post|put|delete)$")) @definition.function
+//! ```
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context, Result};
+use tree_sitter::{Language, Query, QueryError, QueryErrorKind};
+
+use super::traits::{LanguageId, LanguageSupport, QueryKind, ScannedDefinition, SymbolKindMapping};
+
+/// Directory of query overrides, relative to the workspace root
+pub const QUERY_OVERRIDE_DIR: &str = ".codescope/queries";
+
+/// First line marking an override that extends the built-in query
+const EXTENDS_DIRECTIVE: &str = "; extends";
+
+/// A language whose queries are partly replaced by workspace files
+pub struct QueryOverrideLanguage {
+    base: Arc<dyn LanguageSupport>,
+    queries: HashMap<QueryKind, (String, Query)>,
+}
+
+impl QueryOverrideLanguage {
+    /// Apply the query files in a directory to a language
+    ///
+    /// Returns `None` if the directory has no query files. Every file is
+    /// validated, and the first invalid one fails the whole directory.
+    pub fn load(base: Arc<dyn LanguageSupport>, dir: &Path) -> Result<Option<Self>> {
+        let mut queries = HashMap::new();
+
+        for kind in QueryKind::ALL {
+            let path = dir.join(kind.file_name());
+            if !path.exists() {
+                continue;
+            }
+
+            let source = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let extends = source
+                .lines()
+                .find(|line| !line.trim().is_empty())
+                .map(str::trim)
+                == Some(EXTENDS_DIRECTIVE);
+            let prefix = match base.query_source(kind) {
+                Some(base_source) if extends => format!("{}\n", base_source),
+                _ => String::new(),
+            };
+
+            let combined = format!("{}{}", prefix, source);
+            let query = Query::new(base.tree_sitter_language(), &combined)
+                .map_err(|e| query_error(&path, &source, &prefix, &e))?;
+            queries.insert(kind, (combined, query));
+        }
+
+        if queries.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Self { base, queries }))
+    }
+
+    fn query(&self, kind: QueryKind) -> Option<&Query> {
+        self.queries.get(&kind).map(|(_, query)| query)
+    }
+}
+
+impl LanguageSupport for QueryOverrideLanguage {
+    fn id(&self) -> LanguageId {
+        self.base.id()
+    }
+
+    fn name(&self) -> &'static str {
+        self.base.name()
+    }
+
+    fn file_extensions(&self) -> &[&'static str] {
+        self.base.file_extensions()
+    }
+
+    fn file_names(&self) -> &[&'static str] {
+        self.base.file_names()
+    }
+
+    fn tree_sitter_language(&self) -> &Language {
+        self.base.tree_sitter_language()
+    }
+
+    fn definitions_query(&self) -> &Query {
+        self.query(QueryKind::Definitions)
+            .unwrap_or_else(|| self.base.definitions_query())
+    }
+
+    fn usages_query(&self) -> &Query {
+        self.query(QueryKind::Usages)
+            .unwrap_or_else(|| self.base.usages_query())
+    }
+
+    fn comments_query(&self) -> &Query {
+        self.query(QueryKind::Comments)
+            .unwrap_or_else(|| self.base.comments_query())
+    }
+
+    fn injections_query(&self) -> Option<&Query> {
+        self.query(QueryKind::Injections)
+            .or_else(|| self.base.injections_query())
+    }
+
+    fn query_source(&self, kind: QueryKind) -> Option<&str> {
+        match self.queries.get(&kind) {
+            Some((source, _)) => Some(source),
+            None => self.base.query_source(kind),
+        }
+    }
+
+    fn definition_mappings(&self) -> &[SymbolKindMapping] {
+        self.base.definition_mappings()
+    }
+
+    fn uses_separate_docs(&self) -> bool {
+        self.base.uses_separate_docs()
+    }
+
+    fn scan_definitions(&self, source: &str) -> Option<Vec<ScannedDefinition>> {
+        self.base.scan_definitions(source)
+    }
+}
+
+/// Read and compile a query file
+///
+/// Returns the source along with the query. Errors name the file, line and
+/// offending pattern.
+pub fn load_query_file(language: &Language, path: &Path) -> Result<(String, Query)> {
+    let source = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let query = Query::new(language, &source).map_err(|e| query_error(path, &source, "", &e))?;
+    Ok((source, query))
+}
+
+/// Describe a query error in terms of the file the user wrote
+///
+/// `prefix` is the built-in query text compiled ahead of `source`; errors
+/// past it are reported relative to `source`.
+fn query_error(path: &Path, source: &str, prefix: &str, error: &QueryError) -> anyhow::Error {
+    let description = match error.kind {
+        QueryErrorKind::NodeType => format!("invalid node type `{}`", error.message),
+        QueryErrorKind::Field => format!("invalid field name `{}`", error.message),
+        QueryErrorKind::Capture => format!("invalid capture name `{}`", error.message),
+        QueryErrorKind::Predicate => format!("invalid predicate: {}", error.message),
+        QueryErrorKind::Structure => "impossible pattern".to_string(),
+        QueryErrorKind::Syntax => "invalid syntax".to_string(),
+        QueryErrorKind::Language => error.message.clone(),
+    };
+
+    let Some(offset) = error.offset.checked_sub(prefix.len()) else {
+        // The built-in part failed, e.g. a capture it uses was redefined
+        return anyhow!(
+            "{}: conflicts with the built-in query: {}\n  in built-in pattern: {}",
+            path.display(),
+            description,
+            pattern_at(prefix, error.offset)
+        );
+    };
+
+    let line = source[..offset.min(source.len())].matches('\n').count() + 1;
+    let column = error.column + 1;
+    anyhow!(
+        "{}:{}:{}: {}\n  in pattern: {}",
+        path.display(),
+        line,
+        column,
+        description,
+        pattern_at(source, offset)
+    )
+}
+
+/// Find the top-level pattern containing a byte offset
+///
+/// Falls back to the line containing the offset when it is not inside a
+/// parenthesised or bracketed pattern.
+fn pattern_at(source: &str, offset: usize) -> &str {
+    let mut depth = 0usize;
+    let mut start = 0;
+    let mut chars = source.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            ';' => {
+                // Comment: skip to the end of the line
+                while chars.next_if(|&(_, c)| c != '\n').is_some() {}
+            }
+            '"' => {
+                // String: skip to the closing quote
+                while let Some((_, c)) = chars.next() {
+                    match c {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '(' | '[' => {
+                if depth == 0 {
+                    start = i;
+                }
+                depth += 1;
+            }
+            ')' | ']' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 && (start..=i).contains(&offset) {
+                    return source[start..=i].trim();
+                }
+            }
+            _ => {}
+        }
+        if depth == 0 && i >= offset {
+            break;
+        }
+    }
+
+    if depth > 0 && start <= offset {
+        // Unclosed pattern
+        return source[start..].trim();
+    }
+    let line_start = source[..offset.min(source.len())]
+        .rfind('\n')
+        .map_or(0, |i| i + 1);
+    let line_end = source[line_start..]
+        .find('\n')
+        .map_or(source.len(), |i| line_start + i);
+    source[line_start..line_end].trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::language::LanguageRegistry;
+    use tempfile::TempDir;
+
+    fn typescript() -> Arc<dyn LanguageSupport> {
+        LanguageRegistry::new()
+            .unwrap()
+            .get(LanguageId::TypeScript)
+            .unwrap()
+            .clone()
+    }
+
+    fn override_dir(file_name: &str, source: &str) -> TempDir {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join(file_name), source).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_replace_query() {
+        let dir = override_dir(
+            "definitions.scm",
+            "(class_declaration name: (type_identifier) @name) @definition.class\n",
+        );
+        let base = typescript();
+        let language = QueryOverrideLanguage::load(base.clone(), dir.path())
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(language.definitions_query().pattern_count(), 1);
+        assert_eq!(
+            language.usages_query().pattern_count(),
+            base.usages_query().pattern_count()
+        );
+    }
+
+    #[test]
+    fn test_extend_query() {
+        let dir = override_dir(
+            "definitions.scm",
+            "; extends\n(call_expression function: (identifier) @name) @definition.function\n",
+        );
+        let base = typescript();
+        let language = QueryOverrideLanguage::load(base.clone(), dir.path())
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            language.definitions_query().pattern_count(),
+            base.definitions_query().pattern_count() + 1
+        );
+        assert!(language
+            .query_source(QueryKind::Definitions)
+            .unwrap()
+            .starts_with(base.query_source(QueryKind::Definitions).unwrap()));
+    }
+
+    #[test]
+    fn test_invalid_extension_names_pattern() {
+        let dir = override_dir(
+            "definitions.scm",
+            "; extends\n\n; Routes\n(call_expression\n  function: (no_such_node) @name) @definition.function\n",
+        );
+
+        let error = QueryOverrideLanguage::load(typescript(), dir.path())
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(error.contains("definitions.scm:5:"), "{}", error);
+        assert!(
+            error.contains("invalid node type `no_such_node`"),
+            "{}",
+            error
+        );
+        assert!(
+            error.contains("in pattern: (call_expression\n  function: (no_such_node) @name)"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_no_query_files() {
+        let dir = TempDir::new().unwrap();
+        assert!(QueryOverrideLanguage::load(typescript(), dir.path())
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_pattern_at() {
+        let source = "; (not a pattern)\n(a \"(\" (b)) @x\n[(c) (d)]\n";
+
+        assert_eq!(pattern_at(source, 22), "(a \"(\" (b))");
+        assert_eq!(pattern_at(source, 36), "[(c) (d)]");
+        assert_eq!(pattern_at(source, 2), "; (not a pattern)");
+        assert_eq!(pattern_at("(a (b", 4), "(a (b");
+    }
+}
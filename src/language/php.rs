@@ -0,0 +1,61 @@
+use crate::symbol::types::SymbolKind;
+
+use super::traits::SymbolKindMapping;
+
+define_language! {
+    name: PhpLanguage,
+    id: Php,
+    display_name: "Php",
+    extensions: ["php"],
+    tree_sitter_language: tree_sitter_php::LANGUAGE_PHP,
+    query_dir: "php",
+    mappings: PHP_DEFINITION_MAPPINGS,
+}
+
+/// Definition mappings for PHP
+const PHP_DEFINITION_MAPPINGS: &[SymbolKindMapping] = &[
+    SymbolKindMapping {
+        capture_name: "definition.namespace",
+        kind: SymbolKind::Namespace,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.class",
+        kind: SymbolKind::Class,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.interface",
+        kind: SymbolKind::Interface,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.trait",
+        kind: SymbolKind::Trait,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.enum",
+        kind: SymbolKind::Enum,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.constructor",
+        kind: SymbolKind::Constructor,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.static_method",
+        kind: SymbolKind::StaticMethod,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.method",
+        kind: SymbolKind::Method,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.function",
+        kind: SymbolKind::Function,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.constant",
+        kind: SymbolKind::Const,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.property",
+        kind: SymbolKind::Variable,
+    },
+];
@@ -0,0 +1,442 @@
+//! Runtime-loaded language plugins
+//!
+//! Languages without a built-in grammar can be added from the directory
+//! named by `language_plugins` in `.mcp.json`, if the server allows plugins
+//! (see [`crate::server::workspace::ALLOW_PLUGINS_ENV`]). Each subdirectory
+//! holds one language:
+//!
+//! ```text
+//! .codescope/languages/
+//!   mydsl/
+//!     language.json           # name, extensions, grammar library, mappings
+//!     libtree-sitter-mydsl.so # compiled tree-sitter grammar
+//!     definitions.scm
+//!     usages.scm
+//!     comments.scm            # optional
+//!     injections.scm          # optional
+//! ```
+//!
+//! `language.json` maps definition captures to symbol kinds:
+//!
+//! ```json
+//! {
+//!   "name": "mydsl",
+//!   "extensions": ["dsl"],
+//!   "library": "libtree-sitter-mydsl.so",
+//!   "mappings": { "definition.rule": "Function", "definition.type": "Struct" }
+//! }
+//! ```
+//!
+//! The grammar is looked up by its `symbol`, `tree_sitter_<name>` by default.
+//! Plugin libraries stay loaded for the life of the process, since parsed
+//! trees point into them. Registries are rebuilt whenever the workspace
+//! roots change, so each library is opened once per process and reused.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use tree_sitter::{Language, Query};
+
+use super::overrides::load_query_file;
+use super::traits::{LanguageId, LanguageSupport, QueryKind, SymbolKindMapping};
+use crate::symbol::types::SymbolKind;
+
+/// Name of the manifest file in a plugin directory
+pub const MANIFEST_FILE: &str = "language.json";
+
+/// Contents of a plugin's `language.json`
+#[derive(Debug, Deserialize)]
+pub struct PluginManifest {
+    /// Language name, used as the language filter value (e.g., "mydsl")
+    pub name: String,
+
+    /// File extensions without the leading dot
+    #[serde(default)]
+    pub extensions: Vec<String>,
+
+    /// Exact file names recognised regardless of extension
+    #[serde(default)]
+    pub file_names: Vec<String>,
+
+    /// Grammar shared library, relative to the plugin directory
+    pub library: String,
+
+    /// Exported grammar function (default: `tree_sitter_<name>`)
+    #[serde(default)]
+    pub symbol: Option<String>,
+
+    /// Definition capture names mapped to symbol kinds
+    pub mappings: HashMap<String, SymbolKind>,
+}
+
+impl PluginManifest {
+    /// Read and parse the manifest in a plugin directory
+    pub fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join(MANIFEST_FILE);
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    /// Name of the function exported by the grammar library
+    fn grammar_symbol(&self) -> String {
+        self.symbol
+            .clone()
+            .unwrap_or_else(|| format!("tree_sitter_{}", self.name.replace('-', "_")))
+    }
+}
+
+/// A language loaded from a plugin directory
+pub struct PluginLanguage {
+    name: &'static str,
+    extensions: Vec<&'static str>,
+    file_names: Vec<&'static str>,
+    language: Language,
+    definitions_query: Query,
+    usages_query: Query,
+    comments_query: Query,
+    injections_query: Option<Query>,
+    sources: HashMap<QueryKind, String>,
+    mappings: Vec<SymbolKindMapping>,
+}
+
+impl PluginLanguage {
+    /// Load the plugin in a directory
+    pub fn load(dir: &Path) -> Result<Self> {
+        let manifest = PluginManifest::load(dir)?;
+        let library = dir.join(&manifest.library);
+        let language = cached_grammar(&library, &manifest.grammar_symbol())?;
+        Self::from_manifest(dir, manifest, language)
+    }
+
+    /// Build a plugin from its manifest and an already loaded grammar
+    ///
+    /// Queries are read from `dir`. Names are interned, since the registry
+    /// hands out `&'static str` names and plugins live as long as the process.
+    pub fn from_manifest(dir: &Path, manifest: PluginManifest, language: Language) -> Result<Self> {
+        if manifest.name.is_empty() {
+            bail!(
+                "{}: name must not be empty",
+                dir.join(MANIFEST_FILE).display()
+            );
+        }
+        let name = intern(manifest.name);
+
+        let mut sources = HashMap::new();
+        let mut load = |kind: QueryKind| -> Result<Option<Query>> {
+            let path = dir.join(kind.file_name());
+            if !path.exists() {
+                return Ok(None);
+            }
+            let (source, query) = load_query_file(&language, &path)?;
+            sources.insert(kind, source);
+            Ok(Some(query))
+        };
+
+        let definitions_query = load(QueryKind::Definitions)?
+            .with_context(|| format!("{}: definitions.scm is required", dir.display()))?;
+        let usages_query = load(QueryKind::Usages)?
+            .with_context(|| format!("{}: usages.scm is required", dir.display()))?;
+        let comments_query = match load(QueryKind::Comments)? {
+            Some(query) => query,
+            None => Query::new(&language, "")
+                .with_context(|| format!("Failed to create {} comments query", name))?,
+        };
+        let injections_query = load(QueryKind::Injections)?;
+
+        let mut mappings: Vec<_> = manifest
+            .mappings
+            .into_iter()
+            .map(|(capture_name, kind)| SymbolKindMapping {
+                capture_name: intern(capture_name),
+                kind,
+            })
+            .collect();
+        mappings.sort_by_key(|mapping| mapping.capture_name);
+
+        Ok(Self {
+            name,
+            extensions: manifest.extensions.into_iter().map(intern).collect(),
+            file_names: manifest.file_names.into_iter().map(intern).collect(),
+            language,
+            definitions_query,
+            usages_query,
+            comments_query,
+            injections_query,
+            sources,
+            mappings,
+        })
+    }
+}
+
+impl LanguageSupport for PluginLanguage {
+    fn id(&self) -> LanguageId {
+        LanguageId::Plugin(self.name)
+    }
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn file_extensions(&self) -> &[&'static str] {
+        &self.extensions
+    }
+
+    fn file_names(&self) -> &[&'static str] {
+        &self.file_names
+    }
+
+    fn tree_sitter_language(&self) -> &Language {
+        &self.language
+    }
+
+    fn definitions_query(&self) -> &Query {
+        &self.definitions_query
+    }
+
+    fn usages_query(&self) -> &Query {
+        &self.usages_query
+    }
+
+    fn comments_query(&self) -> &Query {
+        &self.comments_query
+    }
+
+    fn injections_query(&self) -> Option<&Query> {
+        self.injections_query.as_ref()
+    }
+
+    fn query_source(&self, kind: QueryKind) -> Option<&str> {
+        self.sources.get(&kind).map(String::as_str)
+    }
+
+    fn definition_mappings(&self) -> &[SymbolKindMapping] {
+        &self.mappings
+    }
+}
+
+/// Load a tree-sitter grammar, reusing the one already loaded from the same library
+///
+/// Libraries are keyed by their canonical path, so each one is opened once
+/// however many times the registries are rebuilt.
+fn cached_grammar(library: &Path, symbol: &str) -> Result<Language> {
+    static GRAMMARS: OnceLock<Mutex<HashMap<(PathBuf, String), Language>>> = OnceLock::new();
+
+    let canonical = library
+        .canonicalize()
+        .with_context(|| format!("Failed to load grammar library {}", library.display()))?;
+    let key = (canonical, symbol.to_string());
+
+    let mut grammars = GRAMMARS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    if let Some(language) = grammars.get(&key) {
+        return Ok(language.clone());
+    }
+    let language = load_grammar(&key.0, symbol)?;
+    grammars.insert(key, language.clone());
+    Ok(language)
+}
+
+/// Load a tree-sitter grammar from a shared library
+#[cfg(unix)]
+fn load_grammar(library: &Path, symbol: &str) -> Result<Language> {
+    use std::ffi::{c_void, CStr, CString};
+    use std::os::unix::ffi::OsStrExt;
+
+    let library_path = CString::new(library.as_os_str().as_bytes())
+        .with_context(|| format!("Invalid library path {}", library.display()))?;
+    let symbol_name =
+        CString::new(symbol).with_context(|| format!("Invalid grammar symbol {}", symbol))?;
+
+    let last_error = || {
+        // SAFETY: dlerror returns null or a valid C string owned by libc
+        let message = unsafe { libc::dlerror() };
+        if message.is_null() {
+            "unknown error".to_string()
+        } else {
+            unsafe { CStr::from_ptr(message) }
+                .to_string_lossy()
+                .into_owned()
+        }
+    };
+
+    // SAFETY: the path is a valid C string. The handle is never closed, so
+    // the grammar stays valid for the life of the process.
+    let handle = unsafe { libc::dlopen(library_path.as_ptr(), libc::RTLD_NOW | libc::RTLD_LOCAL) };
+    if handle.is_null() {
+        bail!(
+            "Failed to load grammar library {}: {}",
+            library.display(),
+            last_error()
+        );
+    }
+
+    // SAFETY: the handle is valid and the symbol is a valid C string
+    let function = unsafe { libc::dlsym(handle, symbol_name.as_ptr()) };
+    if function.is_null() {
+        bail!(
+            "Grammar library {} does not export {}",
+            library.display(),
+            symbol
+        );
+    }
+
+    // SAFETY: tree-sitter grammars export `const TSLanguage *tree_sitter_<name>(void)`
+    let language = unsafe {
+        let function =
+            std::mem::transmute::<*mut c_void, unsafe extern "C" fn() -> *const ()>(function);
+        Language::new(tree_sitter_language::LanguageFn::from_raw(function))
+    };
+
+    let version = language.abi_version();
+    if !(tree_sitter::MIN_COMPATIBLE_LANGUAGE_VERSION..=tree_sitter::LANGUAGE_VERSION)
+        .contains(&version)
+    {
+        bail!(
+            "Grammar library {} has ABI version {}, expected {} to {}",
+            library.display(),
+            version,
+            tree_sitter::MIN_COMPATIBLE_LANGUAGE_VERSION,
+            tree_sitter::LANGUAGE_VERSION
+        );
+    }
+
+    Ok(language)
+}
+
+/// Load a tree-sitter grammar from a shared library
+#[cfg(not(unix))]
+fn load_grammar(library: &Path, _symbol: &str) -> Result<Language> {
+    bail!(
+        "Cannot load {}: language plugins are only supported on Unix",
+        library.display()
+    )
+}
+
+/// Get a `'static` copy of a plugin string
+///
+/// Each distinct string is leaked once, so reloading plugins does not grow
+/// memory.
+fn intern(value: String) -> &'static str {
+    static INTERNED: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+
+    let mut interned = INTERNED
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    if let Some(existing) = interned.get(value.as_str()) {
+        return existing;
+    }
+    let leaked: &'static str = Box::leak(value.into_boxed_str());
+    interned.insert(leaked);
+    leaked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Plugin directory with a manifest and queries for the JSON grammar
+    fn plugin_dir(manifest: &str, definitions: &str) -> TempDir {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join(MANIFEST_FILE), manifest).unwrap();
+        std::fs::write(dir.path().join("definitions.scm"), definitions).unwrap();
+        std::fs::write(dir.path().join("usages.scm"), "(string_content) @usage").unwrap();
+        dir
+    }
+
+    const MANIFEST: &str = r#"{
+        "name": "settings",
+        "extensions": ["settings"],
+        "library": "libtree-sitter-settings.so",
+        "mappings": { "definition.key": "Key" }
+    }"#;
+
+    #[test]
+    fn test_from_manifest() {
+        let dir = plugin_dir(
+            MANIFEST,
+            "(pair key: (string (string_content) @name)) @definition.key",
+        );
+        let manifest = PluginManifest::load(dir.path()).unwrap();
+        assert_eq!(manifest.grammar_symbol(), "tree_sitter_settings");
+
+        let plugin =
+            PluginLanguage::from_manifest(dir.path(), manifest, tree_sitter_json::LANGUAGE.into())
+                .unwrap();
+
+        assert_eq!(plugin.id(), LanguageId::Plugin("settings"));
+        assert_eq!(plugin.file_extensions(), ["settings"]);
+        assert_eq!(plugin.definition_mappings()[0].kind, SymbolKind::Key);
+        assert_eq!(plugin.comments_query().pattern_count(), 0);
+        assert!(plugin.injections_query().is_none());
+    }
+
+    #[test]
+    fn test_reloaded_names_are_interned() {
+        let dir = plugin_dir(
+            MANIFEST,
+            "(pair key: (string (string_content) @name)) @definition.key",
+        );
+        let load = || {
+            let manifest = PluginManifest::load(dir.path()).unwrap();
+            PluginLanguage::from_manifest(dir.path(), manifest, tree_sitter_json::LANGUAGE.into())
+                .unwrap()
+        };
+
+        let (first, second) = (load(), load());
+        assert!(std::ptr::eq(first.name(), second.name()));
+        assert!(std::ptr::eq(
+            first.definition_mappings()[0].capture_name,
+            second.definition_mappings()[0].capture_name
+        ));
+    }
+
+    #[test]
+    fn test_invalid_query_names_file() {
+        let dir = plugin_dir(MANIFEST, "(no_such_node) @definition.key");
+        let manifest = PluginManifest::load(dir.path()).unwrap();
+
+        let error =
+            PluginLanguage::from_manifest(dir.path(), manifest, tree_sitter_json::LANGUAGE.into())
+                .err()
+                .unwrap();
+        let message = format!("{:#}", error);
+        assert!(message.contains("definitions.scm:1:2"), "{}", message);
+        assert!(
+            message.contains("in pattern: (no_such_node)"),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn test_missing_library() {
+        let dir = plugin_dir(MANIFEST, "");
+
+        let error = PluginLanguage::load(dir.path()).err().unwrap();
+        assert!(
+            error.to_string().contains("libtree-sitter-settings.so"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_invalid_mapping_kind() {
+        let dir = plugin_dir(
+            r#"{"name": "x", "library": "x.so", "mappings": {"definition.x": "Widget"}}"#,
+            "",
+        );
+
+        let error = PluginManifest::load(dir.path()).err().unwrap();
+        assert!(format!("{:#}", error).contains("Widget"));
+    }
+}
@@ -1,28 +1,72 @@
 use std::collections::HashMap;
-use std::path::Path;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use anyhow::{Context, Result};
 
+use super::bash::BashLanguage;
+use super::build::{DockerfileLanguage, MakefileLanguage};
+use super::c::CLanguage;
+use super::cpp::CppLanguage;
+use super::csharp::CSharpLanguage;
 use super::css::CssLanguage;
 use super::go::GoLanguage;
 use super::html::HtmlLanguage;
 use super::java::JavaLanguage;
 use super::javascript::{JavaScriptLanguage, JavaScriptReactLanguage};
+use super::json::JsonLanguage;
+use super::kotlin::KotlinLanguage;
 use super::markdown::MarkdownLanguage;
+use super::overrides::{QueryOverrideLanguage, QUERY_OVERRIDE_DIR};
+use super::php::PhpLanguage;
+use super::plugin::PluginLanguage;
 use super::python::PythonLanguage;
+use super::ruby::RubyLanguage;
 use super::rust::RustLanguage;
+use super::scala::ScalaLanguage;
+use super::sfc::{AstroLanguage, SvelteLanguage, VueLanguage};
 use super::sql::SqlLanguage;
+use super::swift::SwiftLanguage;
+use super::toml::TomlLanguage;
 use super::traits::{LanguageId, LanguageSupport};
 use super::typescript::{TypeScriptLanguage, TypeScriptReactLanguage};
+use super::yaml::YamlLanguage;
+use crate::config::{CodeScopeConfig, FileTypeConfig};
+use crate::pipeline::parse_language_id;
+
+/// Interpreters recognised in the `#!` line of extensionless scripts
+const DEFAULT_INTERPRETERS: &[(&str, LanguageId)] = &[
+    ("python", LanguageId::Python),
+    ("node", LanguageId::JavaScript),
+    ("nodejs", LanguageId::JavaScript),
+    ("deno", LanguageId::TypeScript),
+    ("ts-node", LanguageId::TypeScript),
+    ("bash", LanguageId::Bash),
+    ("sh", LanguageId::Bash),
+    ("zsh", LanguageId::Bash),
+    ("dash", LanguageId::Bash),
+    ("ksh", LanguageId::Bash),
+    ("ruby", LanguageId::Ruby),
+    ("php", LanguageId::Php),
+    ("swift", LanguageId::Swift),
+];
 
 /// Registry for language support implementations
 ///
 /// Manages all supported languages and provides methods to look up
-/// the appropriate language handler based on file extension.
+/// the appropriate language handler based on file name or extension.
 pub struct LanguageRegistry {
     languages: HashMap<LanguageId, Arc<dyn LanguageSupport>>,
-    extension_map: HashMap<&'static str, LanguageId>,
+    extension_map: HashMap<String, LanguageId>,
+    file_name_map: HashMap<String, LanguageId>,
+    /// Glob patterns from the configuration, checked in order
+    glob_map: Vec<(glob::Pattern, LanguageId)>,
+    interpreter_map: HashMap<String, LanguageId>,
+    /// Root that relative glob patterns are matched against
+    workspace_root: Option<PathBuf>,
+    /// Query override directories that were skipped, with the reason
+    override_errors: Vec<String>,
 }
 
 impl LanguageRegistry {
@@ -31,6 +75,14 @@ impl LanguageRegistry {
         let mut registry = Self {
             languages: HashMap::new(),
             extension_map: HashMap::new(),
+            file_name_map: HashMap::new(),
+            glob_map: Vec::new(),
+            interpreter_map: DEFAULT_INTERPRETERS
+                .iter()
+                .map(|(name, id)| (name.to_string(), *id))
+                .collect(),
+            workspace_root: None,
+            override_errors: Vec::new(),
         };
 
         // Register default languages
@@ -70,17 +122,233 @@ impl LanguageRegistry {
         registry.register(Arc::new(
             SqlLanguage::new().context("Failed to create SQL language")?,
         ))?;
+        registry.register(Arc::new(
+            CLanguage::new().context("Failed to create C language")?,
+        ))?;
+        registry.register(Arc::new(
+            CppLanguage::new().context("Failed to create C++ language")?,
+        ))?;
+        registry.register(Arc::new(
+            CSharpLanguage::new().context("Failed to create C# language")?,
+        ))?;
+        registry.register(Arc::new(
+            RubyLanguage::new().context("Failed to create Ruby language")?,
+        ))?;
+        registry.register(Arc::new(
+            PhpLanguage::new().context("Failed to create PHP language")?,
+        ))?;
+        registry.register(Arc::new(
+            SwiftLanguage::new().context("Failed to create Swift language")?,
+        ))?;
+        registry.register(Arc::new(
+            KotlinLanguage::new().context("Failed to create Kotlin language")?,
+        ))?;
+        registry.register(Arc::new(
+            ScalaLanguage::new().context("Failed to create Scala language")?,
+        ))?;
+        registry.register(Arc::new(
+            BashLanguage::new().context("Failed to create Bash language")?,
+        ))?;
+        registry.register(Arc::new(
+            JsonLanguage::new().context("Failed to create JSON language")?,
+        ))?;
+        registry.register(Arc::new(
+            YamlLanguage::new().context("Failed to create YAML language")?,
+        ))?;
+        registry.register(Arc::new(
+            TomlLanguage::new().context("Failed to create TOML language")?,
+        ))?;
+        registry.register(Arc::new(
+            VueLanguage::new().context("Failed to create Vue language")?,
+        ))?;
+        registry.register(Arc::new(
+            SvelteLanguage::new().context("Failed to create Svelte language")?,
+        ))?;
+        registry.register(Arc::new(
+            AstroLanguage::new().context("Failed to create Astro language")?,
+        ))?;
+        registry.register(Arc::new(
+            DockerfileLanguage::new().context("Failed to create Dockerfile language")?,
+        ))?;
+        registry.register(Arc::new(
+            MakefileLanguage::new().context("Failed to create Makefile language")?,
+        ))?;
+
+        Ok(registry)
+    }
 
+    /// Create a registry for a workspace
+    ///
+    /// Adds the language plugins from the `language_plugins` directory in the
+    /// configuration, resolved against the workspace root, then applies the
+    /// workspace's query overrides and file type mappings. An unreadable
+    /// plugin directory is logged and skipped.
+    pub fn for_workspace(workspace_root: &Path, config: &CodeScopeConfig) -> Result<Self> {
+        let mut registry = Self::new()?;
+        if let Some(dir) = &config.language_plugins {
+            if let Err(e) = registry.load_plugins(&workspace_root.join(dir)) {
+                tracing::warn!("Skipping language plugins: {:#}", e);
+            }
+        }
+        registry.load_query_overrides(&workspace_root.join(QUERY_OVERRIDE_DIR))?;
+        registry.add_file_types(workspace_root, &config.file_types);
         Ok(registry)
     }
 
+    /// Add file type mappings from the configuration
+    ///
+    /// Configured extensions replace built-in ones. Mappings naming an unknown
+    /// language, and invalid glob patterns, are logged and skipped.
+    pub fn add_file_types(&mut self, workspace_root: &Path, file_types: &FileTypeConfig) {
+        let resolve = |registry: &Self, name: &str, mapping: &str| {
+            let id = registry.language_id(name);
+            if id.is_none() {
+                tracing::warn!(
+                    "Ignoring file type mapping '{}': unknown language '{}'",
+                    mapping,
+                    name
+                );
+            }
+            id
+        };
+
+        for (extension, language) in &file_types.extensions {
+            let extension = extension.trim_start_matches('.');
+            if let Some(id) = resolve(self, language, extension) {
+                self.extension_map.insert(extension.to_string(), id);
+            }
+        }
+
+        for mapping in &file_types.globs {
+            let Some(id) = resolve(self, &mapping.language, &mapping.pattern) else {
+                continue;
+            };
+            match glob::Pattern::new(&mapping.pattern) {
+                Ok(pattern) => self.glob_map.push((pattern, id)),
+                Err(e) => tracing::warn!(
+                    "Ignoring file type mapping '{}': invalid glob pattern: {}",
+                    mapping.pattern,
+                    e
+                ),
+            }
+        }
+
+        for (interpreter, language) in &file_types.interpreters {
+            if let Some(id) = resolve(self, language, interpreter) {
+                self.interpreter_map.insert(interpreter.clone(), id);
+            }
+        }
+
+        self.workspace_root = Some(workspace_root.to_path_buf());
+    }
+
+    /// Load every language plugin in a directory
+    ///
+    /// Each subdirectory is one plugin. Plugins that fail to load are logged
+    /// and skipped, so one broken plugin does not disable the others.
+    /// Returns the number of plugins loaded.
+    pub fn load_plugins(&mut self, dir: &Path) -> Result<usize> {
+        let entries = std::fs::read_dir(dir).with_context(|| {
+            format!("Failed to read language plugin directory {}", dir.display())
+        })?;
+
+        let mut plugin_dirs: Vec<_> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect();
+        plugin_dirs.sort();
+
+        let mut loaded = 0;
+        for plugin_dir in plugin_dirs {
+            match PluginLanguage::load(&plugin_dir) {
+                Ok(plugin) => {
+                    tracing::info!(
+                        "Loaded language plugin '{}' from {}",
+                        plugin.name(),
+                        plugin_dir.display()
+                    );
+                    self.register(Arc::new(plugin))?;
+                    loaded += 1;
+                }
+                Err(e) => {
+                    tracing::warn!("Skipping language plugin {}: {:#}", plugin_dir.display(), e);
+                }
+            }
+        }
+        Ok(loaded)
+    }
+
+    /// Apply query overrides from a directory with one subdirectory per language
+    ///
+    /// Subdirectories are named like the language filter (e.g., `typescript`,
+    /// `py`, or a plugin name). A missing directory is not an error. Invalid
+    /// overrides are skipped, leaving the language's queries as they were,
+    /// and recorded in [`Self::override_errors`]. Returns the number of
+    /// languages overridden.
+    pub fn load_query_overrides(&mut self, dir: &Path) -> Result<usize> {
+        if !dir.is_dir() {
+            return Ok(0);
+        }
+        let entries = std::fs::read_dir(dir).with_context(|| {
+            format!("Failed to read query override directory {}", dir.display())
+        })?;
+
+        let mut override_dirs: Vec<_> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect();
+        override_dirs.sort();
+
+        let mut overridden = 0;
+        for override_dir in override_dirs {
+            let name = override_dir
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let Some(base) = self.get_by_name(&name).cloned() else {
+                let error = format!("{}: unknown language '{}'", override_dir.display(), name);
+                tracing::warn!("Skipping query overrides in {}", error);
+                self.override_errors.push(error);
+                continue;
+            };
+
+            match QueryOverrideLanguage::load(base, &override_dir) {
+                Ok(Some(language)) => {
+                    tracing::info!(
+                        "Loaded query overrides for {} from {}",
+                        language.name(),
+                        override_dir.display()
+                    );
+                    self.register(Arc::new(language))?;
+                    overridden += 1;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    tracing::warn!("Skipping invalid query overrides: {:#}", e);
+                    self.override_errors.push(format!("{:#}", e));
+                }
+            }
+        }
+        Ok(overridden)
+    }
+
+    /// Errors from query overrides that were skipped
+    pub fn override_errors(&self) -> &[String] {
+        &self.override_errors
+    }
+
     /// Register a new language
     pub fn register(&mut self, language: Arc<dyn LanguageSupport>) -> Result<()> {
         let id = language.id();
 
         // Register extension mappings
         for ext in language.file_extensions() {
-            self.extension_map.insert(ext, id);
+            self.extension_map.insert(ext.to_string(), id);
+        }
+        for file_name in language.file_names() {
+            self.file_name_map.insert(file_name.to_string(), id);
         }
 
         self.languages.insert(id, language);
@@ -92,6 +360,20 @@ impl LanguageRegistry {
         self.languages.get(&id)
     }
 
+    /// Resolve a language name (e.g., "typescript", "ts" or a plugin name)
+    pub fn language_id(&self, name: &str) -> Option<LanguageId> {
+        parse_language_id(name).or_else(|| {
+            self.languages.keys().copied().find(
+                |id| matches!(id, LanguageId::Plugin(plugin) if plugin.eq_ignore_ascii_case(name)),
+            )
+        })
+    }
+
+    /// Get a language by name
+    pub fn get_by_name(&self, name: &str) -> Option<&Arc<dyn LanguageSupport>> {
+        self.get(self.language_id(name)?)
+    }
+
     /// Get a language by file extension
     pub fn get_by_extension(&self, extension: &str) -> Option<&Arc<dyn LanguageSupport>> {
         let id = self.extension_map.get(extension)?;
@@ -99,9 +381,45 @@ impl LanguageRegistry {
     }
 
     /// Get a language for a given file path
+    ///
+    /// Checks, in order: configured glob patterns, exact file names (e.g.,
+    /// `Rakefile`), extensions (the longest match, so `blade.php` can be
+    /// mapped apart from `php`), and for files without an extension, the
+    /// interpreter in a `#!` line, which is read from disk.
     pub fn get_for_path(&self, path: &Path) -> Option<&Arc<dyn LanguageSupport>> {
-        let extension = path.extension()?.to_str()?;
-        self.get_by_extension(extension)
+        let relative_path = self
+            .workspace_root
+            .as_deref()
+            .and_then(|root| path.strip_prefix(root).ok())
+            .unwrap_or(path);
+        if let Some((_, id)) = self
+            .glob_map
+            .iter()
+            .find(|(pattern, _)| pattern.matches_path(relative_path))
+        {
+            return self.languages.get(id);
+        }
+
+        let file_name = path.file_name()?.to_str()?;
+        if let Some(id) = self.file_name_map.get(file_name) {
+            return self.languages.get(id);
+        }
+
+        let mut extensions = file_name
+            .char_indices()
+            .filter(|&(i, c)| c == '.' && i > 0)
+            .map(|(i, _)| &file_name[i + 1..])
+            .peekable();
+        if extensions.peek().is_none() {
+            let interpreter = shebang_interpreter(path)?;
+            let id = self.interpreter_map.get(&interpreter).or_else(|| {
+                // python3.12 -> python
+                let base = interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+                self.interpreter_map.get(base)
+            })?;
+            return self.languages.get(id);
+        }
+        extensions.find_map(|extension| self.get_by_extension(extension))
     }
 
     /// Check if a file is supported by any registered language
@@ -110,8 +428,10 @@ impl LanguageRegistry {
     }
 
     /// Get all supported file extensions
-    pub fn supported_extensions(&self) -> Vec<&'static str> {
-        self.extension_map.keys().copied().collect()
+    pub fn supported_extensions(&self) -> Vec<&str> {
+        let mut extensions: Vec<_> = self.extension_map.keys().map(String::as_str).collect();
+        extensions.sort_unstable();
+        extensions
     }
 
     /// Get all registered language IDs
@@ -120,10 +440,30 @@ impl LanguageRegistry {
     }
 }
 
+/// Read the interpreter named in a script's `#!` line
+///
+/// `#!/usr/bin/env -S deno run` yields `deno`, `#!/bin/bash -e` yields `bash`.
+fn shebang_interpreter(path: &Path) -> Option<String> {
+    let mut buffer = [0u8; 256];
+    let mut file = std::fs::File::open(path).ok()?;
+    let len = file.read(&mut buffer).ok()?;
+
+    let first_line = buffer[..len].split(|&b| b == b'\n').next()?;
+    let command = std::str::from_utf8(first_line).ok()?.strip_prefix("#!")?;
+    let mut words = command.split_whitespace();
+
+    let program = words.next()?.rsplit('/').next()?;
+    let interpreter = if program == "env" {
+        words.find(|word| !word.starts_with('-'))?
+    } else {
+        program
+    };
+    Some(interpreter.to_string())
+}
+
 // Note: LanguageRegistry intentionally does not implement Default because
-// new() can fail if the embedded tree-sitter queries are malformed.
-// While this is unlikely at runtime (queries are compile-time embedded),
-// failing explicitly is preferable to silently returning an empty registry.
+// new() returns a Result. Built-in queries are compiled on first use, and
+// test_builtin_queries_compile checks that every one of them is valid.
 
 #[cfg(test)]
 mod tests {
@@ -145,6 +485,35 @@ mod tests {
         assert!(registry.get(LanguageId::Go).is_some());
         assert!(registry.get(LanguageId::Java).is_some());
         assert!(registry.get(LanguageId::Sql).is_some());
+        assert!(registry.get(LanguageId::C).is_some());
+        assert!(registry.get(LanguageId::Cpp).is_some());
+        assert!(registry.get(LanguageId::CSharp).is_some());
+        assert!(registry.get(LanguageId::Ruby).is_some());
+        assert!(registry.get(LanguageId::Php).is_some());
+        assert!(registry.get(LanguageId::Swift).is_some());
+        assert!(registry.get(LanguageId::Kotlin).is_some());
+        assert!(registry.get(LanguageId::Scala).is_some());
+        assert!(registry.get(LanguageId::Bash).is_some());
+        assert!(registry.get(LanguageId::Json).is_some());
+        assert!(registry.get(LanguageId::Yaml).is_some());
+        assert!(registry.get(LanguageId::Toml).is_some());
+        assert!(registry.get(LanguageId::Vue).is_some());
+        assert!(registry.get(LanguageId::Svelte).is_some());
+        assert!(registry.get(LanguageId::Astro).is_some());
+        assert!(registry.get(LanguageId::Dockerfile).is_some());
+        assert!(registry.get(LanguageId::Makefile).is_some());
+    }
+
+    #[test]
+    fn test_builtin_queries_compile() {
+        let registry = LanguageRegistry::new().unwrap();
+        for id in registry.registered_languages() {
+            let language = registry.get(id).unwrap();
+            assert!(language.definitions_query().pattern_count() > 0, "{}", id);
+            language.usages_query();
+            language.comments_query();
+            language.injections_query();
+        }
     }
 
     #[test]
@@ -201,6 +570,76 @@ mod tests {
 
         let sql_lang = registry.get_by_extension("sql").unwrap();
         assert_eq!(sql_lang.id(), LanguageId::Sql);
+
+        let c_lang = registry.get_by_extension("c").unwrap();
+        assert_eq!(c_lang.id(), LanguageId::C);
+
+        for ext in ["cpp", "cc", "cxx", "h", "hpp", "hh", "hxx"] {
+            let cpp_lang = registry.get_by_extension(ext).unwrap();
+            assert_eq!(cpp_lang.id(), LanguageId::Cpp, "extension {}", ext);
+        }
+
+        let cs_lang = registry.get_by_extension("cs").unwrap();
+        assert_eq!(cs_lang.id(), LanguageId::CSharp);
+
+        for ext in ["rb", "rake", "gemspec"] {
+            let rb_lang = registry.get_by_extension(ext).unwrap();
+            assert_eq!(rb_lang.id(), LanguageId::Ruby, "extension {}", ext);
+        }
+
+        let php_lang = registry.get_by_extension("php").unwrap();
+        assert_eq!(php_lang.id(), LanguageId::Php);
+
+        let swift_lang = registry.get_by_extension("swift").unwrap();
+        assert_eq!(swift_lang.id(), LanguageId::Swift);
+
+        for ext in ["kt", "kts"] {
+            let kotlin_lang = registry.get_by_extension(ext).unwrap();
+            assert_eq!(kotlin_lang.id(), LanguageId::Kotlin, "extension {}", ext);
+        }
+
+        for ext in ["scala", "sc", "sbt"] {
+            let scala_lang = registry.get_by_extension(ext).unwrap();
+            assert_eq!(scala_lang.id(), LanguageId::Scala, "extension {}", ext);
+        }
+
+        for ext in ["sh", "bash"] {
+            let sh_lang = registry.get_by_extension(ext).unwrap();
+            assert_eq!(sh_lang.id(), LanguageId::Bash, "extension {}", ext);
+        }
+
+        let json_lang = registry.get_by_extension("json").unwrap();
+        assert_eq!(json_lang.id(), LanguageId::Json);
+
+        for ext in ["yaml", "yml"] {
+            let yaml_lang = registry.get_by_extension(ext).unwrap();
+            assert_eq!(yaml_lang.id(), LanguageId::Yaml, "extension {}", ext);
+        }
+
+        let toml_lang = registry.get_by_extension("toml").unwrap();
+        assert_eq!(toml_lang.id(), LanguageId::Toml);
+
+        let vue_lang = registry.get_by_extension("vue").unwrap();
+        assert_eq!(vue_lang.id(), LanguageId::Vue);
+
+        let svelte_lang = registry.get_by_extension("svelte").unwrap();
+        assert_eq!(svelte_lang.id(), LanguageId::Svelte);
+
+        let astro_lang = registry.get_by_extension("astro").unwrap();
+        assert_eq!(astro_lang.id(), LanguageId::Astro);
+
+        for (ext, id) in [
+            ("mts", LanguageId::TypeScript),
+            ("cts", LanguageId::TypeScript),
+            ("es6", LanguageId::JavaScript),
+            ("markdown", LanguageId::Markdown),
+            ("pgsql", LanguageId::Sql),
+            ("psql", LanguageId::Sql),
+            ("jsonc", LanguageId::Json),
+        ] {
+            let lang = registry.get_by_extension(ext).unwrap();
+            assert_eq!(lang.id(), id, "extension {}", ext);
+        }
     }
 
     #[test]
@@ -242,6 +681,51 @@ mod tests {
 
         let sql_path = PathBuf::from("schema.sql");
         assert!(registry.get_for_path(&sql_path).is_some());
+
+        let c_path = PathBuf::from("main.c");
+        assert!(registry.get_for_path(&c_path).is_some());
+
+        let header_path = PathBuf::from("include/user.h");
+        assert!(registry.get_for_path(&header_path).is_some());
+
+        for file_name in ["Rakefile", "Gemfile", "lib/tasks/db.rake"] {
+            let rb_lang = registry.get_for_path(Path::new(file_name)).unwrap();
+            assert_eq!(rb_lang.id(), LanguageId::Ruby, "path {}", file_name);
+        }
+
+        let bashrc_path = PathBuf::from("home/.bashrc");
+        assert_eq!(
+            registry.get_for_path(&bashrc_path).unwrap().id(),
+            LanguageId::Bash
+        );
+
+        for (file_name, id) in [
+            ("Makefile", LanguageId::Makefile),
+            ("build/rules.mk", LanguageId::Makefile),
+            ("docker/Dockerfile", LanguageId::Dockerfile),
+            ("api.dockerfile", LanguageId::Dockerfile),
+        ] {
+            let language = registry.get_for_path(Path::new(file_name)).unwrap();
+            assert_eq!(language.id(), id, "path {}", file_name);
+        }
+
+        let php_path = PathBuf::from("src/Invoice.php");
+        assert_eq!(
+            registry.get_for_path(&php_path).unwrap().id(),
+            LanguageId::Php
+        );
+
+        let dts_path = PathBuf::from("types/index.d.ts");
+        assert_eq!(
+            registry.get_for_path(&dts_path).unwrap().id(),
+            LanguageId::TypeScript
+        );
+
+        let vue_path = PathBuf::from("src/components/UserCard.vue");
+        assert_eq!(
+            registry.get_for_path(&vue_path).unwrap().id(),
+            LanguageId::Vue
+        );
     }
 
     #[test]
@@ -265,5 +749,174 @@ mod tests {
         assert!(registry.is_supported(Path::new("test.go")));
         assert!(registry.is_supported(Path::new("test.java")));
         assert!(registry.is_supported(Path::new("test.sql")));
+        assert!(registry.is_supported(Path::new("Gemfile")));
+        assert!(registry.is_supported(Path::new("test.vue")));
+        assert!(registry.is_supported(Path::new("test.svelte")));
+        assert!(registry.is_supported(Path::new("test.astro")));
+        assert!(registry.is_supported(Path::new("Makefile")));
+        assert!(registry.is_supported(Path::new("Dockerfile")));
+        assert!(!registry.is_supported(Path::new("Procfile")));
+    }
+
+    #[test]
+    fn test_language_id() {
+        let registry = LanguageRegistry::new().unwrap();
+
+        assert_eq!(registry.language_id("ts"), Some(LanguageId::TypeScript));
+        assert_eq!(registry.language_id("Vue"), Some(LanguageId::Vue));
+        assert_eq!(registry.language_id("mydsl"), None);
+    }
+
+    #[test]
+    fn test_load_plugins_skips_broken_plugins() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let plugin_dir = dir.path().join("mydsl");
+        std::fs::create_dir(&plugin_dir).unwrap();
+        std::fs::write(
+            plugin_dir.join("language.json"),
+            r#"{"name": "mydsl", "library": "missing.so", "mappings": {}}"#,
+        )
+        .unwrap();
+
+        let mut registry = LanguageRegistry::new().unwrap();
+        assert_eq!(registry.load_plugins(dir.path()).unwrap(), 0);
+        assert!(registry.get_by_name("mydsl").is_none());
+        assert!(registry.get(LanguageId::TypeScript).is_some());
+    }
+
+    #[test]
+    fn test_load_query_overrides() {
+        let dir = tempfile::TempDir::new().unwrap();
+        for (language, source) in [
+            (
+                "py",
+                "(function_definition name: (identifier) @name) @definition.function",
+            ),
+            ("rust", "(no_such_node) @definition.function"),
+            ("cobol", "(program) @definition.module"),
+        ] {
+            std::fs::create_dir(dir.path().join(language)).unwrap();
+            std::fs::write(dir.path().join(language).join("definitions.scm"), source).unwrap();
+        }
+
+        let mut registry = LanguageRegistry::new().unwrap();
+        let rust_patterns = registry
+            .get(LanguageId::Rust)
+            .unwrap()
+            .definitions_query()
+            .pattern_count();
+        assert_eq!(registry.load_query_overrides(dir.path()).unwrap(), 1);
+
+        let python = registry.get_for_path(Path::new("app.py")).unwrap();
+        assert_eq!(python.id(), LanguageId::Python);
+        assert_eq!(python.definitions_query().pattern_count(), 1);
+        assert_eq!(
+            registry
+                .get(LanguageId::Rust)
+                .unwrap()
+                .definitions_query()
+                .pattern_count(),
+            rust_patterns,
+            "Invalid overrides should leave the built-in query in place"
+        );
+
+        let errors = registry.override_errors();
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert!(
+            errors[0].contains("unknown language 'cobol'"),
+            "{}",
+            errors[0]
+        );
+        assert!(
+            errors[1].contains("definitions.scm:1:2")
+                && errors[1].contains("in pattern: (no_such_node)"),
+            "{}",
+            errors[1]
+        );
+    }
+
+    #[test]
+    fn test_file_types() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let file_types: FileTypeConfig = serde_json::from_str(
+            r#"{
+                "extensions": {"tpl": "html", ".blade.php": "html", "x": "cobol"},
+                "globs": [{"pattern": "config/*.conf", "language": "yaml"}],
+                "interpreters": {"pypy": "python"}
+            }"#,
+        )
+        .unwrap();
+
+        let mut registry = LanguageRegistry::new().unwrap();
+        registry.add_file_types(dir.path(), &file_types);
+
+        let id = |path: PathBuf| registry.get_for_path(&path).map(|lang| lang.id());
+        assert_eq!(id(dir.path().join("page.tpl")), Some(LanguageId::Html));
+        assert_eq!(
+            id(dir.path().join("views/home.blade.php")),
+            Some(LanguageId::Html)
+        );
+        assert_eq!(id(dir.path().join("src/api.php")), Some(LanguageId::Php));
+        assert_eq!(
+            id(dir.path().join("config/app.conf")),
+            Some(LanguageId::Yaml)
+        );
+        assert_eq!(id(dir.path().join("other/app.conf")), None);
+        assert_eq!(id(dir.path().join("file.x")), None);
+
+        std::fs::write(dir.path().join("tool"), "#!/usr/bin/env pypy\nprint(1)\n").unwrap();
+        assert_eq!(id(dir.path().join("tool")), Some(LanguageId::Python));
+    }
+
+    #[test]
+    fn test_shebang_detection() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let registry = LanguageRegistry::new().unwrap();
+
+        for (file_name, first_line, expected) in [
+            ("deploy", "#!/bin/bash -e", Some(LanguageId::Bash)),
+            (
+                "manage",
+                "#!/usr/bin/env python3.12",
+                Some(LanguageId::Python),
+            ),
+            (
+                "serve",
+                "#!/usr/bin/env -S deno run",
+                Some(LanguageId::TypeScript),
+            ),
+            ("cli", "#!/usr/bin/env node", Some(LanguageId::JavaScript)),
+            ("LICENSE", "MIT License", None),
+            ("script.unknown", "#!/bin/sh", None),
+        ] {
+            let path = dir.path().join(file_name);
+            std::fs::write(&path, format!("{}\n", first_line)).unwrap();
+            assert_eq!(
+                registry.get_for_path(&path).map(|lang| lang.id()),
+                expected,
+                "{}",
+                first_line
+            );
+        }
+    }
+
+    #[test]
+    fn test_for_workspace_missing_plugin_dir() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut config = CodeScopeConfig {
+            language_plugins: Some("languages".to_string()),
+            ..CodeScopeConfig::default_config()
+        };
+        config
+            .file_types
+            .extensions
+            .insert("tpl".to_string(), "html".to_string());
+
+        // The missing directory is skipped and the file types still apply
+        let registry = LanguageRegistry::for_workspace(dir.path(), &config).unwrap();
+        assert_eq!(
+            registry.get_for_path(Path::new("page.tpl")).unwrap().id(),
+            LanguageId::Html
+        );
     }
 }
@@ -0,0 +1,42 @@
+use crate::symbol::types::SymbolKind;
+
+use super::traits::SymbolKindMapping;
+
+define_language! {
+    name: RubyLanguage,
+    id: Ruby,
+    display_name: "Ruby",
+    extensions: ["rb", "rake", "gemspec"],
+    file_names: ["Rakefile", "Gemfile"],
+    tree_sitter_language: tree_sitter_ruby::LANGUAGE,
+    query_dir: "ruby",
+    mappings: RUBY_DEFINITION_MAPPINGS,
+}
+
+/// Definition mappings for Ruby
+const RUBY_DEFINITION_MAPPINGS: &[SymbolKindMapping] = &[
+    SymbolKindMapping {
+        capture_name: "definition.module",
+        kind: SymbolKind::Module,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.class",
+        kind: SymbolKind::Class,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.function",
+        kind: SymbolKind::Function,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.static_method",
+        kind: SymbolKind::StaticMethod,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.method",
+        kind: SymbolKind::Method,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.constant",
+        kind: SymbolKind::Const,
+    },
+];
@@ -0,0 +1,53 @@
+use crate::symbol::types::SymbolKind;
+
+use super::traits::SymbolKindMapping;
+
+define_language! {
+    name: ScalaLanguage,
+    id: Scala,
+    display_name: "Scala",
+    extensions: ["scala", "sc", "sbt"],
+    tree_sitter_language: tree_sitter_scala::LANGUAGE,
+    query_dir: "scala",
+    mappings: SCALA_DEFINITION_MAPPINGS,
+}
+
+/// Definition mappings for Scala
+const SCALA_DEFINITION_MAPPINGS: &[SymbolKindMapping] = &[
+    SymbolKindMapping {
+        capture_name: "definition.class",
+        kind: SymbolKind::Class,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.trait",
+        kind: SymbolKind::Trait,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.enum",
+        kind: SymbolKind::Enum,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.object",
+        kind: SymbolKind::Object,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.type_alias",
+        kind: SymbolKind::TypeAlias,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.function",
+        kind: SymbolKind::Function,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.method",
+        kind: SymbolKind::Method,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.constructor",
+        kind: SymbolKind::Constructor,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.property",
+        kind: SymbolKind::Variable,
+    },
+];
@@ -0,0 +1,44 @@
+// Single-file component support (Vue, Svelte, Astro)
+//
+// Components are parsed with the HTML grammar and queries, so the template
+// reports elements, ids and classes like an HTML file. `<script>` and
+// `<style>` sections go through the HTML injections query: scripts are
+// analysed as JavaScript, or TypeScript with `lang="ts"`, and styles as CSS.
+// Astro's `---` frontmatter is analysed as TypeScript.
+//
+// Known limitations:
+// - Template expressions (`{{ user.name }}`, `{#if ...}`, `{title}`) are plain
+//   text; only attribute values such as `@click="save"` are reported as usages.
+// - `<style lang="scss">` and other preprocessors are parsed as CSS.
+define_language! {
+    name: VueLanguage,
+    id: Vue,
+    display_name: "Vue",
+    extensions: ["vue"],
+    tree_sitter_language: tree_sitter_html::LANGUAGE,
+    query_dir: "html",
+    mappings: super::html::HTML_DEFINITION_MAPPINGS,
+    injections: true,
+}
+
+define_language! {
+    name: SvelteLanguage,
+    id: Svelte,
+    display_name: "Svelte",
+    extensions: ["svelte"],
+    tree_sitter_language: tree_sitter_html::LANGUAGE,
+    query_dir: "html",
+    mappings: super::html::HTML_DEFINITION_MAPPINGS,
+    injections: true,
+}
+
+define_language! {
+    name: AstroLanguage,
+    id: Astro,
+    display_name: "Astro",
+    extensions: ["astro"],
+    tree_sitter_language: tree_sitter_html::LANGUAGE,
+    query_dir: "html",
+    mappings: super::html::HTML_DEFINITION_MAPPINGS,
+    injections: true,
+}
@@ -6,7 +6,7 @@ define_language! {
     name: SqlLanguage,
     id: Sql,
     display_name: "Sql",
-    extensions: ["sql"],
+    extensions: ["sql", "pgsql", "psql"],
     tree_sitter_language: tree_sitter_sequel::LANGUAGE,
     query_dir: "sql",
     mappings: SQL_DEFINITION_MAPPINGS,
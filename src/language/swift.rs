@@ -0,0 +1,61 @@
+use crate::symbol::types::SymbolKind;
+
+use super::traits::SymbolKindMapping;
+
+define_language! {
+    name: SwiftLanguage,
+    id: Swift,
+    display_name: "Swift",
+    extensions: ["swift"],
+    tree_sitter_language: tree_sitter_swift::LANGUAGE,
+    query_dir: "swift",
+    mappings: SWIFT_DEFINITION_MAPPINGS,
+}
+
+/// Definition mappings for Swift
+const SWIFT_DEFINITION_MAPPINGS: &[SymbolKindMapping] = &[
+    SymbolKindMapping {
+        capture_name: "definition.class",
+        kind: SymbolKind::Class,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.struct",
+        kind: SymbolKind::Struct,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.enum",
+        kind: SymbolKind::Enum,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.extension",
+        kind: SymbolKind::Impl,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.protocol",
+        kind: SymbolKind::Interface,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.type_alias",
+        kind: SymbolKind::TypeAlias,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.function",
+        kind: SymbolKind::Function,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.static_method",
+        kind: SymbolKind::StaticMethod,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.method",
+        kind: SymbolKind::Method,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.constructor",
+        kind: SymbolKind::Constructor,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.property",
+        kind: SymbolKind::Variable,
+    },
+];
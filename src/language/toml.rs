@@ -0,0 +1,25 @@
+use crate::symbol::types::SymbolKind;
+
+use super::traits::SymbolKindMapping;
+
+define_language! {
+    name: TomlLanguage,
+    id: Toml,
+    display_name: "Toml",
+    extensions: ["toml"],
+    tree_sitter_language: tree_sitter_toml_ng::LANGUAGE,
+    query_dir: "toml",
+    mappings: TOML_DEFINITION_MAPPINGS,
+}
+
+/// Definition mappings for TOML (keys are named by their dotted path)
+const TOML_DEFINITION_MAPPINGS: &[SymbolKindMapping] = &[
+    SymbolKindMapping {
+        capture_name: "definition.key",
+        kind: SymbolKind::Key,
+    },
+    SymbolKindMapping {
+        capture_name: "definition.table",
+        kind: SymbolKind::Key,
+    },
+];
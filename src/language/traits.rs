@@ -1,3 +1,5 @@
+use std::ops::Range;
+
 use tree_sitter::{Language, Query};
 
 use crate::symbol::types::SymbolKind;
@@ -18,6 +20,25 @@ pub enum LanguageId {
     Go,
     Java,
     Sql,
+    C,
+    Cpp,
+    CSharp,
+    Ruby,
+    Php,
+    Swift,
+    Kotlin,
+    Scala,
+    Bash,
+    Json,
+    Yaml,
+    Toml,
+    Vue,
+    Svelte,
+    Astro,
+    Dockerfile,
+    Makefile,
+    /// Language loaded at runtime from a plugin directory, identified by name
+    Plugin(&'static str),
 }
 
 impl std::fmt::Display for LanguageId {
@@ -35,6 +56,24 @@ impl std::fmt::Display for LanguageId {
             LanguageId::Go => write!(f, "Go"),
             LanguageId::Java => write!(f, "Java"),
             LanguageId::Sql => write!(f, "Sql"),
+            LanguageId::C => write!(f, "C"),
+            LanguageId::Cpp => write!(f, "Cpp"),
+            LanguageId::CSharp => write!(f, "CSharp"),
+            LanguageId::Ruby => write!(f, "Ruby"),
+            LanguageId::Php => write!(f, "Php"),
+            LanguageId::Swift => write!(f, "Swift"),
+            LanguageId::Kotlin => write!(f, "Kotlin"),
+            LanguageId::Scala => write!(f, "Scala"),
+            LanguageId::Bash => write!(f, "Bash"),
+            LanguageId::Json => write!(f, "Json"),
+            LanguageId::Yaml => write!(f, "Yaml"),
+            LanguageId::Toml => write!(f, "Toml"),
+            LanguageId::Vue => write!(f, "Vue"),
+            LanguageId::Svelte => write!(f, "Svelte"),
+            LanguageId::Astro => write!(f, "Astro"),
+            LanguageId::Dockerfile => write!(f, "Dockerfile"),
+            LanguageId::Makefile => write!(f, "Makefile"),
+            LanguageId::Plugin(name) => write!(f, "{}", name),
         }
     }
 }
@@ -46,6 +85,47 @@ pub struct SymbolKindMapping {
     pub kind: SymbolKind,
 }
 
+/// Definition found by scanning source lines instead of running a query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScannedDefinition {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// Byte range of the whole definition
+    pub range: Range<usize>,
+    /// First line of the definition (0-indexed)
+    pub start_row: usize,
+    /// Last line of the definition (0-indexed)
+    pub end_row: usize,
+}
+
+/// Kinds of tree-sitter query a language provides
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QueryKind {
+    Definitions,
+    Usages,
+    Comments,
+    Injections,
+}
+
+impl QueryKind {
+    pub const ALL: [QueryKind; 4] = [
+        QueryKind::Definitions,
+        QueryKind::Usages,
+        QueryKind::Comments,
+        QueryKind::Injections,
+    ];
+
+    /// Query file name (e.g., `definitions.scm`)
+    pub fn file_name(self) -> &'static str {
+        match self {
+            QueryKind::Definitions => "definitions.scm",
+            QueryKind::Usages => "usages.scm",
+            QueryKind::Comments => "comments.scm",
+            QueryKind::Injections => "injections.scm",
+        }
+    }
+}
+
 /// Trait for language support implementations
 ///
 /// Each language (TypeScript, Python, Rust, etc.) implements this trait
@@ -60,6 +140,11 @@ pub trait LanguageSupport: Send + Sync {
     /// Get the file extensions supported by this language (e.g., ["ts", "tsx"])
     fn file_extensions(&self) -> &[&'static str];
 
+    /// Get exact file names recognised regardless of extension (e.g., ["Rakefile"])
+    fn file_names(&self) -> &[&'static str] {
+        &[]
+    }
+
     /// Get the tree-sitter Language grammar
     fn tree_sitter_language(&self) -> &Language;
 
@@ -69,6 +154,28 @@ pub trait LanguageSupport: Send + Sync {
     /// Get the query for finding symbol usages
     fn usages_query(&self) -> &Query;
 
+    /// Get the query for finding comments
+    ///
+    /// Captures named `comment` match comment nodes; captures named
+    /// `comment.doc` match documentation strings (e.g., Python docstrings).
+    fn comments_query(&self) -> &Query;
+
+    /// Get the query for finding regions written in other languages
+    ///
+    /// Captures named `injection.content` mark the embedded region; its
+    /// language comes from an `injection.language` capture or a
+    /// `(#set! injection.language "...")` property.
+    fn injections_query(&self) -> Option<&Query> {
+        None
+    }
+
+    /// Get the source text of a query, if the language has one of that kind
+    ///
+    /// Used to extend a query with workspace overrides.
+    fn query_source(&self, _kind: QueryKind) -> Option<&str> {
+        None
+    }
+
     /// Get the mappings from capture names to symbol kinds
     fn definition_mappings(&self) -> &[SymbolKindMapping];
 
@@ -79,4 +186,13 @@ pub trait LanguageSupport: Send + Sync {
     fn uses_separate_docs(&self) -> bool {
         false
     }
+
+    /// Find definitions by scanning the source text
+    ///
+    /// Line-oriented files whose grammar cannot name their definitions
+    /// (e.g., Make targets) return them here; the definitions query is then
+    /// not used.
+    fn scan_definitions(&self, _source: &str) -> Option<Vec<ScannedDefinition>> {
+        None
+    }
 }
@@ -6,7 +6,7 @@ define_language! {
     name: TypeScriptLanguage,
     id: TypeScript,
     display_name: "TypeScript",
-    extensions: ["ts"],
+    extensions: ["ts", "mts", "cts"],
     tree_sitter_language: tree_sitter_typescript::LANGUAGE_TYPESCRIPT,
     query_dir: "typescript",
     mappings: TYPESCRIPT_DEFINITION_MAPPINGS,
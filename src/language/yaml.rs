@@ -0,0 +1,19 @@
+use crate::symbol::types::SymbolKind;
+
+use super::traits::SymbolKindMapping;
+
+define_language! {
+    name: YamlLanguage,
+    id: Yaml,
+    display_name: "Yaml",
+    extensions: ["yaml", "yml"],
+    tree_sitter_language: tree_sitter_yaml::LANGUAGE,
+    query_dir: "yaml",
+    mappings: YAML_DEFINITION_MAPPINGS,
+}
+
+/// Definition mappings for YAML (keys are named by their dotted path)
+const YAML_DEFINITION_MAPPINGS: &[SymbolKindMapping] = &[SymbolKindMapping {
+    capture_name: "definition.key",
+    kind: SymbolKind::Key,
+}];
@@ -4,5 +4,6 @@ pub mod context;
 pub mod language;
 pub mod parser;
 pub mod pipeline;
+pub mod query;
 pub mod server;
 pub mod symbol;
@@ -7,6 +7,7 @@ use tree_sitter::Tree;
 
 use super::cache::ParserCache;
 use super::generic::GenericParser;
+use super::injection::{parse_injections, Injection};
 use crate::language::{LanguageRegistry, LanguageSupport};
 
 /// Cached parser that wraps GenericParser with AST caching
@@ -102,6 +103,37 @@ impl CachedParser {
         Ok((tree, lang))
     }
 
+    /// Parse the regions of a parsed file that embed other languages
+    ///
+    /// Injected trees share the host file's positions, so their line numbers
+    /// need no mapping. They are not cached.
+    pub fn parse_injections(
+        &self,
+        tree: &Tree,
+        language: &dyn LanguageSupport,
+        source_code: &str,
+    ) -> Vec<Injection> {
+        parse_injections(tree, language, source_code, self.parser.registry())
+    }
+
+    /// Parse source code and return the host tree followed by its injections
+    ///
+    /// Use this when embedded code (e.g., `<script>` in HTML or fenced code
+    /// in Markdown) should be searched like the rest of the file.
+    pub fn parse_with_injections(
+        &mut self,
+        path: &Path,
+        source_code: &str,
+        modified_time: SystemTime,
+    ) -> Result<Vec<(Tree, Arc<dyn LanguageSupport>)>> {
+        let (tree, language) = self.parse_with_language(path, source_code, modified_time)?;
+        let injections = self.parse_injections(&tree, language.as_ref(), source_code);
+
+        Ok(std::iter::once((tree, language))
+            .chain(injections.into_iter().map(|i| (i.tree, i.language)))
+            .collect())
+    }
+
     /// Check if a file is supported
     pub fn is_supported(&self, path: &Path) -> bool {
         self.parser.is_supported(path)
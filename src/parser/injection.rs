@@ -0,0 +1,239 @@
+//! Embedded language injection
+//!
+//! Some files embed code in another language: `<script>` and `<style>` in
+//! HTML, fenced code blocks in Markdown. A language's injections query marks
+//! these regions, and each region is re-parsed with the embedded language's
+//! grammar. Regions are parsed as included ranges of the host source, so
+//! nodes in an injected tree carry the host file's byte offsets and line
+//! numbers.
+//!
+//! Astro components also embed TypeScript in a `---` frontmatter block at
+//! the top of the file, which the HTML grammar only sees as text.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use streaming_iterator::StreamingIterator;
+use tree_sitter::{Parser, Point, QueryCursor, Range, Tree};
+
+use crate::language::{LanguageId, LanguageRegistry, LanguageSupport};
+
+/// Capture marking an embedded region
+const CONTENT_CAPTURE: &str = "injection.content";
+
+/// Capture or `#set!` property naming the embedded language
+const LANGUAGE_KEY: &str = "injection.language";
+
+/// A region of a host file parsed with an embedded language
+pub struct Injection {
+    pub tree: Tree,
+    pub language: Arc<dyn LanguageSupport>,
+}
+
+/// Parse the regions of a host tree that are written in other languages
+///
+/// Regions whose language is unknown or not registered are skipped. A
+/// language captured from the source (e.g., a fence info string) takes
+/// precedence over a `#set!` default for the same region.
+pub fn parse_injections(
+    tree: &Tree,
+    language: &dyn LanguageSupport,
+    source: &str,
+    registry: &LanguageRegistry,
+) -> Vec<Injection> {
+    let Some(query) = language.injections_query() else {
+        return Vec::new();
+    };
+
+    // Content range -> (language name, whether it was captured from the source)
+    let mut regions: HashMap<(usize, usize), (Range, String, bool)> = HashMap::new();
+
+    if language.id() == LanguageId::Astro {
+        if let Some(range) = frontmatter_range(source) {
+            regions.insert(
+                (range.start_byte, range.end_byte),
+                (range, "typescript".to_string(), true),
+            );
+        }
+    }
+    let mut cursor = QueryCursor::new();
+    let mut matches = cursor.matches(query, tree.root_node(), source.as_bytes());
+
+    while let Some(m) = matches.next() {
+        let mut content = None;
+        let mut captured_language = None;
+
+        for capture in m.captures {
+            match query.capture_names()[capture.index as usize] {
+                CONTENT_CAPTURE => content = Some(capture.node),
+                LANGUAGE_KEY => {
+                    captured_language = capture.node.utf8_text(source.as_bytes()).ok();
+                }
+                _ => {}
+            }
+        }
+
+        let Some(content) = content else {
+            continue;
+        };
+        if content.start_byte() == content.end_byte() {
+            continue;
+        }
+
+        let (name, explicit) = match captured_language {
+            Some(name) => (name.to_string(), true),
+            None => {
+                let property = query
+                    .property_settings(m.pattern_index)
+                    .iter()
+                    .find(|p| &*p.key == LANGUAGE_KEY)
+                    .and_then(|p| p.value.as_deref());
+                match property {
+                    Some(name) => (name.to_string(), false),
+                    None => continue,
+                }
+            }
+        };
+
+        let key = (content.start_byte(), content.end_byte());
+        match regions.get(&key) {
+            Some((_, _, true)) => {}
+            Some(_) if !explicit => {}
+            _ => {
+                regions.insert(key, (content.range(), name, explicit));
+            }
+        }
+    }
+
+    let mut regions: Vec<_> = regions.into_values().collect();
+    regions.sort_by_key(|(range, _, _)| range.start_byte);
+
+    let mut parser = Parser::new();
+    regions
+        .into_iter()
+        .filter_map(|(range, name, _)| {
+            let language = registry
+                .get_by_name(injected_language_name(&name)?)?
+                .clone();
+            parser.set_language(language.tree_sitter_language()).ok()?;
+            parser.set_included_ranges(&[range]).ok()?;
+            let tree = parser.parse(source, None)?;
+            Some(Injection { tree, language })
+        })
+        .collect()
+}
+
+/// Find the body of a `---` frontmatter block at the start of a file
+///
+/// The range covers the lines between the opening and closing fences.
+fn frontmatter_range(source: &str) -> Option<Range> {
+    let mut lines = source.split_inclusive('\n');
+    if lines.next()?.trim_end() != "---" {
+        return None;
+    }
+
+    let start_byte = source.find('\n')? + 1;
+    let mut end_byte = start_byte;
+    for (row, line) in lines.enumerate() {
+        if line.trim_end() == "---" {
+            return (end_byte > start_byte).then_some(Range {
+                start_byte,
+                end_byte,
+                start_point: Point::new(1, 0),
+                end_point: Point::new(row + 1, 0),
+            });
+        }
+        end_byte += line.len();
+    }
+    None
+}
+
+/// Resolve an injected language name such as a fence info string
+///
+/// Only the first word is used, so `rust,ignore` and `python title="x"`
+/// resolve to Rust and Python.
+fn injected_language_name(name: &str) -> Option<&str> {
+    name.split(|c: char| c == ',' || c == '{' || c.is_whitespace())
+        .next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    use crate::language::LanguageId;
+    use crate::parser::GenericParser;
+
+    fn injections(file_name: &str, source: &str) -> Vec<Injection> {
+        let registry = Arc::new(LanguageRegistry::new().unwrap());
+        let mut parser = GenericParser::new(registry.clone()).unwrap();
+        let (tree, language) = parser
+            .parse_with_language(Path::new(file_name), source)
+            .unwrap();
+        parse_injections(&tree, language.as_ref(), source, &registry)
+    }
+
+    #[test]
+    fn test_html_script_and_style() {
+        let source = "<div>\n<script>\nfunction greet() {}\n</script>\n<style>\n.card { color: red; }\n</style>\n</div>\n";
+        let injections = injections("index.html", source);
+
+        let languages: Vec<_> = injections.iter().map(|i| i.language.id()).collect();
+        assert_eq!(languages, [LanguageId::JavaScript, LanguageId::Css]);
+
+        // Injected trees use host file positions
+        let script = injections[0].tree.root_node();
+        let function = script.named_child(0).unwrap();
+        assert_eq!(function.kind(), "function_declaration");
+        assert_eq!(function.start_position().row, 2);
+    }
+
+    #[test]
+    fn test_html_script_lang_attribute() {
+        let source = "<script lang=\"ts\">\nconst a: number = 1;\n</script>\n";
+        let injections = injections("index.html", source);
+
+        assert_eq!(injections.len(), 1);
+        assert_eq!(injections[0].language.id(), LanguageId::TypeScript);
+    }
+
+    #[test]
+    fn test_markdown_fences() {
+        let source = "# Title\n\n```rust,ignore\nfn main() {}\n```\n\n```unknown\nx\n```\n\n```py\nx = 1\n```\n";
+        let injections = injections("README.md", source);
+
+        let languages: Vec<_> = injections.iter().map(|i| i.language.id()).collect();
+        assert_eq!(languages, [LanguageId::Rust, LanguageId::Python]);
+        assert_eq!(injections[0].tree.root_node().start_position().row, 3);
+    }
+
+    #[test]
+    fn test_astro_frontmatter() {
+        let source =
+            "---\nconst items: Array<string> = [];\n---\n<ul><script>let x = 1;</script></ul>\n";
+        let injections = injections("index.astro", source);
+
+        let languages: Vec<_> = injections.iter().map(|i| i.language.id()).collect();
+        assert_eq!(languages, [LanguageId::TypeScript, LanguageId::JavaScript]);
+
+        let frontmatter = injections[0].tree.root_node();
+        assert!(!frontmatter.has_error());
+        assert_eq!(frontmatter.start_position().row, 1);
+    }
+
+    #[test]
+    fn test_frontmatter_range() {
+        let range = frontmatter_range("---\na\nb\n---\n<p/>").unwrap();
+        assert_eq!((range.start_byte, range.end_byte), (4, 8));
+        assert_eq!(range.end_point, Point::new(3, 0));
+
+        assert!(frontmatter_range("<p/>\n---\n").is_none());
+        assert!(frontmatter_range("---\nunterminated\n").is_none());
+    }
+
+    #[test]
+    fn test_no_injections_query() {
+        assert!(injections("main.rs", "fn main() {}").is_empty());
+    }
+}
@@ -1,8 +1,10 @@
 pub mod cache;
 pub mod cached;
 pub mod generic;
+pub mod injection;
 pub mod typescript;
 
 pub use cache::ParserCache;
 pub use cached::CachedParser;
 pub use generic::GenericParser;
+pub use injection::Injection;
@@ -1,19 +1,32 @@
+use std::cell::OnceCell;
 use std::collections::{HashMap, HashSet};
-use std::path::Path;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use anyhow::Result;
 use streaming_iterator::StreamingIterator;
+use tree_sitter::Query;
 
 use crate::cache::CachedContent;
 use crate::context::extractor::extract_contexts;
+use crate::language::{LanguageId, LanguageSupport};
 use crate::parser::CachedParser;
 use crate::pipeline::stats::{count_lines, FileStatistics};
+use crate::query::engine::run_query;
+use crate::query::fix::{apply_fixes, content_hash, unified_diff};
+use crate::query::lint::CompiledRule;
+use crate::query::pattern::Pattern;
+use crate::query::types::{Diagnostic, FileFix, FixStatus, PatternMatch, QueryMatch};
 use crate::symbol::comment::{
-    extract_docs_before_line, find_comments_in_file, find_comments_in_sql_file,
-    find_text_in_markdown_file,
+    extract_docs_before_line, find_comments_in_tree, find_text_in_markdown_source,
 };
+use crate::symbol::key_path::{definition_key_path, key_path, key_text};
+use crate::symbol::matcher::TextMatcher;
+use crate::symbol::redact::Redactor;
 use crate::symbol::sql_comment::{extract_sql_comments, find_parent_table_name};
 use crate::symbol::types::{CommentMatch, SymbolDefinition, SymbolKind, SymbolUsage, UsageKind};
+use crate::symbol::usage::is_in_import_statement;
 
 /// Trait for collecting results from parsed files
 pub trait ResultCollector: Sync {
@@ -31,6 +44,8 @@ pub trait ResultCollector: Sync {
 pub struct DefinitionCollector {
     pub symbol: String,
     pub include_docs: bool,
+    /// Masks secrets in the returned code
+    pub redactor: Option<Arc<Redactor>>,
 }
 
 impl ResultCollector for DefinitionCollector {
@@ -43,20 +58,88 @@ impl ResultCollector for DefinitionCollector {
         cached_content: &CachedContent,
     ) -> Result<Vec<Self::Item>> {
         let source_code = &cached_content.content;
-        let (tree, language) =
-            parser.parse_with_language(path, source_code, cached_content.modified_time)?;
+        // Embedded code (e.g., <script> in HTML) is searched like the host file
+        let trees =
+            parser.parse_with_injections(path, source_code, cached_content.modified_time)?;
+
+        // Secrets are only looked for once a definition is found
+        let secrets = OnceCell::new();
+        let code_at = |range: Range<usize>| match &self.redactor {
+            Some(redactor) => secrets
+                .get_or_init(|| redactor.find(source_code, trees.iter().map(|(tree, _)| tree)))
+                .mask(range),
+            None => source_code.get(range).unwrap_or("").to_string(),
+        };
 
         let mut definitions = Vec::new();
         // Track seen definitions to eliminate duplicates from overlapping query patterns
         // Key: (start_line, end_line, name)
         let mut seen: HashSet<(usize, usize, String)> = HashSet::new();
+        for (tree, language) in &trees {
+            self.collect_definitions(
+                tree,
+                language.as_ref(),
+                path,
+                source_code,
+                &code_at,
+                &mut seen,
+                &mut definitions,
+            );
+        }
+
+        Ok(definitions)
+    }
+}
+
+impl DefinitionCollector {
+    /// Collect matching definitions from one parsed tree
+    ///
+    /// `code_at` gets the code of a byte range of the source.
+    #[allow(clippy::too_many_arguments)]
+    fn collect_definitions(
+        &self,
+        tree: &tree_sitter::Tree,
+        language: &dyn LanguageSupport,
+        path: &Path,
+        source_code: &str,
+        code_at: &dyn Fn(Range<usize>) -> String,
+        seen: &mut HashSet<(usize, usize, String)>,
+        definitions: &mut Vec<SymbolDefinition>,
+    ) {
+        if let Some(scanned) = language.scan_definitions(source_code) {
+            for definition in scanned.into_iter().filter(|d| d.name == self.symbol) {
+                let start_line = definition.start_row + 1;
+                let end_line = definition.end_row + 1;
+                if !seen.insert((start_line, end_line, definition.name.clone())) {
+                    continue;
+                }
+
+                let docs = if self.include_docs {
+                    extract_docs_before_line(source_code, definition.start_row)
+                } else {
+                    None
+                };
+
+                definitions.push(SymbolDefinition {
+                    file_path: path.to_string_lossy().to_string(),
+                    start_line,
+                    end_line,
+                    node_kind: definition.kind,
+                    code: code_at(definition.range),
+                    name: definition.name,
+                    docs,
+                });
+            }
+            return;
+        }
+
         let query = language.definitions_query();
         let mappings = language.definition_mappings();
 
         // For languages with separate doc statements (e.g., SQL COMMENT ON),
         // extract documentation map first
         let sql_comment_map = if self.include_docs && language.uses_separate_docs() {
-            Some(extract_sql_comments(&tree, source_code, query))
+            Some(extract_sql_comments(tree, source_code, query))
         } else {
             None
         };
@@ -87,6 +170,10 @@ impl ResultCollector for DefinitionCollector {
             }
 
             if let (Some(name_str), Some(node), Some(symbol_kind)) = (name, definition_node, kind) {
+                // Configuration keys are named by their full dotted path
+                let key_path = definition_key_path(symbol_kind, node, source_code);
+                let name_str = key_path.as_deref().unwrap_or(name_str);
+
                 if name_str == self.symbol {
                     let start_line = node.start_position().row + 1;
                     let end_line = node.end_position().row + 1;
@@ -97,10 +184,7 @@ impl ResultCollector for DefinitionCollector {
                         continue;
                     }
 
-                    let code = node
-                        .utf8_text(source_code.as_bytes())
-                        .unwrap_or("")
-                        .to_string();
+                    let code = code_at(node.byte_range());
 
                     let docs = if self.include_docs {
                         if let Some(ref comment_map) = sql_comment_map {
@@ -142,8 +226,6 @@ impl ResultCollector for DefinitionCollector {
                 }
             }
         }
-
-        Ok(definitions)
     }
 }
 
@@ -165,12 +247,45 @@ impl ResultCollector for UsageCollector {
         cached_content: &CachedContent,
     ) -> Result<Vec<Self::Item>> {
         let source_code = &cached_content.content;
-        let (tree, language) =
-            parser.parse_with_language(path, source_code, cached_content.modified_time)?;
+        // Embedded code (e.g., <script> in HTML) is searched like the host file
+        let trees =
+            parser.parse_with_injections(path, source_code, cached_content.modified_time)?;
 
         let mut usages = Vec::new();
         let mut seen: HashSet<(usize, usize)> = HashSet::new();
+        for (tree, language) in &trees {
+            self.collect_usages(
+                tree,
+                language.as_ref(),
+                path,
+                source_code,
+                &mut seen,
+                &mut usages,
+            );
+        }
+
+        Ok(usages)
+    }
+}
+
+impl UsageCollector {
+    /// Collect matching usages from one parsed tree
+    fn collect_usages(
+        &self,
+        tree: &tree_sitter::Tree,
+        language: &dyn LanguageSupport,
+        path: &Path,
+        source_code: &str,
+        seen: &mut HashSet<(usize, usize)>,
+        usages: &mut Vec<SymbolUsage>,
+    ) {
         let query = language.usages_query();
+        let is_config = matches!(
+            language.id(),
+            LanguageId::Json | LanguageId::Yaml | LanguageId::Toml
+        );
+        // Configuration keys also match their full dotted path
+        let match_key_paths = is_config && self.symbol.contains('.');
 
         let mut cursor = tree_sitter::QueryCursor::new();
         let mut matches = cursor.matches(query, tree.root_node(), source_code.as_bytes());
@@ -181,12 +296,19 @@ impl ResultCollector for UsageCollector {
 
                 if *capture_name == "usage" {
                     let node = capture.node;
-                    let usage_name = node
-                        .utf8_text(source_code.as_bytes())
-                        .unwrap_or("")
-                        .to_string();
+                    // Quoted configuration keys match without their quotes
+                    let usage_name = if is_config {
+                        key_text(node, source_code)
+                    } else {
+                        node.utf8_text(source_code.as_bytes())
+                            .unwrap_or("")
+                            .to_string()
+                    };
 
-                    if usage_name != self.symbol {
+                    if usage_name != self.symbol
+                        && !(match_key_paths
+                            && key_path(node, source_code).as_deref() == Some(&self.symbol))
+                    {
                         continue;
                     }
 
@@ -197,7 +319,7 @@ impl ResultCollector for UsageCollector {
                         continue;
                     }
 
-                    let is_import = is_in_import_statement(node);
+                    let is_import = is_in_import_statement(node, language.id());
                     if !self.include_imports && is_import {
                         continue;
                     }
@@ -234,8 +356,6 @@ impl ResultCollector for UsageCollector {
                 }
             }
         }
-
-        Ok(usages)
     }
 }
 
@@ -302,9 +422,13 @@ impl ResultCollector for ImportCollector {
     }
 }
 
-/// Collector for comments containing specific text
+/// Collector for comments matching a text pattern
 pub struct CommentCollector {
-    pub text: String,
+    pub matcher: TextMatcher,
+    /// Report one result per match, positioned at the match
+    pub match_positions: bool,
+    /// Masks secrets in the returned comment content
+    pub redactor: Option<Arc<Redactor>>,
 }
 
 impl ResultCollector for CommentCollector {
@@ -312,23 +436,285 @@ impl ResultCollector for CommentCollector {
 
     fn process_file(
         &self,
-        _parser: &mut CachedParser,
+        parser: &mut CachedParser,
         path: &Path,
-        _cached_content: &CachedContent,
+        cached_content: &CachedContent,
     ) -> Result<Vec<Self::Item>> {
-        let ext = path.extension().and_then(|ext| ext.to_str());
+        let source_code = &cached_content.content;
+        let (tree, language) =
+            parser.parse_with_language(path, source_code, cached_content.modified_time)?;
 
-        match ext {
+        let injections = match language.id() {
+            LanguageId::Markdown => Vec::new(),
+            _ => parser.parse_injections(&tree, language.as_ref(), source_code),
+        };
+        // Secrets are found once per file, including high-entropy literals
+        // in embedded code, and masked in each returned comment
+        let secrets = self.redactor.as_ref().map(|redactor| {
+            redactor.find(
+                source_code,
+                std::iter::once(&tree).chain(injections.iter().map(|injection| &injection.tree)),
+            )
+        });
+
+        let comments = match language.id() {
             // Markdown files: full text search (no comment syntax)
-            Some("md" | "mdc") => find_text_in_markdown_file(path, &self.text),
-            // SQL files: -- and /* */ comments
-            Some("sql") => find_comments_in_sql_file(path, &self.text),
-            // Other files: // and /* */ comments
-            _ => find_comments_in_file(path, &self.text),
+            LanguageId::Markdown => find_text_in_markdown_source(
+                path,
+                source_code,
+                &self.matcher,
+                self.match_positions,
+                secrets.as_ref(),
+            ),
+            // Other files: comment nodes from the language's comments query,
+            // including comments in embedded code (e.g., <script> in HTML)
+            _ => {
+                let mut comments = find_comments_in_tree(
+                    &tree,
+                    source_code,
+                    language.comments_query(),
+                    path,
+                    &self.matcher,
+                    self.match_positions,
+                    secrets.as_ref(),
+                );
+                for injection in &injections {
+                    comments.extend(find_comments_in_tree(
+                        &injection.tree,
+                        source_code,
+                        injection.language.comments_query(),
+                        path,
+                        &self.matcher,
+                        self.match_positions,
+                        secrets.as_ref(),
+                    ));
+                }
+                comments.sort_by_key(|comment| comment.line);
+                comments
+            }
+        };
+
+        Ok(comments)
+    }
+}
+
+/// Collector for raw tree-sitter query matches
+///
+/// The query is compiled against a single language's grammar, so files in
+/// any other language are skipped.
+pub struct AstQueryCollector {
+    pub language: LanguageId,
+    pub query: Query,
+    /// Masks secrets in the captured text
+    pub redactor: Option<Arc<Redactor>>,
+}
+
+impl ResultCollector for AstQueryCollector {
+    type Item = QueryMatch;
+
+    fn process_file(
+        &self,
+        parser: &mut CachedParser,
+        path: &Path,
+        cached_content: &CachedContent,
+    ) -> Result<Vec<Self::Item>> {
+        let source_code = &cached_content.content;
+        let (tree, language) =
+            parser.parse_with_language(path, source_code, cached_content.modified_time)?;
+
+        if language.id() != self.language {
+            return Ok(Vec::new());
+        }
+
+        let secrets = self
+            .redactor
+            .as_ref()
+            .map(|redactor| redactor.find(source_code, [&tree]));
+        Ok(run_query(
+            &tree,
+            source_code,
+            &self.query,
+            path,
+            secrets.as_ref(),
+        ))
+    }
+}
+
+/// Collector for code pattern matches
+///
+/// Like [`AstQueryCollector`], the pattern is compiled for a single language.
+pub struct PatternCollector {
+    pub language: LanguageId,
+    pub pattern: Pattern,
+    /// Masks secrets in the matched text and metavariables
+    pub redactor: Option<Arc<Redactor>>,
+}
+
+impl ResultCollector for PatternCollector {
+    type Item = PatternMatch;
+
+    fn process_file(
+        &self,
+        parser: &mut CachedParser,
+        path: &Path,
+        cached_content: &CachedContent,
+    ) -> Result<Vec<Self::Item>> {
+        let source_code = &cached_content.content;
+        let (tree, language) =
+            parser.parse_with_language(path, source_code, cached_content.modified_time)?;
+
+        if language.id() != self.language {
+            return Ok(Vec::new());
         }
+
+        let secrets = self
+            .redactor
+            .as_ref()
+            .map(|redactor| redactor.find(source_code, [&tree]));
+        Ok(self
+            .pattern
+            .find_matches(&tree, source_code, path, secrets.as_ref()))
     }
 }
 
+/// Collector for lint diagnostics from custom rules
+pub struct LintCollector {
+    pub rules: Vec<CompiledRule>,
+    /// Workspace root that rule `paths` globs are relative to
+    pub root: PathBuf,
+    /// Masks secrets in the matched text and fixes
+    pub redactor: Option<Arc<Redactor>>,
+}
+
+impl ResultCollector for LintCollector {
+    type Item = Diagnostic;
+
+    fn process_file(
+        &self,
+        parser: &mut CachedParser,
+        path: &Path,
+        cached_content: &CachedContent,
+    ) -> Result<Vec<Self::Item>> {
+        lint_file(
+            &self.rules,
+            &self.root,
+            parser,
+            path,
+            cached_content,
+            self.redactor.as_deref(),
+        )
+    }
+}
+
+/// Collector for fixes from custom rules with rewrite templates
+///
+/// Produces at most one [`FileFix`] per file, in preview status.
+pub struct FixCollector {
+    pub rules: Vec<CompiledRule>,
+    /// Workspace root that rule `paths` globs are relative to
+    pub root: PathBuf,
+    /// Masks secrets in the diff; the fixed content is never masked
+    pub redactor: Option<Arc<Redactor>>,
+}
+
+impl ResultCollector for FixCollector {
+    type Item = FileFix;
+
+    fn process_file(
+        &self,
+        parser: &mut CachedParser,
+        path: &Path,
+        cached_content: &CachedContent,
+    ) -> Result<Vec<Self::Item>> {
+        let diagnostics = lint_file(&self.rules, &self.root, parser, path, cached_content, None)?;
+        if diagnostics.iter().all(|d| d.fix.is_none()) {
+            return Ok(Vec::new());
+        }
+
+        let source_code = &cached_content.content;
+        let file_path = path.to_string_lossy().to_string();
+        let (fixed, applied, skipped) = apply_fixes(source_code, &diagnostics, None);
+        // Templates that reproduce the matched code leave nothing to write
+        if fixed == **source_code {
+            return Ok(Vec::new());
+        }
+
+        let relative_path = path.strip_prefix(&self.root).unwrap_or(path);
+        let relative_path = relative_path.to_string_lossy();
+        let diff = match &self.redactor {
+            // The fixes are rendered again from masked text for the diff only
+            Some(redactor) => {
+                let masked = lint_file(
+                    &self.rules,
+                    &self.root,
+                    parser,
+                    path,
+                    cached_content,
+                    Some(redactor),
+                )?;
+                let tree = parser.parse(path, source_code, cached_content.modified_time)?;
+                let secrets = redactor.find(source_code, [&tree]);
+                let (masked_fixed, _, _) = apply_fixes(source_code, &masked, Some(&secrets));
+                unified_diff(
+                    &relative_path,
+                    &secrets.mask(0..source_code.len()),
+                    &masked_fixed,
+                )
+            }
+            None => unified_diff(&relative_path, source_code, &fixed),
+        };
+
+        Ok(vec![FileFix {
+            diff,
+            file_path,
+            content_hash: content_hash(source_code),
+            applied,
+            skipped,
+            status: FixStatus::Preview,
+            fixed_content: fixed,
+        }])
+    }
+}
+
+/// Run the rules that apply to a file, sorted by position
+///
+/// Secrets found by the redactor are masked in the text and fixes.
+fn lint_file(
+    rules: &[CompiledRule],
+    root: &Path,
+    parser: &mut CachedParser,
+    path: &Path,
+    cached_content: &CachedContent,
+    redactor: Option<&Redactor>,
+) -> Result<Vec<Diagnostic>> {
+    // Avoid parsing files that no rule applies to
+    let Some(language_id) = parser.registry().get_for_path(path).map(|l| l.id()) else {
+        return Ok(Vec::new());
+    };
+    let relative_path = path.strip_prefix(root).unwrap_or(path);
+    let rules: Vec<&CompiledRule> = rules
+        .iter()
+        .filter(|rule| rule.applies_to(language_id, relative_path))
+        .collect();
+    if rules.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let source_code = &cached_content.content;
+    let tree = parser.parse(path, source_code, cached_content.modified_time)?;
+    let secrets = redactor.map(|redactor| redactor.find(source_code, [&tree]));
+
+    let mut diagnostics: Vec<Diagnostic> = rules
+        .iter()
+        .flat_map(|rule| rule.check(&tree, source_code, path, secrets.as_ref()))
+        .collect();
+    diagnostics.sort_by(|a, b| {
+        (a.start_line, a.start_column, &a.rule_id).cmp(&(b.start_line, b.start_column, &b.rule_id))
+    });
+
+    Ok(diagnostics)
+}
+
 /// Collector for codebase statistics
 pub struct StatsCollector;
 
@@ -352,47 +738,16 @@ impl ResultCollector for StatsCollector {
             count_lines(source_code, language_id);
 
         // Count symbols
-        let mut symbol_counts: HashMap<SymbolKind, usize> = HashMap::new();
-        let query = language.definitions_query();
-        let mappings = language.definition_mappings();
-
-        let mut cursor = tree_sitter::QueryCursor::new();
-        let mut matches = cursor.matches(query, tree.root_node(), source_code.as_bytes());
-
-        // Track seen definitions to avoid duplicates
-        let mut seen: HashSet<(usize, usize, String)> = HashSet::new();
-
-        while let Some(m) = matches.next() {
-            let mut name: Option<&str> = None;
-            let mut definition_node: Option<tree_sitter::Node> = None;
-            let mut kind: Option<SymbolKind> = None;
-
-            for capture in m.captures {
-                let capture_name = &query.capture_names()[capture.index as usize];
-
-                if *capture_name == "name" {
-                    name = Some(capture.node.utf8_text(source_code.as_bytes()).unwrap_or(""));
-                } else {
-                    for mapping in mappings {
-                        if *capture_name == mapping.capture_name {
-                            definition_node = Some(capture.node);
-                            kind = Some(mapping.kind);
-                            break;
-                        }
-                    }
+        let symbol_counts = match language.scan_definitions(source_code) {
+            Some(scanned) => {
+                let mut symbol_counts: HashMap<SymbolKind, usize> = HashMap::new();
+                for definition in scanned {
+                    *symbol_counts.entry(definition.kind).or_insert(0) += 1;
                 }
+                symbol_counts
             }
-
-            if let (Some(name_str), Some(node), Some(symbol_kind)) = (name, definition_node, kind) {
-                let start_line = node.start_position().row + 1;
-                let end_line = node.end_position().row + 1;
-
-                let key = (start_line, end_line, name_str.to_string());
-                if seen.insert(key) {
-                    *symbol_counts.entry(symbol_kind).or_insert(0) += 1;
-                }
-            }
-        }
+            None => count_definitions(&tree, language.as_ref(), source_code),
+        };
 
         Ok(vec![FileStatistics {
             file_path: path.to_string_lossy().to_string(),
@@ -406,20 +761,61 @@ impl ResultCollector for StatsCollector {
     }
 }
 
-// Helper functions moved from usage.rs
+/// Count the definitions of each kind found by a language's definitions query
+fn count_definitions(
+    tree: &tree_sitter::Tree,
+    language: &dyn LanguageSupport,
+    source_code: &str,
+) -> HashMap<SymbolKind, usize> {
+    let mut symbol_counts: HashMap<SymbolKind, usize> = HashMap::new();
+    let query = language.definitions_query();
+    let mappings = language.definition_mappings();
+
+    let mut cursor = tree_sitter::QueryCursor::new();
+    let mut matches = cursor.matches(query, tree.root_node(), source_code.as_bytes());
+
+    // Track seen definitions to avoid duplicates
+    let mut seen: HashSet<(usize, usize, String)> = HashSet::new();
+
+    while let Some(m) = matches.next() {
+        let mut name: Option<&str> = None;
+        let mut definition_node: Option<tree_sitter::Node> = None;
+        let mut kind: Option<SymbolKind> = None;
+
+        for capture in m.captures {
+            let capture_name = &query.capture_names()[capture.index as usize];
+
+            if *capture_name == "name" {
+                name = Some(capture.node.utf8_text(source_code.as_bytes()).unwrap_or(""));
+            } else {
+                for mapping in mappings {
+                    if *capture_name == mapping.capture_name {
+                        definition_node = Some(capture.node);
+                        kind = Some(mapping.kind);
+                        break;
+                    }
+                }
+            }
+        }
 
-fn is_in_import_statement(node: tree_sitter::Node) -> bool {
-    let mut current = node;
-    while let Some(parent) = current.parent() {
-        let kind = parent.kind();
-        if kind == "import_statement" || kind == "import_specifier" || kind == "import_clause" {
-            return true;
+        if let (Some(name_str), Some(node), Some(symbol_kind)) = (name, definition_node, kind) {
+            let start_line = node.start_position().row + 1;
+            let end_line = node.end_position().row + 1;
+
+            let key_path = definition_key_path(symbol_kind, node, source_code);
+            let name_str = key_path.as_deref().unwrap_or(name_str);
+            let key = (start_line, end_line, name_str.to_string());
+            if seen.insert(key) {
+                *symbol_counts.entry(symbol_kind).or_insert(0) += 1;
+            }
         }
-        current = parent;
     }
-    false
+
+    symbol_counts
 }
 
+// Helper functions moved from usage.rs
+
 fn extract_member_access_info(
     node: tree_sitter::Node,
     source: &str,
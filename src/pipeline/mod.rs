@@ -1,13 +1,18 @@
 mod collectors;
+mod order;
+mod progress;
 pub mod stats;
 
 pub use collectors::{
-    CommentCollector, DefinitionCollector, ImportCollector, MethodCallCollector, ResultCollector,
-    StatsCollector, UsageCollector,
+    AstQueryCollector, CommentCollector, DefinitionCollector, FixCollector, ImportCollector,
+    LintCollector, MethodCallCollector, PatternCollector, ResultCollector, StatsCollector,
+    UsageCollector,
 };
+pub use order::{sort_by_location, Located};
+pub use progress::ScanProgress;
 pub use stats::{aggregate_statistics, FileStatistics};
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use ignore::WalkBuilder;
@@ -29,6 +34,7 @@ pub struct FilePipeline {
     additional_excludes: Option<Vec<String>>,
     language_filter: Option<LanguageId>,
     cache_manager: Arc<CacheManager>,
+    progress: Option<Arc<ScanProgress>>,
 }
 
 impl FilePipeline {
@@ -45,6 +51,7 @@ impl FilePipeline {
             additional_excludes: None,
             language_filter: None,
             cache_manager,
+            progress: None,
         }
     }
 
@@ -57,7 +64,13 @@ impl FilePipeline {
     ///
     /// If set, only files matching the specified language will be processed.
     pub fn with_language_filter(mut self, language: Option<String>) -> Self {
-        self.language_filter = language.and_then(|lang| parse_language_id(&lang));
+        self.language_filter = language.and_then(|lang| self.registry.language_id(&lang));
+        self
+    }
+
+    /// Report progress to, and stop when cancelled through, the given state
+    pub fn with_progress(mut self, progress: Option<Arc<ScanProgress>>) -> Self {
+        self.progress = progress;
         self
     }
 
@@ -72,6 +85,12 @@ impl FilePipeline {
         for entry in walker.flatten() {
             let path = entry.path();
             if path.is_file() && self.registry.is_supported(path) {
+                // Never read files on the deny list, e.g. credentials.json
+                let relative = path.strip_prefix(&self.workspace_root).unwrap_or(path);
+                if self.config.is_denied_file(relative) {
+                    continue;
+                }
+
                 // Check exclusions
                 if !self
                     .config
@@ -90,6 +109,8 @@ impl FilePipeline {
             }
         }
 
+        // Walk order depends on the file system
+        files.sort();
         files
     }
 
@@ -112,47 +133,69 @@ impl FilePipeline {
         T: Send,
     {
         let files = self.get_files();
+        let progress = self.progress.as_deref();
+        if let Some(progress) = progress {
+            progress.add_total(files.len());
+        }
 
         files
             .par_iter()
             .filter_map(|file_path| {
-                // Read file content using shared cache
-                let source_code = match self.cache_manager.file_cache.get_or_read(file_path) {
-                    Ok(content) => content,
-                    Err(e) => {
-                        tracing::warn!("Failed to read {:?}: {}", file_path, e);
-                        return None;
-                    }
-                };
-
-                // Each thread needs its own Parser instance because tree-sitter's
-                // Parser requires mutable access and is not thread-safe.
-                let mut parser = match CachedParser::new(
-                    self.registry.clone(),
-                    self.cache_manager.parser_cache.clone(),
-                ) {
-                    Ok(p) => p,
-                    Err(e) => {
-                        tracing::warn!("Failed to create parser: {}", e);
-                        return None;
-                    }
-                };
-
-                match collector.process_file(&mut parser, file_path, &source_code) {
-                    Ok(items) => Some(items),
-                    Err(e) => {
-                        tracing::warn!("Failed to process {:?}: {}", file_path, e);
-                        None
-                    }
+                // Rayon cannot stop early, so files left after a
+                // cancellation are skipped instead
+                if progress.is_some_and(ScanProgress::is_cancelled) {
+                    return None;
+                }
+                let items = self.process_file(collector, file_path);
+                if let Some(progress) = progress {
+                    progress.file_scanned(items.as_ref().map_or(0, Vec::len));
                 }
+                items
             })
             .flatten()
             .collect()
     }
+
+    /// Process one file, logging failures
+    fn process_file<C, T>(&self, collector: &C, file_path: &Path) -> Option<Vec<T>>
+    where
+        C: ResultCollector<Item = T> + Sync,
+        T: Send,
+    {
+        // Read file content using shared cache
+        let source_code = match self.cache_manager.file_cache.get_or_read(file_path) {
+            Ok(content) => content,
+            Err(e) => {
+                tracing::warn!("Failed to read {:?}: {}", file_path, e);
+                return None;
+            }
+        };
+
+        // Each thread needs its own Parser instance because tree-sitter's
+        // Parser requires mutable access and is not thread-safe.
+        let mut parser = match CachedParser::new(
+            self.registry.clone(),
+            self.cache_manager.parser_cache.clone(),
+        ) {
+            Ok(p) => p,
+            Err(e) => {
+                tracing::warn!("Failed to create parser: {}", e);
+                return None;
+            }
+        };
+
+        match collector.process_file(&mut parser, file_path, &source_code) {
+            Ok(items) => Some(items),
+            Err(e) => {
+                tracing::warn!("Failed to process {:?}: {}", file_path, e);
+                None
+            }
+        }
+    }
 }
 
 /// Parse a language name string to LanguageId
-fn parse_language_id(name: &str) -> Option<LanguageId> {
+pub fn parse_language_id(name: &str) -> Option<LanguageId> {
     match name.to_lowercase().as_str() {
         "typescript" | "ts" => Some(LanguageId::TypeScript),
         "typescriptreact" | "tsx" => Some(LanguageId::TypeScriptReact),
@@ -166,6 +209,23 @@ fn parse_language_id(name: &str) -> Option<LanguageId> {
         "go" => Some(LanguageId::Go),
         "java" => Some(LanguageId::Java),
         "sql" => Some(LanguageId::Sql),
+        "c" => Some(LanguageId::C),
+        "cpp" | "c++" => Some(LanguageId::Cpp),
+        "csharp" | "c#" | "cs" => Some(LanguageId::CSharp),
+        "ruby" | "rb" => Some(LanguageId::Ruby),
+        "php" => Some(LanguageId::Php),
+        "swift" => Some(LanguageId::Swift),
+        "kotlin" | "kt" => Some(LanguageId::Kotlin),
+        "scala" => Some(LanguageId::Scala),
+        "bash" | "sh" | "shell" => Some(LanguageId::Bash),
+        "json" => Some(LanguageId::Json),
+        "yaml" | "yml" => Some(LanguageId::Yaml),
+        "toml" => Some(LanguageId::Toml),
+        "vue" => Some(LanguageId::Vue),
+        "svelte" => Some(LanguageId::Svelte),
+        "astro" => Some(LanguageId::Astro),
+        "dockerfile" | "docker" => Some(LanguageId::Dockerfile),
+        "makefile" | "make" => Some(LanguageId::Makefile),
         _ => None,
     }
 }
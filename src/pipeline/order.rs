@@ -0,0 +1,101 @@
+//! Ordering of results
+//!
+//! Files are processed in parallel, so results are sorted by their location
+//! before being returned to get the same order, and the same pages, on every
+//! call.
+
+use crate::query::types::{Diagnostic, PatternMatch, QueryMatch};
+use crate::symbol::types::{CommentMatch, SymbolDefinition, SymbolUsage};
+
+/// A result at a location in a file
+pub trait Located {
+    /// File path, line (1-indexed) and column (0-indexed) the result starts at
+    fn location(&self) -> (&str, usize, usize);
+}
+
+/// Sort results by file path, then line and column
+///
+/// The sort is stable, so results at the same location keep their order.
+pub fn sort_by_location<T: Located>(results: &mut [T]) {
+    results.sort_by(|a, b| a.location().cmp(&b.location()));
+}
+
+impl Located for SymbolDefinition {
+    fn location(&self) -> (&str, usize, usize) {
+        (&self.file_path, self.start_line, 0)
+    }
+}
+
+impl Located for SymbolUsage {
+    fn location(&self) -> (&str, usize, usize) {
+        (&self.file_path, self.line, self.column)
+    }
+}
+
+impl Located for CommentMatch {
+    fn location(&self) -> (&str, usize, usize) {
+        (&self.file_path, self.line, self.column)
+    }
+}
+
+impl Located for QueryMatch {
+    fn location(&self) -> (&str, usize, usize) {
+        // Captures are in document order
+        match self.captures.first() {
+            Some(capture) => (&self.file_path, capture.start_line, capture.start_column),
+            None => (&self.file_path, 0, 0),
+        }
+    }
+}
+
+impl Located for PatternMatch {
+    fn location(&self) -> (&str, usize, usize) {
+        (&self.file_path, self.start_line, self.start_column)
+    }
+}
+
+impl Located for Diagnostic {
+    fn location(&self) -> (&str, usize, usize) {
+        (&self.file_path, self.start_line, self.start_column)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::symbol::types::UsageKind;
+
+    fn usage(file_path: &str, line: usize, column: usize) -> SymbolUsage {
+        SymbolUsage {
+            file_path: file_path.to_string(),
+            line,
+            column,
+            qualified_name: "foo".to_string(),
+            usage_kind: UsageKind::Identifier,
+            object_name: None,
+            contexts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_sort_by_location() {
+        let mut usages = vec![
+            usage("src/b.ts", 1, 0),
+            usage("src/a.ts", 10, 4),
+            usage("src/a.ts", 2, 8),
+            usage("src/a.ts", 10, 0),
+        ];
+        sort_by_location(&mut usages);
+
+        let locations: Vec<_> = usages.iter().map(Located::location).collect();
+        assert_eq!(
+            locations,
+            [
+                ("src/a.ts", 2, 8),
+                ("src/a.ts", 10, 0),
+                ("src/a.ts", 10, 4),
+                ("src/b.ts", 1, 0)
+            ]
+        );
+    }
+}
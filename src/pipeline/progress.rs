@@ -0,0 +1,56 @@
+//! Progress and cancellation of a scan
+//!
+//! A scan runs on rayon threads while the server reports its progress and
+//! listens for cancellation, so the state is shared through atomics.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// Progress of a scan over one or more pipelines
+#[derive(Debug, Default)]
+pub struct ScanProgress {
+    /// Files to scan, growing as each pipeline lists its files
+    total: AtomicUsize,
+    /// Files scanned so far, including skipped ones
+    scanned: AtomicUsize,
+    /// Results found so far
+    found: AtomicUsize,
+    cancelled: AtomicBool,
+}
+
+impl ScanProgress {
+    /// Add files to scan
+    pub fn add_total(&self, files: usize) {
+        self.total.fetch_add(files, Ordering::Relaxed);
+    }
+
+    /// Record a scanned file and the results found in it
+    pub fn file_scanned(&self, results: usize) {
+        self.found.fetch_add(results, Ordering::Relaxed);
+        self.scanned.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Files to scan
+    pub fn total(&self) -> usize {
+        self.total.load(Ordering::Relaxed)
+    }
+
+    /// Files scanned so far
+    pub fn scanned(&self) -> usize {
+        self.scanned.load(Ordering::Relaxed)
+    }
+
+    /// Results found so far
+    pub fn found(&self) -> usize {
+        self.found.load(Ordering::Relaxed)
+    }
+
+    /// Stop the scan; files not yet started are skipped
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the scan was cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
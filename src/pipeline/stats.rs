@@ -123,7 +123,13 @@ pub fn count_lines(source: &str, language_id: LanguageId) -> (usize, usize, usiz
 /// Get the single-line comment prefix for a language
 fn get_single_line_comment_prefix(language_id: LanguageId) -> &'static str {
     match language_id {
-        LanguageId::Python => "#",
+        LanguageId::Python
+        | LanguageId::Ruby
+        | LanguageId::Bash
+        | LanguageId::Yaml
+        | LanguageId::Toml
+        | LanguageId::Dockerfile
+        | LanguageId::Makefile => "#",
         LanguageId::Sql => "--",
         // C-style languages: //, also used for HTML (<!-- is handled differently)
         LanguageId::TypeScript
@@ -133,10 +139,20 @@ fn get_single_line_comment_prefix(language_id: LanguageId) -> &'static str {
         | LanguageId::Rust
         | LanguageId::Go
         | LanguageId::Java
-        | LanguageId::Css => "//",
+        | LanguageId::C
+        | LanguageId::Cpp
+        | LanguageId::CSharp
+        | LanguageId::Php
+        | LanguageId::Swift
+        | LanguageId::Kotlin
+        | LanguageId::Scala
+        | LanguageId::Json
+        | LanguageId::Css
+        // Plugin grammars do not declare a comment syntax; assume C-style
+        | LanguageId::Plugin(_) => "//",
         // HTML and Markdown don't have traditional single-line comments
-        // For HTML we use a prefix that won't match normal lines
-        LanguageId::Html => "<!--",
+        // For HTML and components we use a prefix that won't match normal lines
+        LanguageId::Html | LanguageId::Vue | LanguageId::Svelte | LanguageId::Astro => "<!--",
         // Markdown has no comments, use a non-matching prefix
         LanguageId::Markdown => "<!---",
     }
@@ -196,6 +212,7 @@ pub fn aggregate_statistics(file_stats: Vec<FileStatistics>) -> StatsResponse {
         .collect();
 
     // Sort by code lines descending
+    #[allow(clippy::unnecessary_sort_by)]
     language_stats.sort_by(|a, b| b.code_lines.cmp(&a.code_lines));
 
     let languages_count = language_stats.len();
@@ -0,0 +1,169 @@
+//! Structural query engine
+//!
+//! Compiles user-supplied tree-sitter queries against a registered language
+//! grammar and runs them over parsed syntax trees.
+
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use streaming_iterator::StreamingIterator;
+use tree_sitter::{Query, Tree};
+
+use crate::language::LanguageSupport;
+use crate::query::types::{QueryCapture, QueryMatch};
+use crate::symbol::redact::{source_text, Secrets};
+
+/// Compile a tree-sitter query for the given language
+///
+/// The error message includes the position of the offending pattern and the
+/// pattern text itself, so it can be returned to the caller as-is.
+pub fn compile_query(language: &dyn LanguageSupport, source: &str) -> Result<Query> {
+    Query::new(language.tree_sitter_language(), source).map_err(|e| {
+        let line = source.lines().nth(e.row).unwrap_or("").trim();
+        anyhow!(
+            "Invalid {} query at line {}, column {}: {} ({:?}) in `{}`",
+            language.name(),
+            e.row + 1,
+            e.column,
+            e.message,
+            e.kind,
+            line
+        )
+    })
+}
+
+/// Run a compiled query over a syntax tree and collect its matches
+///
+/// Text predicates such as `#eq?` and `#match?` are evaluated by tree-sitter
+/// while matching. Matches without any captures are skipped. Captured text
+/// has the given secrets masked.
+pub fn run_query(
+    tree: &Tree,
+    source: &str,
+    query: &Query,
+    file_path: &Path,
+    secrets: Option<&Secrets>,
+) -> Vec<QueryMatch> {
+    let file_path_str = file_path.to_string_lossy().to_string();
+    let capture_names = query.capture_names();
+    let mut results = Vec::new();
+
+    let mut cursor = tree_sitter::QueryCursor::new();
+    let mut matches = cursor.matches(query, tree.root_node(), source.as_bytes());
+
+    while let Some(m) = matches.next() {
+        if m.captures.is_empty() {
+            continue;
+        }
+
+        let mut captures: Vec<QueryCapture> = m
+            .captures
+            .iter()
+            .map(|capture| {
+                let node = capture.node;
+                QueryCapture {
+                    name: capture_names[capture.index as usize].to_string(),
+                    node_kind: node.kind().to_string(),
+                    start_line: node.start_position().row + 1,
+                    start_column: node.start_position().column,
+                    end_line: node.end_position().row + 1,
+                    end_column: node.end_position().column,
+                    text: source_text(source, node.byte_range(), secrets),
+                }
+            })
+            .collect();
+        captures.sort_by_key(|c| (c.start_line, c.start_column));
+
+        results.push(QueryMatch {
+            file_path: file_path_str.clone(),
+            pattern_index: m.pattern_index,
+            captures,
+        });
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    use crate::language::{LanguageId, LanguageRegistry};
+    use crate::parser::GenericParser;
+
+    fn run(file_name: &str, source: &str, query_src: &str) -> Result<Vec<QueryMatch>> {
+        let registry = Arc::new(LanguageRegistry::new().unwrap());
+        let mut parser = GenericParser::new(registry).unwrap();
+        let path = PathBuf::from(file_name);
+        let (tree, language) = parser.parse_with_language(&path, source).unwrap();
+        let query = compile_query(language.as_ref(), query_src)?;
+        Ok(run_query(&tree, source, &query, &path, None))
+    }
+
+    #[test]
+    fn test_run_query_captures() {
+        let source = "fn main() {\n    let x = foo().unwrap();\n}\n";
+        let query = r#"(call_expression
+  function: (field_expression
+    field: (field_identifier) @method (#eq? @method "unwrap"))) @call"#;
+
+        let matches = run("test.rs", source, query).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].pattern_index, 0);
+
+        let call = &matches[0].captures[0];
+        assert_eq!(call.name, "call");
+        assert_eq!(call.node_kind, "call_expression");
+        assert_eq!(call.text, "foo().unwrap()");
+        assert_eq!((call.start_line, call.start_column), (2, 12));
+        assert_eq!((call.end_line, call.end_column), (2, 26));
+
+        let method = &matches[0].captures[1];
+        assert_eq!(method.name, "method");
+        assert_eq!(method.text, "unwrap");
+    }
+
+    #[test]
+    fn test_run_query_nested_structure() {
+        let source = r#"struct A;
+impl Drop for A {
+    fn drop(&mut self) { self.x.take().unwrap(); }
+}
+impl A {
+    fn other(&self) { self.y.unwrap(); }
+}
+"#;
+        let query = r#"(impl_item
+  trait: (type_identifier) @trait (#eq? @trait "Drop")
+  body: (declaration_list
+    (function_item
+      body: (block
+        (expression_statement
+          (call_expression
+            function: (field_expression
+              field: (field_identifier) @method (#eq? @method "unwrap"))) @call)))))"#;
+
+        let matches = run("test.rs", source, query).unwrap();
+        assert_eq!(matches.len(), 1);
+        let call = matches[0]
+            .captures
+            .iter()
+            .find(|c| c.name == "call")
+            .unwrap();
+        assert_eq!(call.start_line, 3);
+    }
+
+    #[test]
+    fn test_compile_query_error_message() {
+        let registry = LanguageRegistry::new().unwrap();
+        let language = registry.get(LanguageId::Rust).unwrap();
+
+        let err = compile_query(language.as_ref(), "(call_expression\n  (not_a_node) @x)")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Invalid Rust query at line 2"), "{}", err);
+        assert!(err.contains("not_a_node"), "{}", err);
+    }
+}
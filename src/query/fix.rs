@@ -0,0 +1,170 @@
+//! Automatic fixes for lint diagnostics
+//!
+//! Fixes replace the range reported by a diagnostic with the text rendered
+//! from the rule's rewrite template. Files are only written when their
+//! content still matches the hash returned by the preview, so edits made in
+//! the meantime are never overwritten.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use similar::TextDiff;
+
+use crate::query::types::{Diagnostic, FixStatus};
+use crate::symbol::redact::{source_text, Secrets};
+
+/// SHA-256 hash of file content, as lowercase hex
+pub fn content_hash(content: &str) -> String {
+    format!("{:x}", Sha256::digest(content.as_bytes()))
+}
+
+/// Apply the fixes carried by diagnostics to the source
+///
+/// Returns the fixed source, the number of fixes applied and the number of
+/// fixes skipped because they overlap a fix earlier in the file. The given
+/// secrets are masked in the source left unchanged, for previews.
+pub fn apply_fixes(
+    source: &str,
+    diagnostics: &[Diagnostic],
+    secrets: Option<&Secrets>,
+) -> (String, usize, usize) {
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let offset = |line: usize, column: usize| line_starts.get(line - 1).map(|start| start + column);
+
+    let mut edits: Vec<(usize, usize, &str)> = diagnostics
+        .iter()
+        .filter_map(|d| {
+            let fix = d.fix.as_deref()?;
+            let start = offset(d.start_line, d.start_column)?;
+            let end = offset(d.end_line, d.end_column)?;
+            (start <= end && end <= source.len()).then_some((start, end, fix))
+        })
+        .collect();
+    edits.sort_by_key(|(start, end, _)| (*start, *end));
+
+    let mut fixed = String::with_capacity(source.len());
+    let mut position = 0;
+    let mut applied = 0;
+    let mut skipped = 0;
+
+    for (start, end, fix) in edits {
+        if start < position {
+            skipped += 1;
+            continue;
+        }
+        fixed.push_str(&source_text(source, position..start, secrets));
+        fixed.push_str(fix);
+        position = end;
+        applied += 1;
+    }
+    fixed.push_str(&source_text(source, position..source.len(), secrets));
+
+    (fixed, applied, skipped)
+}
+
+/// Unified diff between the original and fixed content of a file
+///
+/// `relative_path` is the file's path relative to the workspace root, as
+/// used in `a/` and `b/` headers.
+pub fn unified_diff(relative_path: &str, original: &str, fixed: &str) -> String {
+    TextDiff::from_lines(original, fixed)
+        .unified_diff()
+        .context_radius(3)
+        .header(
+            &format!("a/{}", relative_path),
+            &format!("b/{}", relative_path),
+        )
+        .to_string()
+}
+
+/// Write fixed content if the file on disk still has the expected hash
+pub fn write_if_unchanged(path: &Path, fixed: &str, expected_hash: &str) -> Result<FixStatus> {
+    let current = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    if content_hash(&current) != expected_hash {
+        return Ok(FixStatus::Conflict);
+    }
+
+    std::fs::write(path, fixed).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(FixStatus::Written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Severity;
+
+    fn diagnostic(start: (usize, usize), end: (usize, usize), fix: Option<&str>) -> Diagnostic {
+        Diagnostic {
+            file_path: "test.rs".to_string(),
+            start_line: start.0,
+            start_column: start.1,
+            end_line: end.0,
+            end_column: end.1,
+            rule_id: "rule".to_string(),
+            message: "message".to_string(),
+            severity: Severity::Warning,
+            text: String::new(),
+            fix: fix.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_apply_fixes() {
+        let source = "let a = x.unwrap();\nlet b = y.unwrap();\n";
+        let diagnostics = vec![
+            diagnostic((2, 8), (2, 18), Some("y?")),
+            diagnostic((1, 8), (1, 18), Some("x?")),
+            diagnostic((1, 0), (1, 3), None),
+        ];
+
+        let (fixed, applied, skipped) = apply_fixes(source, &diagnostics, None);
+        assert_eq!(fixed, "let a = x?;\nlet b = y?;\n");
+        assert_eq!((applied, skipped), (2, 0));
+    }
+
+    #[test]
+    fn test_apply_fixes_skips_overlapping() {
+        let source = "f(g(x))";
+        let diagnostics = vec![
+            diagnostic((1, 0), (1, 7), Some("outer")),
+            diagnostic((1, 2), (1, 6), Some("inner")),
+        ];
+
+        let (fixed, applied, skipped) = apply_fixes(source, &diagnostics, None);
+        assert_eq!(fixed, "outer");
+        assert_eq!((applied, skipped), (1, 1));
+    }
+
+    #[test]
+    fn test_unified_diff() {
+        let diff = unified_diff("src/a.rs", "a\nb\nc\n", "a\nB\nc\n");
+        assert!(diff.starts_with("--- a/src/a.rs\n+++ b/src/a.rs\n"));
+        assert!(diff.contains("-b\n+B\n"));
+    }
+
+    #[test]
+    fn test_write_if_unchanged() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.rs");
+        std::fs::write(&path, "old").unwrap();
+        let hash = content_hash("old");
+
+        std::fs::write(&path, "edited elsewhere").unwrap();
+        assert_eq!(
+            write_if_unchanged(&path, "new", &hash).unwrap(),
+            FixStatus::Conflict
+        );
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "edited elsewhere");
+
+        std::fs::write(&path, "old").unwrap();
+        assert_eq!(
+            write_if_unchanged(&path, "new", &hash).unwrap(),
+            FixStatus::Written
+        );
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "new");
+    }
+}
@@ -0,0 +1,406 @@
+//! Custom lint rules
+//!
+//! Rules are declared in the `rules` section of `.mcp.json`. Each rule is
+//! compiled once per `lint` call into a [`CompiledRule`] that runs either a
+//! raw tree-sitter query or a code pattern over every file of its language.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Result};
+use tree_sitter::{Query, Tree};
+
+use crate::config::{LintRule, Severity};
+use crate::language::{LanguageId, LanguageRegistry};
+use crate::query::engine::{compile_query, run_query};
+use crate::query::pattern::{render_template, template_metavariables, Pattern};
+use crate::query::types::{Diagnostic, QueryCapture};
+use crate::symbol::redact::Secrets;
+
+/// Capture name that marks the reported range in query rules
+const MATCH_CAPTURE: &str = "match";
+
+/// How a rule finds offending code
+enum RuleMatcher {
+    Query(Query),
+    Pattern(Pattern),
+}
+
+/// A lint rule ready to run
+pub struct CompiledRule {
+    pub id: String,
+    pub language: LanguageId,
+    pub message: String,
+    pub severity: Severity,
+    pub fix: Option<String>,
+    paths: Vec<glob::Pattern>,
+    matcher: RuleMatcher,
+}
+
+impl CompiledRule {
+    /// Compile a rule from the configuration
+    ///
+    /// Errors name the offending rule so they can be returned to the caller as-is.
+    pub fn compile(rule: &LintRule, registry: &LanguageRegistry) -> Result<Self> {
+        let language_support = registry.get_by_name(&rule.language).ok_or_else(|| {
+            anyhow!(
+                "Rule '{}': unsupported language '{}'",
+                rule.id,
+                rule.language
+            )
+        })?;
+
+        let matcher = match (&rule.query, &rule.pattern) {
+            (Some(query), None) => RuleMatcher::Query(
+                compile_query(language_support.as_ref(), query)
+                    .map_err(|e| anyhow!("Rule '{}': {}", rule.id, e))?,
+            ),
+            (None, Some(pattern)) => RuleMatcher::Pattern(
+                Pattern::compile(language_support.as_ref(), pattern, &rule.constraints)
+                    .map_err(|e| anyhow!("Rule '{}': {}", rule.id, e))?,
+            ),
+            _ => bail!(
+                "Rule '{}' must define exactly one of `query` or `pattern`",
+                rule.id
+            ),
+        };
+
+        if let Some(fix) = &rule.fix {
+            let bound: Vec<&str> = match &matcher {
+                RuleMatcher::Query(query) => query.capture_names().to_vec(),
+                RuleMatcher::Pattern(pattern) => pattern.metavariables().collect(),
+            };
+            if let Some(unbound) = template_metavariables(fix)
+                .into_iter()
+                .find(|name| !bound.contains(&name.as_str()))
+            {
+                bail!(
+                    "Rule '{}': fix references unbound metavariable ${}",
+                    rule.id,
+                    unbound
+                );
+            }
+        }
+
+        let paths = rule
+            .paths
+            .iter()
+            .map(|p| {
+                glob::Pattern::new(p)
+                    .map_err(|e| anyhow!("Rule '{}': invalid path glob '{}': {}", rule.id, p, e))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            id: rule.id.clone(),
+            language: language_support.id(),
+            message: rule.message.clone(),
+            severity: rule.severity,
+            fix: rule.fix.clone(),
+            paths,
+            matcher,
+        })
+    }
+
+    /// Check whether the rule applies to a file of the given language
+    ///
+    /// `relative_path` is relative to the workspace root, so that `paths`
+    /// globs such as `src/**` match.
+    pub fn applies_to(&self, language: LanguageId, relative_path: &Path) -> bool {
+        if language != self.language {
+            return false;
+        }
+        if self.paths.is_empty() {
+            return true;
+        }
+        let path_str = relative_path.to_string_lossy();
+        self.paths.iter().any(|p| p.matches(&path_str))
+    }
+
+    /// Run the rule over a parsed file
+    ///
+    /// The reported text and fixes have the given secrets masked, so the
+    /// fixes of diagnostics that are applied must be checked without secrets.
+    pub fn check(
+        &self,
+        tree: &Tree,
+        source: &str,
+        file_path: &Path,
+        secrets: Option<&Secrets>,
+    ) -> Vec<Diagnostic> {
+        match &self.matcher {
+            RuleMatcher::Query(query) => run_query(tree, source, query, file_path, secrets)
+                .into_iter()
+                .map(|m| {
+                    let capture = reported_capture(&m.captures);
+                    let fix = self.fix.as_ref().map(|template| {
+                        let mut bindings = BTreeMap::new();
+                        for c in &m.captures {
+                            bindings
+                                .entry(c.name.clone())
+                                .or_insert_with(|| c.text.clone());
+                        }
+                        render_template(template, &bindings)
+                    });
+                    Diagnostic {
+                        file_path: m.file_path,
+                        start_line: capture.start_line,
+                        start_column: capture.start_column,
+                        end_line: capture.end_line,
+                        end_column: capture.end_column,
+                        rule_id: self.id.clone(),
+                        message: self.message.clone(),
+                        severity: self.severity,
+                        text: capture.text,
+                        fix,
+                    }
+                })
+                .collect(),
+            RuleMatcher::Pattern(pattern) => pattern
+                .find_matches(tree, source, file_path, secrets)
+                .into_iter()
+                .map(|m| Diagnostic {
+                    file_path: m.file_path,
+                    start_line: m.start_line,
+                    start_column: m.start_column,
+                    end_line: m.end_line,
+                    end_column: m.end_column,
+                    rule_id: self.id.clone(),
+                    message: self.message.clone(),
+                    severity: self.severity,
+                    fix: self
+                        .fix
+                        .as_ref()
+                        .map(|template| render_template(template, &m.metavariables)),
+                    text: m.text,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Pick the capture reported for a query match
+///
+/// Prefers the `@match` capture; otherwise the outermost capture, i.e. the
+/// one that starts first and ends last.
+fn reported_capture(captures: &[QueryCapture]) -> QueryCapture {
+    if let Some(capture) = captures.iter().find(|c| c.name == MATCH_CAPTURE) {
+        return capture.clone();
+    }
+    captures
+        .iter()
+        .min_by_key(|c| {
+            (
+                c.start_line,
+                c.start_column,
+                std::cmp::Reverse((c.end_line, c.end_column)),
+            )
+        })
+        .cloned()
+        .expect("run_query never returns matches without captures")
+}
+
+/// Compile all configured rules, optionally keeping only the given ids
+pub fn compile_rules(
+    rules: &[LintRule],
+    only: Option<&[String]>,
+    registry: &LanguageRegistry,
+) -> Result<Vec<CompiledRule>> {
+    if let Some(ids) = only {
+        if let Some(unknown) = ids.iter().find(|id| !rules.iter().any(|r| &r.id == *id)) {
+            bail!("Unknown rule id '{}'", unknown);
+        }
+    }
+
+    rules
+        .iter()
+        .filter(|rule| only.is_none_or(|ids| ids.contains(&rule.id)))
+        .map(|rule| CompiledRule::compile(rule, registry))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::GenericParser;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    fn rule(id: &str, language: &str) -> LintRule {
+        LintRule {
+            id: id.to_string(),
+            language: language.to_string(),
+            query: None,
+            pattern: None,
+            constraints: HashMap::new(),
+            message: format!("{} violated", id),
+            severity: Severity::Warning,
+            paths: Vec::new(),
+            fix: None,
+        }
+    }
+
+    fn check(rule: &LintRule, source: &str, path: &str) -> Vec<Diagnostic> {
+        let registry = Arc::new(LanguageRegistry::new().unwrap());
+        let compiled = CompiledRule::compile(rule, &registry).unwrap();
+        let mut parser = GenericParser::new(registry).unwrap();
+        let path = PathBuf::from(path);
+        let (tree, language) = parser.parse_with_language(&path, source).unwrap();
+        assert!(compiled.applies_to(language.id(), &path));
+        compiled.check(&tree, source, &path, None)
+    }
+
+    #[test]
+    fn test_pattern_rule() {
+        let mut no_date_now = rule("no-date-now", "typescript");
+        no_date_now.pattern = Some("Date.now()".to_string());
+        no_date_now.severity = Severity::Error;
+
+        let source = "const a = Date.now();\n// Date.now()\nconst b = now();\n";
+        let diagnostics = check(&no_date_now, source, "/src/domain/a.ts");
+
+        assert_eq!(diagnostics.len(), 1);
+        let d = &diagnostics[0];
+        assert_eq!(d.rule_id, "no-date-now");
+        assert_eq!(d.message, "no-date-now violated");
+        assert_eq!(d.severity, Severity::Error);
+        assert_eq!((d.start_line, d.start_column), (1, 10));
+        assert_eq!((d.end_line, d.end_column), (1, 20));
+        assert_eq!(d.text, "Date.now()");
+    }
+
+    #[test]
+    fn test_query_rule_reports_match_capture() {
+        let mut no_unwrap = rule("no-unwrap", "rust");
+        no_unwrap.query = Some(
+            r#"(call_expression
+  function: (field_expression
+    field: (field_identifier) @method (#eq? @method "unwrap"))) @match"#
+                .to_string(),
+        );
+
+        let source = "fn main() {\n    let x = foo().unwrap();\n}\n";
+        let diagnostics = check(&no_unwrap, source, "/src/main.rs");
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].start_line, 2);
+        assert_eq!(diagnostics[0].text, "foo().unwrap()");
+    }
+
+    #[test]
+    fn test_query_rule_without_match_capture_reports_outermost() {
+        let mut todo_fn = rule("todo-fn", "python");
+        todo_fn.query = Some("(function_definition name: (identifier) @name) @def".to_string());
+
+        let source = "def todo():\n    pass\n";
+        let diagnostics = check(&todo_fn, source, "/a.py");
+
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].text.starts_with("def todo():"));
+        assert_eq!(diagnostics[0].end_line, 2);
+    }
+
+    #[test]
+    fn test_applies_to_paths() {
+        let registry = LanguageRegistry::new().unwrap();
+        let mut scoped = rule("scoped", "typescript");
+        scoped.pattern = Some("Date.now()".to_string());
+        scoped.paths = vec!["**/domain/**".to_string()];
+        let compiled = CompiledRule::compile(&scoped, &registry).unwrap();
+
+        assert!(compiled.applies_to(LanguageId::TypeScript, Path::new("src/domain/a.ts")));
+        assert!(compiled.applies_to(LanguageId::TypeScript, Path::new("domain/a.ts")));
+        assert!(!compiled.applies_to(LanguageId::TypeScript, Path::new("src/ui/a.ts")));
+        assert!(!compiled.applies_to(LanguageId::JavaScript, Path::new("src/domain/a.js")));
+
+        let mut rooted = rule("rooted", "typescript");
+        rooted.pattern = Some("Date.now()".to_string());
+        rooted.paths = vec!["src/**".to_string()];
+        let compiled = CompiledRule::compile(&rooted, &registry).unwrap();
+
+        assert!(compiled.applies_to(LanguageId::TypeScript, Path::new("src/domain/a.ts")));
+        assert!(!compiled.applies_to(LanguageId::TypeScript, Path::new("test/a.ts")));
+    }
+
+    #[test]
+    fn test_compile_errors() {
+        let registry = LanguageRegistry::new().unwrap();
+
+        let neither = rule("neither", "rust");
+        let err = CompiledRule::compile(&neither, &registry).err().unwrap();
+        assert!(err.to_string().contains("exactly one of"));
+
+        let mut unknown_language = rule("cobol", "cobol");
+        unknown_language.pattern = Some("x".to_string());
+        let err = CompiledRule::compile(&unknown_language, &registry)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("unsupported language 'cobol'"));
+
+        let mut bad_query = rule("bad-query", "rust");
+        bad_query.query = Some("(not_a_node) @x".to_string());
+        let err = CompiledRule::compile(&bad_query, &registry).err().unwrap();
+        assert!(err.to_string().starts_with("Rule 'bad-query':"));
+    }
+
+    #[test]
+    fn test_compile_rules_filters_by_id() {
+        let registry = LanguageRegistry::new().unwrap();
+        let mut a = rule("a", "rust");
+        a.pattern = Some("$X.unwrap()".to_string());
+        let mut b = rule("b", "rust");
+        b.pattern = Some("$X.expect($Y)".to_string());
+        let rules = vec![a, b];
+
+        assert_eq!(compile_rules(&rules, None, &registry).unwrap().len(), 2);
+
+        let only = vec!["b".to_string()];
+        let compiled = compile_rules(&rules, Some(&only), &registry).unwrap();
+        assert_eq!(compiled.len(), 1);
+        assert_eq!(compiled[0].id, "b");
+
+        let unknown = vec!["c".to_string()];
+        assert!(compile_rules(&rules, Some(&unknown), &registry).is_err());
+    }
+
+    #[test]
+    fn test_pattern_rule_fix() {
+        let mut no_unwrap = rule("no-unwrap", "rust");
+        no_unwrap.pattern = Some("$X.unwrap()".to_string());
+        no_unwrap.fix = Some("$X.expect(\"TODO\")".to_string());
+
+        let source = "fn main() {\n    let x = foo(1).unwrap();\n}\n";
+        let diagnostics = check(&no_unwrap, source, "/src/main.rs");
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].fix.as_deref(),
+            Some("foo(1).expect(\"TODO\")")
+        );
+    }
+
+    #[test]
+    fn test_query_rule_fix_uses_captures() {
+        let mut rename = rule("rename-print", "python");
+        rename.query = Some(
+            "(call function: (identifier) @FN (#eq? @FN \"print\") arguments: (_) @ARGS) @match"
+                .to_string(),
+        );
+        rename.fix = Some("logger.info$ARGS".to_string());
+
+        let diagnostics = check(&rename, "print(\"hi\")\n", "/a.py");
+        assert_eq!(diagnostics[0].fix.as_deref(), Some("logger.info(\"hi\")"));
+    }
+
+    #[test]
+    fn test_fix_with_unbound_metavariable() {
+        let registry = LanguageRegistry::new().unwrap();
+        let mut bad_fix = rule("bad-fix", "rust");
+        bad_fix.pattern = Some("$X.unwrap()".to_string());
+        bad_fix.fix = Some("$Y.expect(\"TODO\")".to_string());
+
+        let err = CompiledRule::compile(&bad_fix, &registry).err().unwrap();
+        assert!(err.to_string().contains("unbound metavariable $Y"));
+    }
+}
@@ -0,0 +1,5 @@
+pub mod engine;
+pub mod fix;
+pub mod lint;
+pub mod pattern;
+pub mod types;
@@ -0,0 +1,692 @@
+//! Code patterns with metavariables
+//!
+//! A pattern is a snippet of code in the target language in which
+//! metavariables stand for arbitrary syntax nodes:
+//!
+//! - `$X` matches a single node and captures it as `X`
+//! - `$$$ARGS` matches zero or more sibling nodes and captures them as `ARGS`
+//! - `$_` and `$$$` match without capturing
+//!
+//! The snippet is parsed with the language's grammar and translated into a
+//! tree-sitter query, so `$X.unwrap()` only matches real method calls and
+//! never text inside strings or comments. A metavariable used more than once
+//! must capture identical text everywhere it appears.
+
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use anyhow::{anyhow, bail, Result};
+use regex::Regex;
+use streaming_iterator::StreamingIterator;
+use tree_sitter::{Node, Parser, Query, Tree};
+
+use crate::language::{LanguageId, LanguageSupport};
+use crate::query::engine::compile_query;
+use crate::query::types::PatternMatch;
+use crate::symbol::redact::{source_text, Secrets};
+
+/// Prefix for identifiers substituted for metavariables before parsing
+const PLACEHOLDER_PREFIX: &str = "__codescope_mv_";
+
+/// Capture name for the node matched by the whole pattern
+const MATCH_CAPTURE: &str = "__codescope_match";
+
+/// Prefix for internal captures used by text predicates
+const LITERAL_CAPTURE_PREFIX: &str = "__codescope_lit_";
+
+/// Internal capture for `$_`, so it can be kept from matching comments
+const ANONYMOUS_CAPTURE: &str = "__codescope_any";
+
+/// A metavariable found in a pattern
+#[derive(Debug, Clone)]
+struct Metavariable {
+    /// Capture name, or `None` for `$_` / `$$$`
+    name: Option<String>,
+    /// Whether this metavariable matches a sequence of nodes (`$$$`)
+    multi: bool,
+}
+
+/// A compiled code pattern
+pub struct Pattern {
+    query: Query,
+    query_source: String,
+    multi_metavariables: Vec<String>,
+    constraints: HashMap<String, Regex>,
+}
+
+impl Pattern {
+    /// Compile a pattern written in the given language
+    ///
+    /// `constraints` maps metavariable names (without `$`) to regular
+    /// expressions that the captured text must match.
+    pub fn compile(
+        language: &dyn LanguageSupport,
+        pattern: &str,
+        constraints: &HashMap<String, String>,
+    ) -> Result<Self> {
+        let pattern = pattern.trim();
+        if pattern.is_empty() {
+            bail!("Pattern must not be empty");
+        }
+
+        let (substituted, metavariables) = substitute_metavariables(pattern);
+
+        let mut compiled_constraints = HashMap::new();
+        for (name, regex) in constraints {
+            let name = name.trim_start_matches('$');
+            let known = metavariables
+                .values()
+                .any(|mv| mv.name.as_deref() == Some(name));
+            if !known {
+                bail!("Constraint refers to unknown metavariable ${}", name);
+            }
+            let regex = Regex::new(regex)
+                .map_err(|e| anyhow!("Invalid constraint for ${}: {}", name, e))?;
+            compiled_constraints.insert(name.to_string(), regex);
+        }
+
+        let mut parser = Parser::new();
+        parser.set_language(language.tree_sitter_language())?;
+
+        let (tree, source, start, end) = parse_snippet(&mut parser, language.id(), &substituted)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Pattern is not valid {} code: `{}`",
+                    language.name(),
+                    pattern
+                )
+            })?;
+
+        let root = find_pattern_node(&tree, start, end).ok_or_else(|| {
+            anyhow!(
+                "Pattern must be a single {} expression, statement or declaration: `{}`",
+                language.name(),
+                pattern
+            )
+        })?;
+
+        let mut builder = QueryBuilder {
+            source: &source,
+            metavariables: &metavariables,
+            predicates: Vec::new(),
+            literal_count: 0,
+        };
+        // A lone metavariable would match every node, comments included
+        let root_text = &source[root.start_byte()..root.end_byte()];
+        if metavariables.contains_key(root_text) {
+            bail!("Pattern must contain code besides a metavariable");
+        }
+        let root_query = builder.build(root)?;
+
+        let query_source = format!(
+            "({} @{} {})",
+            root_query,
+            MATCH_CAPTURE,
+            builder.predicates.join(" ")
+        );
+        let query = compile_query(language, &query_source)
+            .map_err(|e| anyhow!("Pattern `{}` could not be compiled: {}", pattern, e))?;
+
+        let multi_metavariables = metavariables
+            .values()
+            .filter(|mv| mv.multi)
+            .filter_map(|mv| mv.name.clone())
+            .collect();
+
+        Ok(Self {
+            query,
+            query_source,
+            multi_metavariables,
+            constraints: compiled_constraints,
+        })
+    }
+
+    /// The tree-sitter query generated for this pattern
+    pub fn query_source(&self) -> &str {
+        &self.query_source
+    }
+
+    /// The compiled tree-sitter query for this pattern
+    pub fn query(&self) -> &Query {
+        &self.query
+    }
+
+    /// Names of the metavariables captured by this pattern
+    pub fn metavariables(&self) -> impl Iterator<Item = &str> + '_ {
+        self.query.capture_names().iter().copied().filter(|name| {
+            *name != MATCH_CAPTURE
+                && *name != ANONYMOUS_CAPTURE
+                && !name.starts_with(LITERAL_CAPTURE_PREFIX)
+        })
+    }
+
+    /// Find all matches of this pattern in a syntax tree
+    ///
+    /// Metavariables are matched against the source text; the returned text
+    /// has the given secrets masked.
+    pub fn find_matches(
+        &self,
+        tree: &Tree,
+        source: &str,
+        file_path: &Path,
+        secrets: Option<&Secrets>,
+    ) -> Vec<PatternMatch> {
+        let file_path_str = file_path.to_string_lossy().to_string();
+        let capture_names = self.query.capture_names();
+
+        // Keyed by the matched node's byte range. Overlapping alternatives can
+        // yield several matches for the same node; keep the one that captured
+        // the most nodes.
+        let mut best: BTreeMap<(usize, usize), (usize, PatternMatch)> = BTreeMap::new();
+
+        let mut cursor = tree_sitter::QueryCursor::new();
+        let mut matches = cursor.matches(&self.query, tree.root_node(), source.as_bytes());
+
+        while let Some(m) = matches.next() {
+            let mut root: Option<Node> = None;
+            let mut captured: BTreeMap<String, Vec<Node>> = BTreeMap::new();
+            let mut binds_comment = false;
+
+            for capture in m.captures {
+                let name = capture_names[capture.index as usize];
+                if name == MATCH_CAPTURE {
+                    root = Some(capture.node);
+                } else if !name.starts_with(LITERAL_CAPTURE_PREFIX) {
+                    // `(_)` also matches extras such as comments between the
+                    // nodes the pattern names
+                    binds_comment |= capture.node.is_extra()
+                        && !self.multi_metavariables.iter().any(|multi| multi == name);
+                    if name == ANONYMOUS_CAPTURE {
+                        continue;
+                    }
+                    captured
+                        .entry(name.to_string())
+                        .or_default()
+                        .push(capture.node);
+                }
+            }
+
+            let Some(root) = root.filter(|_| !binds_comment) else {
+                continue;
+            };
+
+            let Some(metavariables) = self.bind_metavariables(&captured, source) else {
+                continue;
+            };
+
+            let node_count = captured.values().map(Vec::len).sum();
+            let key = (root.start_byte(), root.end_byte());
+            if best
+                .get(&key)
+                .is_some_and(|(count, _)| *count >= node_count)
+            {
+                continue;
+            }
+
+            best.insert(
+                key,
+                (
+                    node_count,
+                    PatternMatch {
+                        file_path: file_path_str.clone(),
+                        start_line: root.start_position().row + 1,
+                        start_column: root.start_position().column,
+                        end_line: root.end_position().row + 1,
+                        end_column: root.end_position().column,
+                        text: source_text(source, root.byte_range(), secrets),
+                        metavariables: metavariables
+                            .into_iter()
+                            .map(|(name, range)| (name, source_text(source, range, secrets)))
+                            .collect(),
+                    },
+                ),
+            );
+        }
+
+        best.into_values().map(|(_, m)| m).collect()
+    }
+
+    /// Resolve captured nodes to the byte ranges of metavariables, enforcing
+    /// that repeated metavariables agree and that constraints hold
+    fn bind_metavariables(
+        &self,
+        captured: &BTreeMap<String, Vec<Node>>,
+        source: &str,
+    ) -> Option<BTreeMap<String, Range<usize>>> {
+        let mut bindings = BTreeMap::new();
+
+        for (name, nodes) in captured {
+            let range = if self.multi_metavariables.contains(name) {
+                let mut nodes = nodes.clone();
+                nodes.sort_by_key(|n| n.start_byte());
+                match (nodes.first(), nodes.last()) {
+                    (Some(first), Some(last)) => first.start_byte()..last.end_byte(),
+                    _ => 0..0,
+                }
+            } else {
+                let first = nodes.first()?;
+                let text = |node: &Node| &source[node.byte_range()];
+                if !nodes.iter().all(|n| text(n) == text(first)) {
+                    return None;
+                }
+                first.byte_range()
+            };
+            bindings.insert(name.clone(), range);
+        }
+
+        // `$$$ARGS` that matched nothing produces no capture; bind it to ""
+        for name in &self.multi_metavariables {
+            bindings.entry(name.clone()).or_insert(0..0);
+        }
+
+        for (name, regex) in &self.constraints {
+            if !regex.is_match(&source[bindings.get(name)?.clone()]) {
+                return None;
+            }
+        }
+
+        Some(bindings)
+    }
+}
+
+/// Regex matching `$$$NAME`, `$$$`, `$NAME` and `$_`
+fn metavariable_regex() -> &'static Regex {
+    static METAVARIABLE: OnceLock<Regex> = OnceLock::new();
+    METAVARIABLE.get_or_init(|| {
+        Regex::new(r"\$\$\$([A-Z_][A-Z0-9_]*)?|\$([A-Z_][A-Z0-9_]*)").expect("valid regex")
+    })
+}
+
+/// Names referenced by metavariables in a rewrite template
+///
+/// `$_` and `$$$` are reported as `_` since they cannot be bound.
+pub fn template_metavariables(template: &str) -> Vec<String> {
+    metavariable_regex()
+        .captures_iter(template)
+        .map(|caps| {
+            caps.get(1)
+                .or_else(|| caps.get(2))
+                .map_or("_", |m| m.as_str())
+                .to_string()
+        })
+        .collect()
+}
+
+/// Substitute metavariables in a rewrite template
+///
+/// `$NAME` and `$$$NAME` are both replaced by the text bound to `NAME`;
+/// unbound metavariables are left as-is.
+pub fn render_template(template: &str, bindings: &BTreeMap<String, String>) -> String {
+    metavariable_regex()
+        .replace_all(template, |caps: &regex::Captures| {
+            caps.get(1)
+                .or_else(|| caps.get(2))
+                .and_then(|m| bindings.get(m.as_str()))
+                .cloned()
+                .unwrap_or_else(|| caps[0].to_string())
+        })
+        .into_owned()
+}
+
+/// Replace metavariables with placeholder identifiers
+///
+/// Returns the substituted source and a map from placeholder to metavariable.
+fn substitute_metavariables(pattern: &str) -> (String, HashMap<String, Metavariable>) {
+    let regex = metavariable_regex();
+    let mut metavariables = HashMap::new();
+    let mut anonymous_count = 0;
+
+    let substituted = regex.replace_all(pattern, |caps: &regex::Captures| {
+        let multi = caps.get(0).is_some_and(|m| m.as_str().starts_with("$$$"));
+        let name = caps
+            .get(1)
+            .or_else(|| caps.get(2))
+            .map(|m| m.as_str())
+            .filter(|name| *name != "_");
+
+        let placeholder = match name {
+            Some(name) if multi => format!("{}s_{}", PLACEHOLDER_PREFIX, name),
+            Some(name) => format!("{}{}", PLACEHOLDER_PREFIX, name),
+            None => {
+                anonymous_count += 1;
+                format!("{}any_{}", PLACEHOLDER_PREFIX, anonymous_count)
+            }
+        };
+
+        metavariables.insert(
+            placeholder.clone(),
+            Metavariable {
+                name: name.map(str::to_string),
+                multi,
+            },
+        );
+        placeholder
+    });
+
+    (substituted.into_owned(), metavariables)
+}
+
+/// Code to wrap around a pattern so that it parses in the language
+///
+/// Patterns are first tried as-is; these wrappers let expression and
+/// statement patterns parse in languages that only allow them inside a body.
+fn snippet_wrappers(language_id: LanguageId) -> &'static [(&'static str, &'static str)] {
+    match language_id {
+        LanguageId::Rust => &[("fn __codescope() {\n", "\n}")],
+        LanguageId::Go => &[
+            ("package __codescope\n", ""),
+            ("package __codescope\nfunc __codescope() {\n", "\n}"),
+        ],
+        LanguageId::Java | LanguageId::CSharp => &[
+            ("class __Codescope {\n", "\n}"),
+            ("class __Codescope {\nvoid __codescope() {\n", "\n}\n}"),
+            ("class __Codescope {\nvoid __codescope() {\n", ";\n}\n}"),
+        ],
+        LanguageId::C | LanguageId::Cpp => &[
+            ("void __codescope() {\n", "\n}"),
+            ("void __codescope() {\n", ";\n}"),
+        ],
+        LanguageId::Php => &[("<?php\n", ""), ("<?php\n", ";")],
+        _ => &[],
+    }
+}
+
+/// Parse a pattern snippet, trying wrappers until one parses without errors
+///
+/// Returns the tree, the parsed source, and the byte range of the pattern.
+/// PHP is never tried unwrapped because anything before `<?php` parses as
+/// inline text.
+fn parse_snippet(
+    parser: &mut Parser,
+    language_id: LanguageId,
+    pattern: &str,
+) -> Option<(Tree, String, usize, usize)> {
+    let unwrapped = (language_id != LanguageId::Php).then_some(&("", ""));
+    unwrapped
+        .into_iter()
+        .chain(snippet_wrappers(language_id))
+        .find_map(|(prefix, suffix)| {
+            let source = format!("{}{}{}", prefix, pattern, suffix);
+            let tree = parser.parse(&source, None)?;
+            if tree.root_node().has_error() {
+                return None;
+            }
+            let start = prefix.len();
+            Some((tree, source, start, start + pattern.len()))
+        })
+}
+
+/// Find the innermost named node spanning exactly the pattern's byte range
+fn find_pattern_node(tree: &Tree, start: usize, end: usize) -> Option<Node<'_>> {
+    let node = tree
+        .root_node()
+        .named_descendant_for_byte_range(start, end)?;
+    (node.start_byte() == start && node.end_byte() == end).then_some(node)
+}
+
+/// Translates a parsed pattern into tree-sitter query syntax
+struct QueryBuilder<'a> {
+    source: &'a str,
+    metavariables: &'a HashMap<String, Metavariable>,
+    predicates: Vec<String>,
+    literal_count: usize,
+}
+
+impl QueryBuilder<'_> {
+    fn build(&mut self, node: Node) -> Result<String> {
+        let text = &self.source[node.start_byte()..node.end_byte()];
+
+        if let Some(metavariable) = self.metavariables.get(text) {
+            let capture = match &metavariable.name {
+                Some(name) => format!(" @{}", name),
+                None if metavariable.multi => String::new(),
+                None => format!(" @{}", ANONYMOUS_CAPTURE),
+            };
+            // Quantified patterns only repeat over consecutive siblings, so a
+            // sequence also has to consume separators such as `,` in between.
+            return Ok(if metavariable.multi {
+                format!("[(_){} _]*", capture)
+            } else {
+                format!("(_){}", capture)
+            });
+        }
+
+        if node.child_count() == 0 {
+            let capture = format!("{}{}", LITERAL_CAPTURE_PREFIX, self.literal_count);
+            self.literal_count += 1;
+            self.predicates
+                .push(format!("(#eq? @{} {})", capture, quote(text)));
+            return Ok(format!("({}) @{}", node.kind(), capture));
+        }
+
+        let mut parts = vec![node.kind().to_string()];
+        let mut cursor = node.walk();
+        for (index, child) in node.children(&mut cursor).enumerate() {
+            if child.is_extra() {
+                continue;
+            }
+            if child.is_missing() {
+                bail!("Pattern is incomplete near `{}`", text);
+            }
+
+            let child_query = if child.is_named() {
+                self.build(child)?
+            } else {
+                quote(child.kind())
+            };
+
+            match node.field_name_for_child(index as u32) {
+                Some(field) => parts.push(format!("{}: {}", field, child_query)),
+                None => parts.push(child_query),
+            }
+        }
+
+        Ok(format!("({})", parts.join(" ")))
+    }
+}
+
+/// Quote a string as a tree-sitter query string literal
+fn quote(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+        .replace('\t', "\\t");
+    format!("\"{}\"", escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    use crate::language::LanguageRegistry;
+    use crate::parser::GenericParser;
+
+    fn find(
+        file_name: &str,
+        source: &str,
+        pattern: &str,
+        constraints: &[(&str, &str)],
+    ) -> Result<Vec<PatternMatch>> {
+        let registry = Arc::new(LanguageRegistry::new().unwrap());
+        let mut parser = GenericParser::new(registry).unwrap();
+        let path = PathBuf::from(file_name);
+        let (tree, language) = parser.parse_with_language(&path, source).unwrap();
+        let constraints = constraints
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let pattern = Pattern::compile(language.as_ref(), pattern, &constraints)?;
+        Ok(pattern.find_matches(&tree, source, &path, None))
+    }
+
+    #[test]
+    fn test_rust_method_call_pattern() {
+        let source = r#"fn main() {
+    let a = foo().unwrap();
+    let b = bar.unwrap_or(1);
+    let s = "x.unwrap()";
+    self.inner.unwrap();
+}
+"#;
+        let matches = find("test.rs", source, "$X.unwrap()", &[]).unwrap();
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].text, "foo().unwrap()");
+        assert_eq!(matches[0].metavariables["X"], "foo()");
+        assert_eq!((matches[0].start_line, matches[0].start_column), (2, 12));
+        assert_eq!(matches[1].metavariables["X"], "self.inner");
+    }
+
+    #[test]
+    fn test_multi_metavariable() {
+        let source = r#"console.log();
+console.log("a", b, 3);
+console.error("no");
+"#;
+        let matches = find("test.js", source, "console.log($$$ARGS)", &[]).unwrap();
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].metavariables["ARGS"], "");
+        assert_eq!(matches[1].metavariables["ARGS"], r#""a", b, 3"#);
+    }
+
+    #[test]
+    fn test_single_and_multi_metavariables() {
+        let source = "console.log(a, b, c);\n";
+        let matches = find("test.js", source, "console.log($FIRST, $$$REST)", &[]).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].metavariables["FIRST"], "a");
+        assert_eq!(matches[0].metavariables["REST"], "b, c");
+    }
+
+    #[test]
+    fn test_repeated_metavariable_must_match() {
+        let source = "x = a == a\ny = a == b\n";
+        let matches = find("test.py", source, "$A == $A", &[]).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].text, "a == a");
+    }
+
+    #[test]
+    fn test_operator_is_significant() {
+        let source = "const a = x + y;\nconst b = x - y;\n";
+        let matches = find("test.ts", source, "$A - $B", &[]).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].text, "x - y");
+    }
+
+    #[test]
+    fn test_constraints() {
+        let source = "Date.now();\nperformance.now();\n";
+        let matches = find("test.ts", source, "$OBJ.now()", &[("OBJ", "^Date$")]).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].metavariables["OBJ"], "Date");
+    }
+
+    #[test]
+    fn test_anonymous_metavariable_is_not_captured() {
+        let source = "foo(1, 2);\n";
+        let matches = find("test.js", source, "foo($_, $_)", &[]).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert!(matches[0].metavariables.is_empty());
+    }
+
+    #[test]
+    fn test_go_statement_pattern() {
+        let source = r#"package main
+
+func main() {
+	defer f.Close()
+	defer g()
+}
+"#;
+        let matches = find("test.go", source, "defer $F.Close()", &[]).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].metavariables["F"], "f");
+    }
+
+    #[test]
+    fn test_php_pattern() {
+        let source = r#"<?php
+$user->save();
+$order->store();
+$user->delete();
+"#;
+        let matches = find("test.php", source, "$OBJ->save()", &[]).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].metavariables["OBJ"], "$user");
+    }
+
+    #[test]
+    fn test_invalid_pattern() {
+        let err = find("test.rs", "fn main() {}", "$X.unwrap(", &[])
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("not valid Rust code"), "{}", err);
+    }
+
+    #[test]
+    fn test_bare_metavariable_is_rejected() {
+        for pattern in ["$X", "$_", "$$$ARGS"] {
+            let err = find("test.rs", "// note\nfn main() {}", pattern, &[])
+                .err()
+                .unwrap()
+                .to_string();
+            assert!(err.contains("besides a metavariable"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_metavariable_skips_comments() {
+        let source = "foo(/* note */);\nfoo(/* note */ a);\n";
+        let matches = find("test.js", source, "foo($X)", &[]).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].metavariables["X"], "a");
+
+        let matches = find("test.js", source, "foo($_)", &[]).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].text, "foo(/* note */ a)");
+    }
+
+    #[test]
+    fn test_unknown_constraint() {
+        let err = find("test.js", "", "foo($X)", &[("Y", ".*")])
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("unknown metavariable $Y"), "{}", err);
+    }
+
+    #[test]
+    fn test_render_template() {
+        let bindings = BTreeMap::from([
+            ("X".to_string(), "foo()".to_string()),
+            ("ARGS".to_string(), "a, b".to_string()),
+        ]);
+        assert_eq!(
+            render_template("$X.expect(\"TODO\")", &bindings),
+            "foo().expect(\"TODO\")"
+        );
+        assert_eq!(render_template("log($$$ARGS)", &bindings), "log(a, b)");
+        assert_eq!(render_template("$Y + $_", &bindings), "$Y + $_");
+        assert_eq!(
+            template_metavariables("$X($$$ARGS, $_)"),
+            vec!["X", "ARGS", "_"]
+        );
+    }
+
+    #[test]
+    fn test_pattern_metavariables() {
+        let registry = LanguageRegistry::new().unwrap();
+        let rust = registry.get(LanguageId::Rust).unwrap();
+        let pattern = Pattern::compile(rust.as_ref(), "$X.map($$$ARGS)", &HashMap::new()).unwrap();
+        let mut names: Vec<&str> = pattern.metavariables().collect();
+        names.sort();
+        assert_eq!(names, vec!["ARGS", "X"]);
+    }
+}
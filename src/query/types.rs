@@ -0,0 +1,114 @@
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Severity;
+
+/// A single captured node from a structural query
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct QueryCapture {
+    /// Capture name without the leading `@` (e.g., "call")
+    pub name: String,
+    /// Tree-sitter node kind of the captured node (e.g., "call_expression")
+    pub node_kind: String,
+    /// Starting line number (1-indexed)
+    pub start_line: usize,
+    /// Starting column number (0-indexed)
+    pub start_column: usize,
+    /// Ending line number (1-indexed)
+    pub end_line: usize,
+    /// Ending column number (0-indexed, exclusive)
+    pub end_column: usize,
+    /// Source text of the captured node
+    pub text: String,
+}
+
+/// A single match of a structural query
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct QueryMatch {
+    /// File path where the match was found
+    pub file_path: String,
+    /// Index of the query pattern that matched (0-indexed, in source order)
+    pub pattern_index: usize,
+    /// Captured nodes for this match, in document order
+    pub captures: Vec<QueryCapture>,
+}
+
+/// A single match of a code pattern
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PatternMatch {
+    /// File path where the match was found
+    pub file_path: String,
+    /// Starting line number (1-indexed)
+    pub start_line: usize,
+    /// Starting column number (0-indexed)
+    pub start_column: usize,
+    /// Ending line number (1-indexed)
+    pub end_line: usize,
+    /// Ending column number (0-indexed, exclusive)
+    pub end_column: usize,
+    /// Source text of the matched code
+    pub text: String,
+    /// Text bound to each named metavariable (e.g., {"X": "foo()"} for `$X.unwrap()`)
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub metavariables: BTreeMap<String, String>,
+}
+
+/// A lint rule violation
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Diagnostic {
+    /// File path where the violation was found
+    pub file_path: String,
+    /// Starting line number (1-indexed)
+    pub start_line: usize,
+    /// Starting column number (0-indexed)
+    pub start_column: usize,
+    /// Ending line number (1-indexed)
+    pub end_line: usize,
+    /// Ending column number (0-indexed, exclusive)
+    pub end_column: usize,
+    /// Id of the rule that produced this diagnostic
+    pub rule_id: String,
+    /// Message from the rule
+    pub message: String,
+    /// Severity from the rule
+    pub severity: Severity,
+    /// Source text of the offending code
+    pub text: String,
+    /// Replacement text from the rule's fix template
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<String>,
+}
+
+/// Outcome of applying fixes to a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum FixStatus {
+    /// Fixes were computed but not written
+    Preview,
+    /// The fixed content was written to disk
+    Written,
+    /// The file changed since the preview; nothing was written
+    Conflict,
+}
+
+/// Fixes computed for a single file
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FileFix {
+    /// File path the fixes apply to
+    pub file_path: String,
+    /// SHA-256 hash of the file content the fixes were computed from
+    pub content_hash: String,
+    /// Number of fixes applied
+    pub applied: usize,
+    /// Number of fixes skipped because they overlap an earlier fix
+    pub skipped: usize,
+    /// Unified diff of the change
+    pub diff: String,
+    /// Whether the fixes were written
+    pub status: FixStatus,
+    /// Fixed file content, written when the fixes are applied
+    #[serde(skip)]
+    pub fixed_content: String,
+}
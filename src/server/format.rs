@@ -0,0 +1,233 @@
+//! Token-efficient output formats
+//!
+//! Search results can be returned grouped by file, so the path is written
+//! once per file, or as terse text lines (see [`OutputFormat`]).
+
+use serde::Serialize;
+
+use crate::pipeline::Located;
+use crate::query::types::{Diagnostic, PatternMatch, QueryMatch};
+use crate::server::types::{FileResults, GroupedPage, OutputFormat, Page, RootResult};
+use crate::symbol::types::{CommentMatch, SymbolDefinition, SymbolUsage};
+
+/// A search result written as one line in the text format
+pub trait TextLine: Located {
+    /// Kind of the result (e.g., the symbol or usage kind)
+    fn kind(&self) -> String;
+
+    /// Name or text of the result
+    fn label(&self) -> String;
+}
+
+/// Group the results of a page by file
+///
+/// Results are ordered by location, so each file's results are adjacent.
+pub fn group_by_file<T: Serialize + Located>(
+    page: Page<RootResult<T>>,
+) -> serde_json::Result<GroupedPage> {
+    let mut files: Vec<FileResults> = Vec::new();
+    for result in page.results {
+        let file_path = result.location().0.to_string();
+        let mut value = serde_json::to_value(&result.item)?;
+        if let Some(object) = value.as_object_mut() {
+            object.remove("file_path");
+        }
+
+        match files.last_mut() {
+            Some(file) if file.file_path == file_path && file.root == result.root => {
+                file.results.push(value)
+            }
+            _ => files.push(FileResults {
+                file_path,
+                root: result.root,
+                results: vec![value],
+            }),
+        }
+    }
+
+    Ok(GroupedPage {
+        total: page.total,
+        offset: page.offset,
+        files,
+        next_cursor: page.next_cursor,
+    })
+}
+
+/// Write a page as `path:line:col kind name` lines
+///
+/// A final `#` line gives the total and the cursor of the next page.
+pub fn to_text<T: TextLine>(page: &Page<RootResult<T>>) -> String {
+    let mut text = String::new();
+    for RootResult { item, .. } in &page.results {
+        let (file_path, line, column) = item.location();
+        text.push_str(&format!(
+            "{}:{}:{} {} {}\n",
+            file_path,
+            line,
+            column,
+            item.kind(),
+            item.label()
+        ));
+    }
+
+    text.push_str(&format!(
+        "# {} of {} results from offset {}",
+        page.results.len(),
+        page.total,
+        page.offset
+    ));
+    if let Some(cursor) = &page.next_cursor {
+        text.push_str(&format!(", next_cursor: {}", cursor));
+    }
+    text
+}
+
+/// Serialize a value as JSON, pretty-printed only for the `json` format
+pub fn to_json<T: Serialize>(value: &T, format: OutputFormat) -> serde_json::Result<String> {
+    match format {
+        OutputFormat::Json => serde_json::to_string_pretty(value),
+        _ => serde_json::to_string(value),
+    }
+}
+
+/// First line of a text, trimmed
+fn first_line(text: &str) -> &str {
+    text.lines().next().unwrap_or("").trim()
+}
+
+impl TextLine for SymbolDefinition {
+    fn kind(&self) -> String {
+        self.node_kind.to_string()
+    }
+
+    fn label(&self) -> String {
+        self.name.clone()
+    }
+}
+
+impl TextLine for SymbolUsage {
+    fn kind(&self) -> String {
+        self.usage_kind.to_string()
+    }
+
+    fn label(&self) -> String {
+        self.qualified_name.clone()
+    }
+}
+
+impl TextLine for CommentMatch {
+    fn kind(&self) -> String {
+        self.comment_type.to_string()
+    }
+
+    fn label(&self) -> String {
+        let text = self.matched_text.as_deref().unwrap_or(&self.content);
+        first_line(text).to_string()
+    }
+}
+
+impl TextLine for QueryMatch {
+    fn kind(&self) -> String {
+        self.captures
+            .first()
+            .map(|capture| format!("@{}", capture.name))
+            .unwrap_or_default()
+    }
+
+    fn label(&self) -> String {
+        self.captures
+            .first()
+            .map(|capture| first_line(&capture.text).to_string())
+            .unwrap_or_default()
+    }
+}
+
+impl TextLine for PatternMatch {
+    fn kind(&self) -> String {
+        "Match".to_string()
+    }
+
+    fn label(&self) -> String {
+        first_line(&self.text).to_string()
+    }
+}
+
+impl TextLine for Diagnostic {
+    fn kind(&self) -> String {
+        format!("{:?}", self.severity).to_lowercase()
+    }
+
+    fn label(&self) -> String {
+        format!("{}: {}", self.rule_id, self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::symbol::types::UsageKind;
+
+    fn page() -> Page<RootResult<SymbolUsage>> {
+        let usage = |file_path: &str, line, qualified_name: &str| RootResult {
+            root: None,
+            item: SymbolUsage {
+                file_path: file_path.to_string(),
+                line,
+                column: 4,
+                qualified_name: qualified_name.to_string(),
+                usage_kind: UsageKind::MethodCall,
+                object_name: None,
+                contexts: Vec::new(),
+            },
+        };
+        Page {
+            total: 5,
+            offset: 0,
+            results: vec![
+                usage("src/a.ts", 3, "Date.now"),
+                usage("src/a.ts", 9, "Date.now"),
+                usage("src/b.ts", 1, "now"),
+            ],
+            next_cursor: Some("3".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_to_text() {
+        assert_eq!(
+            to_text(&page()),
+            "src/a.ts:3:4 MethodCall Date.now\n\
+             src/a.ts:9:4 MethodCall Date.now\n\
+             src/b.ts:1:4 MethodCall now\n\
+             # 3 of 5 results from offset 0, next_cursor: 3"
+        );
+    }
+
+    #[test]
+    fn test_group_by_file() {
+        let grouped = group_by_file(page()).unwrap();
+        assert_eq!(
+            serde_json::to_value(&grouped).unwrap(),
+            serde_json::json!({
+                "total": 5,
+                "offset": 0,
+                "next_cursor": "3",
+                "files": [
+                    {
+                        "file_path": "src/a.ts",
+                        "results": [
+                            {"line": 3, "column": 4, "qualified_name": "Date.now", "usage_kind": "MethodCall"},
+                            {"line": 9, "column": 4, "qualified_name": "Date.now", "usage_kind": "MethodCall"}
+                        ]
+                    },
+                    {
+                        "file_path": "src/b.ts",
+                        "results": [
+                            {"line": 1, "column": 4, "qualified_name": "now", "usage_kind": "MethodCall"}
+                        ]
+                    }
+                ]
+            })
+        );
+    }
+}
@@ -1,37 +1,141 @@
-use std::path::PathBuf;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
 use rmcp::{
     handler::server::{tool::ToolRouter, wrapper::Parameters},
     model::{
-        CallToolResult, Content, Implementation, InitializeResult, ProtocolVersion,
-        ServerCapabilities, ServerInfo,
+        CallToolResult, Content, Implementation, InitializeResult, ProgressNotificationParam,
+        ProtocolVersion, ServerCapabilities, ServerInfo,
     },
-    tool, tool_handler, tool_router, ErrorData as McpError, ServerHandler,
+    service::{NotificationContext, Peer, RequestContext},
+    tool, tool_handler, tool_router, ErrorData as McpError, RoleServer, ServerHandler,
 };
-use tokio::sync::RwLock;
+use tokio::sync::{watch, RwLock};
 
 use crate::cache::CacheManager;
-use crate::config::CodeScopeConfig;
-use crate::language::LanguageRegistry;
+use crate::language::{LanguageId, LanguageRegistry, LanguageSupport};
 use crate::parser::CachedParser;
 use crate::pipeline::{
-    aggregate_statistics, CommentCollector, DefinitionCollector, FilePipeline, ImportCollector,
-    MethodCallCollector, StatsCollector, UsageCollector,
+    aggregate_statistics, sort_by_location, AstQueryCollector, CommentCollector,
+    DefinitionCollector, FilePipeline, FixCollector, ImportCollector, LintCollector, Located,
+    MethodCallCollector, PatternCollector, ResultCollector, ScanProgress, StatsCollector,
+    UsageCollector,
 };
+use crate::query::engine::compile_query;
+use crate::query::fix::write_if_unchanged;
+use crate::query::lint::{compile_rules, CompiledRule};
+use crate::query::pattern::Pattern;
+use crate::query::types::FixStatus;
+use crate::server::format::{group_by_file, to_json, to_text, TextLine};
 use crate::server::types::{
-    CodeAtLocationParams, CommentSearchParams, DefinitionParams, ImportsParams, MethodCallsParams,
-    StatsParams, SymbolAtLocationParams, SymbolAtLocationResponse, UsagesParams,
+    AddRootParams, ApplyFixesParams, AstQueryParams, CodeAtLocationParams, CommentSearchParams,
+    DefinitionParams, FindPatternParams, ImportsParams, LintParams, MethodCallsParams,
+    OutputFormat, Page, PageParams, RootInfo, RootResult, StatsParams, SymbolAtLocationParams,
+    SymbolAtLocationResponse, UsagesParams,
 };
-use crate::symbol::comment::get_code_at_location;
-use crate::symbol::types::SymbolDefinition;
+use crate::server::workspace::{
+    plugins_allowed_by_env, resolve_root_path, root_path_from_uri, Workspace, WorkspaceRoot,
+};
+use crate::symbol::comment::{code_at_location, get_code_at_location};
+use crate::symbol::key_path::definition_key_path;
+use crate::symbol::matcher::TextMatcher;
+use crate::symbol::redact::Redactor;
+use crate::symbol::types::{CodeSnippet, SymbolDefinition};
+
+/// Interval between progress notifications of a scan
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Time allowed for the client to answer a `roots/list` request
+const ROOTS_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Number of results returned by a search tool when no limit is given
+const DEFAULT_PAGE_LIMIT: usize = 100;
+
+/// Hash of the parameters selecting a search tool's results
+///
+/// Covers the tool name and every parameter but the page and output format,
+/// so a cursor is only accepted by the query that returned it.
+fn query_hash(query: &impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    query.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Get the offset of a cursor (`<offset>.<query hash>`) returned for `query`
+fn parse_cursor(cursor: &str, query: u64) -> Result<usize, McpError> {
+    let (offset, hash) = cursor
+        .split_once('.')
+        .and_then(|(offset, hash)| {
+            Some((offset.parse().ok()?, u64::from_str_radix(hash, 16).ok()?))
+        })
+        .ok_or_else(|| McpError::invalid_params(format!("Invalid cursor '{}'", cursor), None))?;
+    if hash != query {
+        return Err(McpError::invalid_params(
+            format!(
+                "Cursor '{}' belongs to a query with different parameters; \
+                repeat the parameters of the request that returned it",
+                cursor
+            ),
+            None,
+        ));
+    }
+    Ok(offset)
+}
+
+/// Built-in languages, listed once in the server instructions
+const LANGUAGES: &[&str] = &[
+    "TypeScript",
+    "TSX",
+    "JavaScript",
+    "JSX",
+    "Python",
+    "Rust",
+    "Go",
+    "Java",
+    "C",
+    "C++",
+    "C#",
+    "Ruby",
+    "PHP",
+    "Swift",
+    "Kotlin",
+    "Scala",
+    "Bash",
+    "HTML",
+    "CSS",
+    "SQL",
+    "Markdown",
+    "JSON",
+    "YAML",
+    "TOML",
+    "Vue",
+    "Svelte",
+    "Astro",
+    "Dockerfile",
+    "Makefile",
+];
+
+/// Roots selected for a tool call
+struct RootSelection {
+    roots: Vec<Arc<WorkspaceRoot>>,
+    /// Whether results are tagged with the root they came from
+    tagged: bool,
+}
 
 /// CodeScope MCP Server
 #[derive(Clone)]
 pub struct CodeScopeServer {
-    workspace_root: Arc<RwLock<Option<PathBuf>>>,
-    config: Arc<RwLock<CodeScopeConfig>>,
-    registry: Arc<LanguageRegistry>,
+    workspace: Arc<RwLock<Arc<Workspace>>>,
+    builtin_registry: Arc<LanguageRegistry>,
+    /// Whether roots load their language plugins, see [`crate::server::workspace::ALLOW_PLUGINS_ENV`]
+    allow_plugins: bool,
+    /// Set while the client's roots are requested after initialization, so
+    /// tool calls wait for them instead of searching the current directory
+    roots_pending: Arc<watch::Sender<bool>>,
     cache_manager: Arc<CacheManager>,
     tool_router: ToolRouter<Self>,
 }
@@ -42,20 +146,21 @@ impl CodeScopeServer {
     ///
     /// # Panics
     ///
-    /// Panics if the language registry cannot be created. This can only happen
-    /// if the embedded tree-sitter queries are malformed, which would indicate
+    /// Panics if the language registry cannot be created. This would indicate
     /// a programming error caught during development/testing rather than a
     /// runtime failure.
     pub fn new() -> Self {
         let registry = Arc::new(LanguageRegistry::new().expect(
-            "Failed to create language registry: embedded queries are malformed. \
+            "Failed to create language registry. \
                 This is a programming error that should be caught during development.",
         ));
+        let allow_plugins = plugins_allowed_by_env();
 
         Self {
-            workspace_root: Arc::new(RwLock::new(None)),
-            config: Arc::new(RwLock::new(CodeScopeConfig::default_config())),
-            registry,
+            workspace: Arc::new(RwLock::new(Arc::new(Workspace::new(allow_plugins)))),
+            builtin_registry: registry,
+            allow_plugins,
+            roots_pending: Arc::new(watch::Sender::new(false)),
             cache_manager: Arc::new(CacheManager::new()),
             tool_router: Self::tool_router(),
         }
@@ -64,66 +169,484 @@ impl CodeScopeServer {
     /// Try to create a new CodeScope server, returning an error if initialization fails
     pub fn try_new() -> Result<Self, anyhow::Error> {
         let registry = Arc::new(LanguageRegistry::new()?);
+        let allow_plugins = plugins_allowed_by_env();
 
         Ok(Self {
-            workspace_root: Arc::new(RwLock::new(None)),
-            config: Arc::new(RwLock::new(CodeScopeConfig::default_config())),
-            registry,
+            workspace: Arc::new(RwLock::new(Arc::new(Workspace::new(allow_plugins)))),
+            builtin_registry: registry,
+            allow_plugins,
+            roots_pending: Arc::new(watch::Sender::new(false)),
             cache_manager: Arc::new(CacheManager::new()),
             tool_router: Self::tool_router(),
         })
     }
 
     /// Set the workspace root directory and load config
+    ///
+    /// Replaces all roots with this one and the roots its config declares.
     pub async fn set_workspace_root(&self, root: PathBuf) {
-        let loaded_config = CodeScopeConfig::load(&root);
-        {
-            let mut config = self.config.write().await;
-            *config = loaded_config;
+        self.set_workspace_roots(vec![(None, root)]).await;
+    }
+
+    /// Set the named workspace roots and load their configs
+    ///
+    /// Each root gets a language registry with its own plugins, query
+    /// overrides and file types; if those fail to load, the root uses the
+    /// built-in languages. Roots whose configuration fails to load are
+    /// refused. Unnamed roots are named after their directory.
+    pub async fn set_workspace_roots(&self, roots: Vec<(Option<String>, PathBuf)>) {
+        let workspace = Workspace::load(roots, &self.builtin_registry, self.allow_plugins);
+        *self.workspace.write().await = Arc::new(workspace);
+        // Cached trees may have been parsed with a different language
+        self.cache_manager.clear();
+    }
+
+    /// Add a workspace root, keeping the existing ones
+    ///
+    /// If no root has been set yet, the current directory is kept as the
+    /// first root. Fails if the root is outside the existing roots or its
+    /// configuration cannot be loaded.
+    pub async fn add_workspace_root(
+        &self,
+        name: Option<String>,
+        path: PathBuf,
+    ) -> anyhow::Result<Arc<WorkspaceRoot>> {
+        self.wait_for_client_roots().await;
+        let mut current = self.workspace.write().await;
+        let mut workspace = Workspace::clone(&current);
+        if workspace.is_empty() {
+            if let Ok(current_dir) = std::env::current_dir() {
+                // The error is logged and recorded by the workspace
+                let _ = workspace.add_base(None, current_dir, &self.builtin_registry);
+            }
         }
 
-        let mut workspace = self.workspace_root.write().await;
-        *workspace = Some(root);
+        let root = workspace.add(name, path, &self.builtin_registry);
+        *current = Arc::new(workspace);
+        self.cache_manager.clear();
+        root
     }
 
-    /// Get the workspace root directory
-    async fn get_workspace_root(&self) -> Result<PathBuf, McpError> {
-        let workspace = self.workspace_root.read().await;
-        match workspace.as_ref() {
-            Some(path) => Ok(path.clone()),
-            None => std::env::current_dir().map_err(|e| {
-                McpError::internal_error(format!("Failed to get current directory: {}", e), None)
-            }),
+    /// Replace the workspace roots with the client's roots
+    ///
+    /// Requests `roots/list` from clients that support roots. Non-file roots
+    /// are skipped, and the current roots are kept if the client reports no
+    /// usable root.
+    async fn sync_client_roots(&self, peer: &Peer<RoleServer>) {
+        let supports_roots = peer
+            .peer_info()
+            .is_some_and(|info| info.capabilities.roots.is_some());
+        if !supports_roots {
+            return;
         }
+
+        let client_roots =
+            match tokio::time::timeout(ROOTS_REQUEST_TIMEOUT, peer.list_roots()).await {
+                Ok(Ok(result)) => result.roots,
+                Ok(Err(e)) => {
+                    tracing::warn!("Failed to list client roots: {}", e);
+                    return;
+                }
+                Err(_) => {
+                    tracing::warn!("Timed out listing client roots");
+                    return;
+                }
+            };
+
+        let roots: Vec<_> = client_roots
+            .into_iter()
+            .filter_map(|root| match root_path_from_uri(&root.uri) {
+                Some(path) => Some((root.name, path)),
+                None => {
+                    tracing::warn!("Ignoring root with unsupported URI: {}", root.uri);
+                    None
+                }
+            })
+            .collect();
+        if roots.is_empty() {
+            tracing::info!("Client reported no file roots, keeping the current roots");
+            return;
+        }
+
+        tracing::info!("Using {} workspace root(s) from the client", roots.len());
+        self.set_workspace_roots(roots).await;
     }
 
-    /// Create a file pipeline with the given excludes
-    async fn create_pipeline(
+    /// Get the workspace roots
+    ///
+    /// Waits for the client's roots while they are being requested. Falls
+    /// back to the current directory if no root has been set, loading
+    /// its `.mcp.json` like any other root. If all roots were refused,
+    /// including the current directory, nothing is searched, since searching
+    /// without their configuration would ignore their deny lists.
+    async fn workspace(&self) -> Result<Arc<Workspace>, McpError> {
+        self.wait_for_client_roots().await;
+        let mut workspace = self.workspace.read().await.clone();
+        if workspace.is_empty() && workspace.refused().next().is_none() {
+            workspace = self.load_current_dir().await?;
+        }
+        if workspace.is_empty() {
+            let refused: Vec<_> = workspace.refused().collect();
+            return Err(McpError::invalid_request(
+                format!("No usable workspace root: {}", refused.join("; ")),
+                None,
+            ));
+        }
+        Ok(workspace)
+    }
+
+    /// Wait until the client's roots requested after initialization are set
+    ///
+    /// Returns at once if the client does not support roots or they were
+    /// already requested. The request times out, so this cannot block forever.
+    async fn wait_for_client_roots(&self) {
+        let mut pending = self.roots_pending.subscribe();
+        // The sender lives as long as the server, so this cannot fail
+        let _ = pending.wait_for(|pending| !pending).await;
+    }
+
+    /// Use the current directory as the only root, unless roots were set
+    /// in the meantime
+    async fn load_current_dir(&self) -> Result<Arc<Workspace>, McpError> {
+        let current_dir = std::env::current_dir().map_err(|e| {
+            McpError::internal_error(format!("Failed to get current directory: {}", e), None)
+        })?;
+
+        let mut current = self.workspace.write().await;
+        if current.is_empty() && current.refused().next().is_none() {
+            *current = Arc::new(Workspace::load(
+                vec![(None, current_dir)],
+                &self.builtin_registry,
+                self.allow_plugins,
+            ));
+        }
+        Ok(current.clone())
+    }
+
+    /// Select the roots searched by a tool call, all roots by default
+    ///
+    /// Fails if a selected root has errors, naming them.
+    async fn select_roots(&self, roots: Option<Vec<String>>) -> Result<RootSelection, McpError> {
+        let workspace = self.workspace().await?;
+        let selected = workspace
+            .select(roots.as_deref())
+            .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+        // Searching with an override silently dropped would give results the
+        // user did not ask for
+        if let Some(root) = selected.iter().find(|root| !root.errors.is_empty()) {
+            return Err(McpError::invalid_params(
+                format!(
+                    "Root '{}' failed to load its languages:\n{}",
+                    root.name,
+                    root.errors.join("\n")
+                ),
+                None,
+            ));
+        }
+
+        Ok(RootSelection {
+            roots: selected,
+            tagged: workspace.roots().len() > 1,
+        })
+    }
+
+    /// Run a collector over each selected root
+    ///
+    /// The collector is built per root, since compiled queries and rules
+    /// belong to the root's languages. The scan runs on the blocking thread
+    /// pool and reports progress to the client (see [`Self::run_scan`]).
+    async fn process_roots<C, T, F>(
         &self,
+        selection: &RootSelection,
         exclude_dirs: Option<Vec<String>>,
-    ) -> Result<FilePipeline, McpError> {
-        let workspace_root = self.get_workspace_root().await?;
-        let config = self.config.read().await.clone();
-
-        Ok(FilePipeline::new(
-            self.registry.clone(),
-            workspace_root,
-            config,
-            self.cache_manager.clone(),
-        )
-        .with_excludes(exclude_dirs))
+        language: Option<String>,
+        context: &RequestContext<RoleServer>,
+        collector: F,
+    ) -> Result<Vec<RootResult<T>>, McpError>
+    where
+        C: ResultCollector<Item = T> + Sync,
+        T: Send + 'static,
+        F: Fn(&WorkspaceRoot) -> Result<C, McpError> + Send + 'static,
+    {
+        let roots = selection.roots.clone();
+        let tagged = selection.tagged;
+        let cache_manager = self.cache_manager.clone();
+
+        Self::run_scan(context, move |progress| {
+            let mut results = Vec::new();
+            for root in &roots {
+                if progress.is_cancelled() {
+                    break;
+                }
+                let collector = collector(root)?;
+                let pipeline = FilePipeline::new(
+                    root.registry.clone(),
+                    root.path.clone(),
+                    root.config.clone(),
+                    cache_manager.clone(),
+                )
+                .with_excludes(exclude_dirs.clone())
+                .with_language_filter(language.clone())
+                .with_progress(Some(progress.clone()));
+
+                let name = tagged.then(|| root.name.clone());
+                results.extend(
+                    pipeline
+                        .process(&collector)
+                        .into_iter()
+                        .map(|item| RootResult {
+                            root: name.clone(),
+                            item,
+                        }),
+                );
+            }
+            Ok(results)
+        })
+        .await
     }
 
-    /// Helper to serialize results to JSON
-    fn serialize_result<T: serde::Serialize>(result: &T) -> Result<CallToolResult, McpError> {
-        let json = serde_json::to_string_pretty(result).map_err(|e| {
-            McpError::internal_error(format!("Failed to serialize result: {}", e), None)
-        })?;
+    /// Run a scan on the blocking thread pool
+    ///
+    /// While the scan runs, progress notifications (files scanned out of the
+    /// files listed so far) are sent if the client passed a progress token,
+    /// and a cancellation of the request stops the scan.
+    async fn run_scan<T, F>(context: &RequestContext<RoleServer>, scan: F) -> Result<T, McpError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Arc<ScanProgress>) -> Result<T, McpError> + Send + 'static,
+    {
+        let progress = Arc::new(ScanProgress::default());
+
+        let watcher = {
+            let progress = progress.clone();
+            let ct = context.ct.clone();
+            let peer = context.peer.clone();
+            let progress_token = context.meta.get_progress_token();
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(PROGRESS_INTERVAL);
+                let mut reported = None;
+                loop {
+                    tokio::select! {
+                        _ = ct.cancelled() => {
+                            progress.cancel();
+                            return;
+                        }
+                        _ = interval.tick() => {}
+                    }
+
+                    let Some(progress_token) = &progress_token else {
+                        continue;
+                    };
+                    let current = (progress.scanned(), progress.total());
+                    if current.1 == 0 || reported == Some(current) {
+                        continue;
+                    }
+                    reported = Some(current);
+
+                    let (scanned, total) = current;
+                    let notification = ProgressNotificationParam {
+                        progress_token: progress_token.clone(),
+                        progress: scanned as f64,
+                        total: Some(total as f64),
+                        message: Some(format!(
+                            "Scanned {} of {} files, {} results so far",
+                            scanned,
+                            total,
+                            progress.found()
+                        )),
+                    };
+                    if let Err(e) = peer.notify_progress(notification).await {
+                        tracing::warn!("Failed to send progress notification: {}", e);
+                    }
+                }
+            })
+        };
+
+        let result = {
+            let progress = progress.clone();
+            tokio::task::spawn_blocking(move || scan(&progress)).await
+        };
+        watcher.abort();
+
+        if progress.is_cancelled() {
+            return Err(McpError::internal_error("Request cancelled", None));
+        }
+        result.map_err(|e| McpError::internal_error(format!("Scan failed: {}", e), None))?
+    }
+
+    /// Resolve a language name to a registered language
+    fn resolve_language(
+        registry: &LanguageRegistry,
+        language: &str,
+    ) -> Result<(LanguageId, Arc<dyn LanguageSupport>), McpError> {
+        registry
+            .get_by_name(language)
+            .map(|lang| (lang.id(), lang.clone()))
+            .ok_or_else(|| {
+                McpError::invalid_params(format!("Unsupported language: {}", language), None)
+            })
+    }
+
+    /// Check that each requested rule id is configured in a selected root
+    fn check_rule_ids(selection: &RootSelection, only: Option<&[String]>) -> Result<(), McpError> {
+        let Some(ids) = only else {
+            return Ok(());
+        };
+        match ids.iter().find(|id| {
+            !selection
+                .roots
+                .iter()
+                .any(|root| root.config.rules.iter().any(|rule| &rule.id == *id))
+        }) {
+            Some(unknown) => Err(McpError::invalid_params(
+                format!("Unknown rule id '{}'", unknown),
+                None,
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Compile a root's lint rules
+    ///
+    /// Requested ids naming rules of other roots are skipped.
+    fn root_rules(
+        root: &WorkspaceRoot,
+        only: Option<&[String]>,
+    ) -> Result<Vec<CompiledRule>, McpError> {
+        let only: Option<Vec<String>> = only.map(|ids| {
+            ids.iter()
+                .filter(|id| root.config.rules.iter().any(|rule| &rule.id == *id))
+                .cloned()
+                .collect()
+        });
+        compile_rules(&root.config.rules, only.as_deref(), &root.registry)
+            .map_err(|e| McpError::invalid_params(e.to_string(), None))
+    }
+
+    /// Names and paths of the workspace roots
+    fn root_infos(workspace: &Workspace) -> Vec<RootInfo> {
+        workspace
+            .roots()
+            .iter()
+            .map(|root| RootInfo {
+                name: root.name.clone(),
+                path: root.path.display().to_string(),
+                errors: root.errors.clone(),
+            })
+            .collect()
+    }
+
+    /// Get code at a location with the secrets in the file masked
+    ///
+    /// Files in a supported language are parsed, so high-entropy string
+    /// literals are masked along with the secret patterns.
+    fn redacted_code_at_location(
+        &self,
+        root: &WorkspaceRoot,
+        redactor: &Redactor,
+        path: &Path,
+        line: usize,
+        before: usize,
+        after: usize,
+    ) -> Result<CodeSnippet, McpError> {
+        let cached_content = self
+            .cache_manager
+            .file_cache
+            .get_or_read(path)
+            .map_err(|e| McpError::internal_error(format!("Failed to read code: {}", e), None))?;
+        let source_code = &cached_content.content;
+
+        let trees = if root.registry.get_for_path(path).is_some() {
+            CachedParser::new(
+                root.registry.clone(),
+                self.cache_manager.parser_cache.clone(),
+            )
+            .and_then(|mut parser| {
+                parser.parse_with_injections(path, source_code, cached_content.modified_time)
+            })
+            .map_err(|e| McpError::internal_error(format!("Failed to parse file: {}", e), None))?
+        } else {
+            Vec::new()
+        };
+
+        let secrets = redactor.find(source_code, trees.iter().map(|(tree, _)| tree));
+        Ok(code_at_location(
+            source_code,
+            path,
+            line,
+            before,
+            after,
+            Some(&secrets),
+        ))
+    }
+
+    /// Sort results by location and select the requested page
+    ///
+    /// `query` is the [`query_hash`] of the tool's parameters; it is encoded
+    /// in `next_cursor`, and a cursor from a different query is rejected.
+    fn paginate<T: Located>(
+        mut results: Vec<T>,
+        page: PageParams,
+        query: u64,
+    ) -> Result<Page<T>, McpError> {
+        let offset = match (page.offset, page.cursor) {
+            (Some(_), Some(_)) => {
+                return Err(McpError::invalid_params(
+                    "Pass either offset or cursor, not both",
+                    None,
+                ))
+            }
+            (_, Some(cursor)) => parse_cursor(&cursor, query)?,
+            (offset, None) => offset.unwrap_or(0),
+        };
+        let limit = page.limit.unwrap_or(DEFAULT_PAGE_LIMIT);
+        if limit == 0 {
+            return Err(McpError::invalid_params("limit must be at least 1", None));
+        }
+
+        sort_by_location(&mut results);
+        let total = results.len();
+        let end = offset.saturating_add(limit).min(total);
+        let results = results.drain(offset.min(total)..end).collect();
+
+        Ok(Page {
+            total,
+            offset,
+            results,
+            next_cursor: (end < total).then(|| format!("{}.{:016x}", end, query)),
+        })
+    }
+
+    /// Serialize a result as JSON in the requested format
+    fn serialize_result<T: serde::Serialize>(
+        result: &T,
+        format: OutputFormat,
+    ) -> Result<CallToolResult, McpError> {
+        let json = to_json(result, format).map_err(Self::serialize_error)?;
         Ok(CallToolResult::success(vec![Content::text(json)]))
     }
 
+    /// Serialize a page of search results in the requested format
+    fn serialize_page<T: serde::Serialize + TextLine>(
+        page: Page<RootResult<T>>,
+        format: OutputFormat,
+    ) -> Result<CallToolResult, McpError> {
+        match format {
+            OutputFormat::Json | OutputFormat::CompactJson => Self::serialize_result(&page, format),
+            OutputFormat::Grouped => {
+                let grouped = group_by_file(page).map_err(Self::serialize_error)?;
+                Self::serialize_result(&grouped, format)
+            }
+            OutputFormat::Text => Ok(CallToolResult::success(vec![Content::text(to_text(&page))])),
+        }
+    }
+
+    fn serialize_error(e: serde_json::Error) -> McpError {
+        McpError::internal_error(format!("Failed to serialize result: {}", e), None)
+    }
+
     #[tool(
-        description = "Lightweight AST search for symbol definitions. Find functions, classes, methods, variables. Use include_docs=true for JSDoc/docstrings. Simple interface: just symbol name, no path required. Supports 12 languages: TypeScript, TSX, JavaScript, JSX, Python, Rust, Go, Java, HTML, CSS, SQL, Markdown."
+        description = "Lightweight AST search for symbol definitions. Find functions, classes, methods, variables. Use include_docs=true for JSDoc/docstrings. Simple interface: just symbol name, no path required. Configuration keys use dotted paths (e.g. dependencies.serde)."
     )]
     async fn symbol_definition(
         &self,
@@ -132,23 +655,38 @@ impl CodeScopeServer {
             include_docs,
             exclude_dirs,
             language,
+            roots,
+            page,
+            output_format,
         }): Parameters<DefinitionParams>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, McpError> {
-        let pipeline = self
-            .create_pipeline(exclude_dirs)
-            .await?
-            .with_language_filter(language);
-        let collector = DefinitionCollector {
-            symbol,
-            include_docs: include_docs.unwrap_or(false),
-        };
-
-        let results = pipeline.process(&collector);
-        Self::serialize_result(&results)
+        let fingerprint = query_hash(&(
+            "symbol_definition",
+            &symbol,
+            include_docs,
+            &exclude_dirs,
+            &language,
+            &roots,
+        ));
+        let selection = self.select_roots(roots).await?;
+        let results = self
+            .process_roots(&selection, exclude_dirs, language, &context, move |root| {
+                Ok(DefinitionCollector {
+                    symbol: symbol.clone(),
+                    include_docs: include_docs.unwrap_or(false),
+                    redactor: root.redactor.clone(),
+                })
+            })
+            .await?;
+        Self::serialize_page(
+            Self::paginate(results, page, fingerprint)?,
+            output_format.unwrap_or_default(),
+        )
     }
 
     #[tool(
-        description = "Find all usages of a symbol with usage classification (Import, MethodCall, PropertyAccess, TypeReference, Identifier). Use include_contexts=true for scope hierarchy. Supports 12 languages: TypeScript, TSX, JavaScript, JSX, Python, Rust, Go, Java, HTML, CSS, SQL, Markdown."
+        description = "Find all usages of a symbol with usage classification (Import, MethodCall, PropertyAccess, TypeReference, Identifier). Use include_contexts=true for scope hierarchy."
     )]
     async fn symbol_usages(
         &self,
@@ -157,25 +695,39 @@ impl CodeScopeServer {
             include_contexts,
             exclude_dirs,
             language,
+            roots,
+            page,
+            output_format,
         }): Parameters<UsagesParams>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, McpError> {
-        let pipeline = self
-            .create_pipeline(exclude_dirs)
-            .await?
-            .with_language_filter(language);
-        let collector = UsageCollector {
-            symbol,
-            include_imports: true,
-            max_contexts: if include_contexts.unwrap_or(false) {
-                2
-            } else {
-                0
-            },
-            object_filter: None,
-        };
-
-        let results = pipeline.process(&collector);
-        Self::serialize_result(&results)
+        let fingerprint = query_hash(&(
+            "symbol_usages",
+            &symbol,
+            include_contexts,
+            &exclude_dirs,
+            &language,
+            &roots,
+        ));
+        let selection = self.select_roots(roots).await?;
+        let results = self
+            .process_roots(&selection, exclude_dirs, language, &context, move |_| {
+                Ok(UsageCollector {
+                    symbol: symbol.clone(),
+                    include_imports: true,
+                    max_contexts: if include_contexts.unwrap_or(false) {
+                        2
+                    } else {
+                        0
+                    },
+                    object_filter: None,
+                })
+            })
+            .await?;
+        Self::serialize_page(
+            Self::paginate(results, page, fingerprint)?,
+            output_format.unwrap_or_default(),
+        )
     }
 
     #[tool(
@@ -188,23 +740,37 @@ impl CodeScopeServer {
             object_name,
             exclude_dirs,
             language,
+            roots,
+            page,
+            output_format,
         }): Parameters<MethodCallsParams>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, McpError> {
-        let pipeline = self
-            .create_pipeline(exclude_dirs)
-            .await?
-            .with_language_filter(language);
-        let collector = MethodCallCollector {
-            method_name,
-            object_name,
-        };
-
-        let results = pipeline.process(&collector);
-        Self::serialize_result(&results)
+        let fingerprint = query_hash(&(
+            "find_method_calls",
+            &method_name,
+            &object_name,
+            &exclude_dirs,
+            &language,
+            &roots,
+        ));
+        let selection = self.select_roots(roots).await?;
+        let results = self
+            .process_roots(&selection, exclude_dirs, language, &context, move |_| {
+                Ok(MethodCallCollector {
+                    method_name: method_name.clone(),
+                    object_name: object_name.clone(),
+                })
+            })
+            .await?;
+        Self::serialize_page(
+            Self::paginate(results, page, fingerprint)?,
+            output_format.unwrap_or_default(),
+        )
     }
 
     #[tool(
-        description = "Find import/require statements for a symbol. See where and how a module is imported across the codebase. UNIQUE: Specialized import search, more precise than grep 'import'. Supports: TypeScript, JavaScript."
+        description = "Find import/require statements for a symbol. See where and how a module is imported across the codebase. UNIQUE: Specialized import search, more precise than grep 'import'. Supports: TypeScript, JavaScript, Java, C# (using directives), Swift."
     )]
     async fn find_imports(
         &self,
@@ -212,41 +778,301 @@ impl CodeScopeServer {
             symbol,
             exclude_dirs,
             language,
+            roots,
+            page,
+            output_format,
         }): Parameters<ImportsParams>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, McpError> {
-        let pipeline = self
-            .create_pipeline(exclude_dirs)
-            .await?
-            .with_language_filter(language);
-        let collector = ImportCollector { symbol };
-
-        let results = pipeline.process(&collector);
-        Self::serialize_result(&results)
+        let fingerprint = query_hash(&("find_imports", &symbol, &exclude_dirs, &language, &roots));
+        let selection = self.select_roots(roots).await?;
+        let results = self
+            .process_roots(&selection, exclude_dirs, language, &context, move |_| {
+                Ok(ImportCollector {
+                    symbol: symbol.clone(),
+                })
+            })
+            .await?;
+        Self::serialize_page(
+            Self::paginate(results, page, fingerprint)?,
+            output_format.unwrap_or_default(),
+        )
     }
 
     #[tool(
-        description = "Search text ONLY within comments - excludes code and strings. Find TODO, FIXME, HACK, or any text in comments. UNIQUE: Comment-only search, grep cannot distinguish comments from code. Supports: TypeScript, JavaScript, Rust, Go, Java, HTML, CSS."
+        description = "Search text ONLY within comments - excludes code and strings. Find TODO, FIXME, HACK, or any text in comments (including Python docstrings). Use mode='regex' for patterns like 'TODO\\(\\w+\\)', mode='word' for whole words, case_sensitive=false to ignore case, match_positions=true to get the exact matched span and its line/column. UNIQUE: AST-based comment-only search, grep cannot distinguish comments from code. Markdown files use full-text search."
     )]
     async fn find_in_comments(
         &self,
         Parameters(CommentSearchParams {
             text,
+            mode,
+            case_sensitive,
+            match_positions,
             exclude_dirs,
             language,
+            roots,
+            page,
+            output_format,
         }): Parameters<CommentSearchParams>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, McpError> {
-        let pipeline = self
-            .create_pipeline(exclude_dirs)
-            .await?
-            .with_language_filter(language);
-        let collector = CommentCollector { text };
+        let fingerprint = query_hash(&(
+            "find_in_comments",
+            &text,
+            mode,
+            case_sensitive,
+            match_positions,
+            &exclude_dirs,
+            &language,
+            &roots,
+        ));
+        let matcher = TextMatcher::new(
+            &text,
+            mode.unwrap_or_default(),
+            case_sensitive.unwrap_or(true),
+        )
+        .map_err(|e| {
+            McpError::invalid_params(format!("Invalid pattern '{}': {}", text, e), None)
+        })?;
+
+        let selection = self.select_roots(roots).await?;
+        let results = self
+            .process_roots(&selection, exclude_dirs, language, &context, move |root| {
+                Ok(CommentCollector {
+                    matcher: matcher.clone(),
+                    match_positions: match_positions.unwrap_or(false),
+                    redactor: root.redactor.clone(),
+                })
+            })
+            .await?;
+        Self::serialize_page(
+            Self::paginate(results, page, fingerprint)?,
+            output_format.unwrap_or_default(),
+        )
+    }
+
+    #[tool(
+        description = "Run a raw tree-sitter S-expression query across the workspace and return every match with its captures (file, line/column range, node kind, text). Use for one-off structural questions, e.g. all unwrap() calls inside impl Drop. Predicates #eq?, #not-eq?, #match? are supported. Requires language, since queries are written against one grammar."
+    )]
+    async fn ast_query(
+        &self,
+        Parameters(AstQueryParams {
+            query,
+            language,
+            exclude_dirs,
+            roots,
+            page,
+            output_format,
+        }): Parameters<AstQueryParams>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let fingerprint = query_hash(&("ast_query", &query, &language, &exclude_dirs, &roots));
+        let selection = self.select_roots(roots).await?;
+        let results = self
+            .process_roots(
+                &selection,
+                exclude_dirs,
+                Some(language.clone()),
+                &context,
+                move |root| {
+                    let (language_id, language_support) =
+                        Self::resolve_language(&root.registry, &language)?;
+                    let query = compile_query(language_support.as_ref(), &query)
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                    Ok(AstQueryCollector {
+                        language: language_id,
+                        query,
+                        redactor: root.redactor.clone(),
+                    })
+                },
+            )
+            .await?;
+        Self::serialize_page(
+            Self::paginate(results, page, fingerprint)?,
+            output_format.unwrap_or_default(),
+        )
+    }
+
+    #[tool(
+        description = "Structural code search with a pattern written in the target language. Metavariables: $X matches one node, $$$ARGS matches zero or more nodes, $_ matches without capturing. Example: pattern='$X.unwrap()' language='rust', or pattern='console.log($$$ARGS)' language='typescript'. Returns each match with file, range, text and metavariable bindings. Use constraints to require metavariable text to match a regex. The pattern must contain code besides metavariables; a lone $X is rejected. Code inside strings and comments is never matched, and metavariables never bind a comment."
+    )]
+    async fn find_pattern(
+        &self,
+        Parameters(FindPatternParams {
+            pattern,
+            language,
+            constraints,
+            exclude_dirs,
+            roots,
+            page,
+            output_format,
+        }): Parameters<FindPatternParams>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let fingerprint = query_hash(&(
+            "find_pattern",
+            &pattern,
+            &language,
+            constraints
+                .as_ref()
+                .map(|c| c.iter().collect::<BTreeMap<_, _>>()),
+            &exclude_dirs,
+            &roots,
+        ));
+        let constraints = constraints.unwrap_or_default();
+        let selection = self.select_roots(roots).await?;
+        let results = self
+            .process_roots(
+                &selection,
+                exclude_dirs,
+                Some(language.clone()),
+                &context,
+                move |root| {
+                    let (language_id, language_support) =
+                        Self::resolve_language(&root.registry, &language)?;
+                    let pattern =
+                        Pattern::compile(language_support.as_ref(), &pattern, &constraints)
+                            .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                    Ok(PatternCollector {
+                        language: language_id,
+                        pattern,
+                        redactor: root.redactor.clone(),
+                    })
+                },
+            )
+            .await?;
+        Self::serialize_page(
+            Self::paginate(results, page, fingerprint)?,
+            output_format.unwrap_or_default(),
+        )
+    }
+
+    #[tool(
+        description = "Run the custom lint rules declared in the `rules` section of .mcp.json across the workspace. Each rule is a tree-sitter query or a find_pattern pattern with a message, severity and language, optionally limited to path globs. Returns diagnostics with file, range, rule id, message and severity. Pass rule ids to run a subset."
+    )]
+    async fn lint(
+        &self,
+        Parameters(LintParams {
+            rules,
+            exclude_dirs,
+            roots,
+            page,
+            output_format,
+        }): Parameters<LintParams>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let fingerprint = query_hash(&("lint", &rules, &exclude_dirs, &roots));
+        let mut selection = self.select_roots(roots).await?;
+        if selection
+            .roots
+            .iter()
+            .all(|root| root.config.rules.is_empty())
+        {
+            return Err(McpError::invalid_params(
+                "No lint rules configured. Add a `rules` section to .mcp.json",
+                None,
+            ));
+        }
+        Self::check_rule_ids(&selection, rules.as_deref())?;
+
+        // Skip roots without any of the requested rules
+        selection.roots.retain(|root| {
+            root.config
+                .rules
+                .iter()
+                .any(|rule| rules.as_ref().is_none_or(|ids| ids.contains(&rule.id)))
+        });
+        let results = self
+            .process_roots(&selection, exclude_dirs, None, &context, move |root| {
+                Ok(LintCollector {
+                    rules: Self::root_rules(root, rules.as_deref())?,
+                    root: root.path.clone(),
+                    redactor: root.redactor.clone(),
+                })
+            })
+            .await?;
+        Self::serialize_page(
+            Self::paginate(results, page, fingerprint)?,
+            output_format.unwrap_or_default(),
+        )
+    }
+
+    #[tool(
+        description = "Apply the rewrite templates (`fix`) of lint rules from .mcp.json. By default returns a preview: for each file, a unified diff, the number of fixes and a content_hash. To write, call again with write=true and expected_hashes mapping each file_path to its content_hash from the preview; files changed since the preview are reported as conflict and left untouched."
+    )]
+    async fn apply_fixes(
+        &self,
+        Parameters(ApplyFixesParams {
+            rules,
+            write,
+            expected_hashes,
+            exclude_dirs,
+            roots,
+            output_format,
+        }): Parameters<ApplyFixesParams>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let write = write.unwrap_or(false);
+        if write && expected_hashes.is_none() {
+            return Err(McpError::invalid_params(
+                "expected_hashes is required when write is true; pass the content_hash values from a preview",
+                None,
+            ));
+        }
+
+        let mut selection = self.select_roots(roots).await?;
+        Self::check_rule_ids(&selection, rules.as_deref())?;
 
-        let results = pipeline.process(&collector);
-        Self::serialize_result(&results)
+        // Skip roots without any of the requested rules that have a fix
+        selection.roots.retain(|root| {
+            root.config.rules.iter().any(|rule| {
+                rule.fix.is_some() && rules.as_ref().is_none_or(|ids| ids.contains(&rule.id))
+            })
+        });
+        if selection.roots.is_empty() {
+            return Err(McpError::invalid_params(
+                "No lint rules with a `fix` template configured in .mcp.json",
+                None,
+            ));
+        }
+
+        let mut results = self
+            .process_roots(&selection, exclude_dirs, None, &context, move |root| {
+                let rules = Self::root_rules(root, rules.as_deref())?
+                    .into_iter()
+                    .filter(|rule| rule.fix.is_some())
+                    .collect();
+                Ok(FixCollector {
+                    rules,
+                    root: root.path.clone(),
+                    redactor: root.redactor.clone(),
+                })
+            })
+            .await?;
+
+        if write {
+            let expected_hashes = expected_hashes.unwrap_or_default();
+            for RootResult { item: file_fix, .. } in &mut results {
+                let Some(expected_hash) = expected_hashes.get(&file_fix.file_path) else {
+                    continue;
+                };
+                if *expected_hash != file_fix.content_hash {
+                    file_fix.status = FixStatus::Conflict;
+                    continue;
+                }
+
+                let path = PathBuf::from(&file_fix.file_path);
+                file_fix.status = write_if_unchanged(&path, &file_fix.fixed_content, expected_hash)
+                    .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+            }
+        }
+
+        Self::serialize_result(&results, output_format.unwrap_or_default())
     }
 
     #[tool(
-        description = "Get code snippet at a specific file:line with surrounding context. Use after grep or symbol_usages to see actual code around a match. Supports all text files."
+        description = "Get code snippet at a specific file:line with surrounding context. Use after grep or symbol_usages to see actual code around a match. file_path may be relative to the workspace root; files outside the workspace roots and secrets such as .env and *.pem are refused. Supports all text files."
     )]
     async fn get_code_at_location(
         &self,
@@ -255,29 +1081,47 @@ impl CodeScopeServer {
             line,
             context_before,
             context_after,
+            output_format,
         }): Parameters<CodeAtLocationParams>,
     ) -> Result<CallToolResult, McpError> {
-        let path = PathBuf::from(&file_path);
+        let workspace = self.workspace().await?;
+        let (path, root) = workspace
+            .resolve_file(&file_path)
+            .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
         let before = context_before.unwrap_or(3);
         let after = context_after.unwrap_or(3);
 
-        let snippet = get_code_at_location(&path, line, before, after)
-            .map_err(|e| McpError::internal_error(format!("Failed to read code: {}", e), None))?;
+        let snippet = match &root.redactor {
+            Some(redactor) => {
+                self.redacted_code_at_location(root, redactor, &path, line, before, after)?
+            }
+            None => get_code_at_location(&path, line, before, after).map_err(|e| {
+                McpError::internal_error(format!("Failed to read code: {}", e), None)
+            })?,
+        };
 
-        Self::serialize_result(&snippet)
+        Self::serialize_result(&snippet, output_format.unwrap_or_default())
     }
 
     #[tool(
-        description = "Get the enclosing function/class/method at a specific line. Use after grep or symbol_usages to get full context. Example: Found 'handleError' at line 42 → get the entire function containing it. Supports: TypeScript, TSX, JavaScript, JSX, Python, Rust, Go, Java, HTML, CSS, SQL, Markdown."
+        description = "Get the enclosing function/class/method at a specific line. Use after grep or symbol_usages to get full context. Example: Found 'handleError' at line 42 → get the entire function containing it."
     )]
     async fn get_symbol_at_location(
         &self,
-        Parameters(SymbolAtLocationParams { file_path, line }): Parameters<SymbolAtLocationParams>,
+        Parameters(SymbolAtLocationParams {
+            file_path,
+            line,
+            output_format,
+        }): Parameters<SymbolAtLocationParams>,
     ) -> Result<CallToolResult, McpError> {
-        let path = PathBuf::from(&file_path);
+        let workspace = self.workspace().await?;
+        let (path, root) = workspace
+            .resolve_file(&file_path)
+            .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
 
-        // Check if file is supported
-        if self.registry.get_for_path(&path).is_none() {
+        // Check if file is supported, using the languages of its root
+        let registry = root.registry.clone();
+        if registry.get_for_path(&path).is_none() {
             return Err(McpError::invalid_params(
                 format!("Unsupported file type: {}", file_path),
                 None,
@@ -291,11 +1135,10 @@ impl CodeScopeServer {
             .get_or_read(&path)
             .map_err(|e| McpError::internal_error(format!("Failed to read file: {}", e), None))?;
 
-        let mut parser = CachedParser::new(
-            self.registry.clone(),
-            self.cache_manager.parser_cache.clone(),
-        )
-        .map_err(|e| McpError::internal_error(format!("Failed to create parser: {}", e), None))?;
+        let mut parser = CachedParser::new(registry, self.cache_manager.parser_cache.clone())
+            .map_err(|e| {
+                McpError::internal_error(format!("Failed to create parser: {}", e), None)
+            })?;
 
         let (tree, lang) = parser
             .parse_with_language(&path, &cached_content.content, cached_content.modified_time)
@@ -306,60 +1149,96 @@ impl CodeScopeServer {
         let query = lang.definitions_query();
         let mappings = lang.definition_mappings();
         let source_code = &cached_content.content;
-
-        let mut cursor = tree_sitter::QueryCursor::new();
-        use streaming_iterator::StreamingIterator;
-        let mut matches = cursor.matches(query, tree.root_node(), source_code.as_bytes());
+        let secrets = root
+            .redactor
+            .as_ref()
+            .map(|redactor| redactor.find(source_code, [&tree]));
 
         let mut best_symbol: Option<SymbolDefinition> = None;
         let mut best_size: usize = usize::MAX;
 
-        while let Some(m) = matches.next() {
-            let mut name: Option<&str> = None;
-            let mut definition_node: Option<tree_sitter::Node> = None;
-            let mut kind = None;
-
-            for capture in m.captures {
-                let capture_name = &query.capture_names()[capture.index as usize];
-
-                if *capture_name == "name" {
-                    name = Some(capture.node.utf8_text(source_code.as_bytes()).unwrap_or(""));
-                } else {
-                    // Check mappings for definition types
-                    for mapping in mappings {
-                        if *capture_name == mapping.capture_name {
-                            definition_node = Some(capture.node);
-                            kind = Some(mapping.kind);
-                            break;
+        if let Some(scanned) = lang.scan_definitions(source_code) {
+            for definition in scanned {
+                if target_line < definition.start_row || target_line > definition.end_row {
+                    continue;
+                }
+                let size = definition.end_row - definition.start_row;
+                if size < best_size {
+                    best_size = size;
+                    let code = match &secrets {
+                        Some(secrets) => secrets.mask(definition.range),
+                        None => source_code[definition.range].to_string(),
+                    };
+
+                    best_symbol = Some(SymbolDefinition {
+                        file_path: path.to_string_lossy().to_string(),
+                        start_line: definition.start_row + 1,
+                        end_line: definition.end_row + 1,
+                        node_kind: definition.kind,
+                        code,
+                        name: definition.name,
+                        docs: None,
+                    });
+                }
+            }
+        } else {
+            let mut cursor = tree_sitter::QueryCursor::new();
+            use streaming_iterator::StreamingIterator;
+            let mut matches = cursor.matches(query, tree.root_node(), source_code.as_bytes());
+
+            while let Some(m) = matches.next() {
+                let mut name: Option<&str> = None;
+                let mut definition_node: Option<tree_sitter::Node> = None;
+                let mut kind = None;
+
+                for capture in m.captures {
+                    let capture_name = &query.capture_names()[capture.index as usize];
+
+                    if *capture_name == "name" {
+                        name = Some(capture.node.utf8_text(source_code.as_bytes()).unwrap_or(""));
+                    } else {
+                        // Check mappings for definition types
+                        for mapping in mappings {
+                            if *capture_name == mapping.capture_name {
+                                definition_node = Some(capture.node);
+                                kind = Some(mapping.kind);
+                                break;
+                            }
                         }
                     }
                 }
-            }
 
-            if let (Some(name_str), Some(node), Some(symbol_kind)) = (name, definition_node, kind) {
-                let start_line = node.start_position().row;
-                let end_line = node.end_position().row;
-
-                // Check if the target line is within this symbol
-                if target_line >= start_line && target_line <= end_line {
-                    let size = end_line - start_line;
-                    // Prefer the smallest enclosing symbol
-                    if size < best_size {
-                        best_size = size;
-                        let code = node
-                            .utf8_text(source_code.as_bytes())
-                            .unwrap_or("")
-                            .to_string();
-
-                        best_symbol = Some(SymbolDefinition {
-                            file_path: file_path.clone(),
-                            start_line: start_line + 1,
-                            end_line: end_line + 1,
-                            node_kind: symbol_kind,
-                            code,
-                            name: name_str.to_string(),
-                            docs: None,
-                        });
+                if let (Some(name_str), Some(node), Some(symbol_kind)) =
+                    (name, definition_node, kind)
+                {
+                    let start_line = node.start_position().row;
+                    let end_line = node.end_position().row;
+
+                    // Check if the target line is within this symbol
+                    if target_line >= start_line && target_line <= end_line {
+                        let size = end_line - start_line;
+                        // Prefer the smallest enclosing symbol
+                        if size < best_size {
+                            best_size = size;
+                            let code = match &secrets {
+                                Some(secrets) => secrets.mask(node.byte_range()),
+                                None => node
+                                    .utf8_text(source_code.as_bytes())
+                                    .unwrap_or("")
+                                    .to_string(),
+                            };
+
+                            best_symbol = Some(SymbolDefinition {
+                                file_path: path.to_string_lossy().to_string(),
+                                start_line: start_line + 1,
+                                end_line: end_line + 1,
+                                node_kind: symbol_kind,
+                                code,
+                                name: definition_key_path(symbol_kind, node, source_code)
+                                    .unwrap_or_else(|| name_str.to_string()),
+                                docs: None,
+                            });
+                        }
                     }
                 }
             }
@@ -369,30 +1248,69 @@ impl CodeScopeServer {
             symbol: best_symbol,
         };
 
-        Self::serialize_result(&response)
+        Self::serialize_result(&response, output_format.unwrap_or_default())
     }
 
     #[tool(
         description = "Get codebase statistics: file counts, line counts (code/blank/comment), symbol distribution by language. \
         Uses AST analysis for accurate code vs comment distinction. \
-        Supports 12 languages: TypeScript, TSX, JavaScript, JSX, Python, Rust, Go, Java, HTML, CSS, SQL, Markdown."
+        With several workspace roots, the statistics combine all selected roots."
     )]
     async fn codebase_stats(
         &self,
         Parameters(StatsParams {
             exclude_dirs,
             language,
+            roots,
+            output_format,
         }): Parameters<StatsParams>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, McpError> {
-        let pipeline = self
-            .create_pipeline(exclude_dirs)
+        let selection = self.select_roots(roots).await?;
+        let file_stats = self
+            .process_roots(&selection, exclude_dirs, language, &context, move |_| {
+                Ok(StatsCollector)
+            })
             .await?
-            .with_language_filter(language);
-        let collector = StatsCollector;
-
-        let file_stats = pipeline.process(&collector);
+            .into_iter()
+            .map(|result| result.item)
+            .collect();
         let response = aggregate_statistics(file_stats);
-        Self::serialize_result(&response)
+        Self::serialize_result(&response, output_format.unwrap_or_default())
+    }
+
+    #[tool(
+        description = "List the workspace roots searched by the other tools, with their names and paths. Pass root names in the `roots` parameter of a search tool to search only those roots. When there is more than one root, each result has a `root` field naming the root it came from."
+    )]
+    async fn list_roots(&self) -> Result<CallToolResult, McpError> {
+        let workspace = self.workspace().await?;
+        Self::serialize_result(&Self::root_infos(&workspace), OutputFormat::Json)
+    }
+
+    #[tool(
+        description = "Add a workspace root inside the current roots, e.g. a vendored library or a package of a monorepo, so it can be searched with its own settings. Directories outside the current roots are refused. The root's own .mcp.json (excludes, rules, plugins) applies to files under it. Relative paths are resolved against the first root. Returns the updated list of roots."
+    )]
+    async fn add_root(
+        &self,
+        Parameters(AddRootParams { path, name }): Parameters<AddRootParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let workspace = self.workspace().await?;
+        let path = match workspace.roots().first() {
+            Some(root) => resolve_root_path(&root.path, &path),
+            None => PathBuf::from(&path),
+        };
+        if !path.is_dir() {
+            return Err(McpError::invalid_params(
+                format!("Not a directory: {}", path.display()),
+                None,
+            ));
+        }
+
+        self.add_workspace_root(name, path)
+            .await
+            .map_err(|e| McpError::invalid_params(format!("{:#}", e), None))?;
+        let workspace = self.workspace().await?;
+        Self::serialize_result(&Self::root_infos(&workspace), OutputFormat::Json)
     }
 }
 
@@ -403,27 +1321,38 @@ impl ServerHandler for CodeScopeServer {
             protocol_version: ProtocolVersion::V_2024_11_05,
             capabilities: ServerCapabilities::builder().enable_tools().build(),
             server_info: Implementation::from_build_env(),
-            instructions: Some(
-                "CodeScope: Lightweight AST-based code search (12 languages, no LSP required).\n\n\
+            instructions: Some(format!(
+                "CodeScope: Lightweight AST-based code search ({} languages, no LSP required).\n\n\
                 UNIQUE TOOLS (not available elsewhere):\n\
                 - find_method_calls: Search obj.method() patterns, filter by object (Date.now vs performance.now)\n\
                 - find_imports: Find import statements for a symbol\n\
                 - find_in_comments: Search ONLY in comments (TODO, FIXME, etc)\n\
                 - get_symbol_at_location: Get enclosing function/class at line number\n\
-                - codebase_stats: Get codebase statistics (files, lines, symbols by language)\n\n\
+                - codebase_stats: Get codebase statistics (files, lines, symbols by language)\n\
+                - ast_query: Run a raw tree-sitter query for structural questions\n\
+                - find_pattern: Structural search with code patterns and metavariables ($X, $$$ARGS)\n\
+                - lint: Run the custom lint rules declared in .mcp.json\n\
+                - apply_fixes: Preview or write the rewrite templates of lint rules\n\
+                - list_roots / add_root: Search several repositories at once; pass `roots` to target some of them\n\n\
+                PAGINATION: Search tools return {{total, offset, results, next_cursor}}, 100 results per page by default. \
+                Pass `limit` to change the page size and `cursor` (the previous next_cursor, with the same parameters) or `offset` for further pages. \
+                Pass output_format='text' (path:line:col kind name lines), 'grouped' (results grouped by file) or 'compact_json' to save tokens.\n\n\
                 GENERAL TOOLS:\n\
                 - symbol_definition: Find where symbols are defined (simple: just name, no path)\n\
                 - symbol_usages: Find all usages with classification (Import/MethodCall/etc)\n\
                 - get_code_at_location: Get code snippet at file:line\n\n\
-                LANGUAGES: TypeScript/TSX, JavaScript/JSX, Python, Rust, Go, Java, HTML, CSS, SQL, Markdown\n\n\
+                LANGUAGES: {}. Every tool supports every language unless its description says otherwise.\n\n\
                 USE CASES:\n\
                 - 'Find all Date.now() calls' → find_method_calls(method_name='now', object_name='Date')\n\
                 - 'Where is useState imported?' → find_imports(symbol='useState')\n\
                 - 'Find all TODOs in comments' → find_in_comments(text='TODO')\n\
                 - 'Get the function at line 42' → get_symbol_at_location(file_path='...', line=42)\n\
-                - 'Show codebase stats' → codebase_stats()"
-                    .to_string(),
-            ),
+                - 'Show codebase stats' → codebase_stats()\n\
+                - 'All unwrap() calls' → find_pattern(language='rust', pattern='$X.unwrap()')\n\
+                - 'All unwrap() calls (raw query)' → ast_query(language='rust', query='(call_expression function: (field_expression field: (field_identifier) @m (#eq? @m \"unwrap\"))) @call')",
+                LANGUAGES.len(),
+                LANGUAGES.join(", ")
+            )),
         }
     }
 
@@ -434,6 +1363,30 @@ impl ServerHandler for CodeScopeServer {
     ) -> Result<InitializeResult, McpError> {
         Ok(self.get_info())
     }
+
+    async fn on_initialized(&self, context: NotificationContext<RoleServer>) {
+        tracing::info!("Client initialized");
+        // The service loop only starts once this returns, so the roots request
+        // must not be awaited here. Tool calls wait for it instead, rather
+        // than searching the current directory until the roots arrive.
+        let supports_roots = context
+            .peer
+            .peer_info()
+            .is_some_and(|info| info.capabilities.roots.is_some());
+        if supports_roots {
+            self.roots_pending.send_replace(true);
+        }
+        let server = self.clone();
+        tokio::spawn(async move {
+            server.sync_client_roots(&context.peer).await;
+            server.roots_pending.send_replace(false);
+        });
+    }
+
+    async fn on_roots_list_changed(&self, context: NotificationContext<RoleServer>) {
+        // Roots added with add_root are replaced along with the others
+        self.sync_client_roots(&context.peer).await;
+    }
 }
 
 impl Default for CodeScopeServer {
@@ -441,3 +1394,72 @@ impl Default for CodeScopeServer {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_instructions_list_every_language() {
+        let registry = LanguageRegistry::new().unwrap();
+        assert_eq!(LANGUAGES.len(), registry.registered_languages().len());
+    }
+
+    #[test]
+    fn test_paginate_cursor() {
+        let results = || {
+            (1..=5)
+                .map(|line| SymbolDefinition {
+                    file_path: "src/main.ts".to_string(),
+                    start_line: line,
+                    end_line: line,
+                    node_kind: crate::symbol::types::SymbolKind::Function,
+                    code: String::new(),
+                    name: "main".to_string(),
+                    docs: None,
+                })
+                .collect::<Vec<_>>()
+        };
+        let page = |cursor: Option<&str>| PageParams {
+            limit: Some(2),
+            offset: None,
+            cursor: cursor.map(str::to_string),
+        };
+        let query = query_hash(&("symbol_definition", "main"));
+
+        let first = CodeScopeServer::paginate(results(), page(None), query).unwrap();
+        let cursor = first.next_cursor.unwrap();
+        let second = CodeScopeServer::paginate(results(), page(Some(&cursor)), query).unwrap();
+        assert_eq!(second.offset, 2);
+        assert_eq!(second.results[0].start_line, 3);
+
+        let other = query_hash(&("symbol_definition", "init"));
+        let error = CodeScopeServer::paginate(results(), page(Some(&cursor)), other)
+            .err()
+            .unwrap();
+        assert!(
+            error.message.contains("different parameters"),
+            "{}",
+            error.message
+        );
+        assert!(CodeScopeServer::paginate(results(), page(Some("2")), query).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_workspace_waits_for_client_roots() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let server = CodeScopeServer::new();
+        server.roots_pending.send_replace(true);
+
+        let waiting = tokio::spawn({
+            let server = server.clone();
+            async move { server.workspace().await.map(|w| w.roots()[0].path.clone()) }
+        });
+        tokio::task::yield_now().await;
+        assert!(!waiting.is_finished(), "should wait for the client's roots");
+
+        server.set_workspace_root(dir.path().to_path_buf()).await;
+        server.roots_pending.send_replace(false);
+        assert_eq!(waiting.await.unwrap().unwrap(), dir.path());
+    }
+}
@@ -1,2 +1,4 @@
+pub mod format;
 pub mod handler;
 pub mod types;
+pub mod workspace;
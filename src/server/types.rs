@@ -3,7 +3,8 @@ use std::collections::HashMap;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::symbol::types::{CommentMatch, SymbolDefinition, SymbolUsage};
+use crate::pipeline::Located;
+use crate::symbol::types::{CommentMatch, SearchMode, SymbolDefinition, SymbolUsage};
 
 /// Parameters for symbol_definition tool
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -17,23 +18,56 @@ pub struct DefinitionParams {
     /// Directories to exclude from search (e.g., ["dist", "node_modules"])
     pub exclude_dirs: Option<Vec<String>>,
 
+    /// Workspace roots to search, by name (default: all roots)
+    pub roots: Option<Vec<String>>,
+
     /// Filter by language (e.g., "typescript", "typescriptreact", "markdown")
     /// If not specified, searches all supported languages
     pub language: Option<String>,
+
+    /// Pagination of the results
+    #[serde(flatten)]
+    pub page: PageParams,
+
+    /// Output format: "json" (default), "compact_json", "grouped" (results
+    /// grouped by file) or "text" (one `path:line:col kind name` line per result)
+    pub output_format: Option<OutputFormat>,
 }
 
 /// Parameters for find_in_comments tool
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct CommentSearchParams {
-    /// Text to search for in comments
+    /// Text or pattern to search for in comments
     pub text: String,
 
+    /// How to interpret `text`: "literal" (default), "regex", or "word" (whole word)
+    pub mode: Option<SearchMode>,
+
+    /// Whether matching is case-sensitive (default: true)
+    pub case_sensitive: Option<bool>,
+
+    /// Return one result per match, with line/column pointing at the match and
+    /// `matched_text` holding the matched span (default: false, one result per
+    /// comment positioned at the comment start)
+    pub match_positions: Option<bool>,
+
     /// Directories to exclude from search (e.g., ["dist", "node_modules"])
     pub exclude_dirs: Option<Vec<String>>,
 
+    /// Workspace roots to search, by name (default: all roots)
+    pub roots: Option<Vec<String>>,
+
     /// Filter by language (e.g., "typescript", "typescriptreact", "markdown")
     /// If not specified, searches all supported languages
     pub language: Option<String>,
+
+    /// Pagination of the results
+    #[serde(flatten)]
+    pub page: PageParams,
+
+    /// Output format: "json" (default), "compact_json", "grouped" (results
+    /// grouped by file) or "text" (one `path:line:col kind name` line per result)
+    pub output_format: Option<OutputFormat>,
 }
 
 /// Response for find_in_comments tool
@@ -63,9 +97,20 @@ pub struct UsagesParams {
     /// Directories to exclude from search (e.g., ["dist", "node_modules"])
     pub exclude_dirs: Option<Vec<String>>,
 
+    /// Workspace roots to search, by name (default: all roots)
+    pub roots: Option<Vec<String>>,
+
     /// Filter by language (e.g., "typescript", "typescriptreact", "markdown")
     /// If not specified, searches all supported languages
     pub language: Option<String>,
+
+    /// Pagination of the results
+    #[serde(flatten)]
+    pub page: PageParams,
+
+    /// Output format: "json" (default), "compact_json", "grouped" (results
+    /// grouped by file) or "text" (one `path:line:col kind name` line per result)
+    pub output_format: Option<OutputFormat>,
 }
 
 /// Response for symbol.usages tool
@@ -87,9 +132,20 @@ pub struct MethodCallsParams {
     /// Directories to exclude from search (e.g., ["dist", "node_modules"])
     pub exclude_dirs: Option<Vec<String>>,
 
+    /// Workspace roots to search, by name (default: all roots)
+    pub roots: Option<Vec<String>>,
+
     /// Filter by language (e.g., "typescript", "typescriptreact", "markdown")
     /// If not specified, searches all supported languages
     pub language: Option<String>,
+
+    /// Pagination of the results
+    #[serde(flatten)]
+    pub page: PageParams,
+
+    /// Output format: "json" (default), "compact_json", "grouped" (results
+    /// grouped by file) or "text" (one `path:line:col kind name` line per result)
+    pub output_format: Option<OutputFormat>,
 }
 
 /// Parameters for find_imports tool
@@ -101,15 +157,252 @@ pub struct ImportsParams {
     /// Directories to exclude from search (e.g., ["dist", "node_modules"])
     pub exclude_dirs: Option<Vec<String>>,
 
+    /// Workspace roots to search, by name (default: all roots)
+    pub roots: Option<Vec<String>>,
+
     /// Filter by language (e.g., "typescript", "typescriptreact", "markdown")
     /// If not specified, searches all supported languages
     pub language: Option<String>,
+
+    /// Pagination of the results
+    #[serde(flatten)]
+    pub page: PageParams,
+
+    /// Output format: "json" (default), "compact_json", "grouped" (results
+    /// grouped by file) or "text" (one `path:line:col kind name` line per result)
+    pub output_format: Option<OutputFormat>,
+}
+
+/// Parameters for ast_query tool
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct AstQueryParams {
+    /// Tree-sitter S-expression query (e.g., "(call_expression) @call").
+    /// Predicates such as #eq? and #match? are supported.
+    pub query: String,
+
+    /// Language whose grammar the query is written against (e.g., "rust", "typescript").
+    /// Only files of this language are searched.
+    pub language: String,
+
+    /// Directories to exclude from search (e.g., ["dist", "node_modules"])
+    pub exclude_dirs: Option<Vec<String>>,
+
+    /// Workspace roots to search, by name (default: all roots)
+    pub roots: Option<Vec<String>>,
+
+    /// Pagination of the results
+    #[serde(flatten)]
+    pub page: PageParams,
+
+    /// Output format: "json" (default), "compact_json", "grouped" (results
+    /// grouped by file) or "text" (one `path:line:col kind name` line per result)
+    pub output_format: Option<OutputFormat>,
+}
+
+/// Parameters for lint tool
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct LintParams {
+    /// Ids of the rules to run (default: all rules from .mcp.json)
+    pub rules: Option<Vec<String>>,
+
+    /// Directories to exclude from search (e.g., ["dist", "node_modules"])
+    pub exclude_dirs: Option<Vec<String>>,
+
+    /// Workspace roots to search, by name (default: all roots)
+    pub roots: Option<Vec<String>>,
+
+    /// Pagination of the results
+    #[serde(flatten)]
+    pub page: PageParams,
+
+    /// Output format: "json" (default), "compact_json", "grouped" (results
+    /// grouped by file) or "text" (one `path:line:col kind name` line per result)
+    pub output_format: Option<OutputFormat>,
+}
+
+/// Parameters for apply_fixes tool
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ApplyFixesParams {
+    /// Ids of the rules whose fixes to apply (default: all rules with a `fix` template)
+    pub rules: Option<Vec<String>>,
+
+    /// Write the fixed files to disk (default: false, preview only)
+    pub write: Option<bool>,
+
+    /// Content hash of each file to write, keyed by file path, as returned by a preview.
+    /// Required when `write` is true; files without a matching hash are not written.
+    pub expected_hashes: Option<HashMap<String, String>>,
+
+    /// Directories to exclude from search (e.g., ["dist", "node_modules"])
+    pub exclude_dirs: Option<Vec<String>>,
+
+    /// Workspace roots to search, by name (default: all roots)
+    pub roots: Option<Vec<String>>,
+
+    /// Output format: "json" (default) or "compact_json"
+    pub output_format: Option<OutputFormat>,
+}
+
+/// Parameters for find_pattern tool
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct FindPatternParams {
+    /// Code pattern in the target language with metavariables, e.g. "$X.unwrap()"
+    /// or "console.log($$$ARGS)". $NAME matches one node, $$$NAME matches zero or
+    /// more nodes, $_ and $$$ match without capturing.
+    pub pattern: String,
+
+    /// Language the pattern is written in (e.g., "rust", "typescript").
+    /// Only files of this language are searched.
+    pub language: String,
+
+    /// Regular expressions that metavariable text must match, keyed by
+    /// metavariable name without "$" (e.g., {"X": "^self\."})
+    pub constraints: Option<HashMap<String, String>>,
+
+    /// Directories to exclude from search (e.g., ["dist", "node_modules"])
+    pub exclude_dirs: Option<Vec<String>>,
+
+    /// Workspace roots to search, by name (default: all roots)
+    pub roots: Option<Vec<String>>,
+
+    /// Pagination of the results
+    #[serde(flatten)]
+    pub page: PageParams,
+
+    /// Output format: "json" (default), "compact_json", "grouped" (results
+    /// grouped by file) or "text" (one `path:line:col kind name` line per result)
+    pub output_format: Option<OutputFormat>,
+}
+
+/// Output format of a tool
+///
+/// `grouped` and `text` apply to the search tools; other tools return
+/// compact JSON for them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputFormat {
+    /// Pretty-printed JSON
+    #[default]
+    Json,
+    /// JSON without whitespace
+    CompactJson,
+    /// Compact JSON with the results of each file listed under its path
+    Grouped,
+    /// One `path:line:col kind name` line per result
+    Text,
+}
+
+/// Pagination parameters of the search tools
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct PageParams {
+    /// Maximum number of results to return (default: 100)
+    pub limit: Option<usize>,
+
+    /// Number of results to skip (default: 0)
+    pub offset: Option<usize>,
+
+    /// Opaque cursor from the `next_cursor` of a previous response, to get
+    /// the next page instead of passing `offset`. Only valid with the same
+    /// parameters as that request.
+    pub cursor: Option<String>,
+}
+
+/// A page of search results
+#[derive(Debug, Serialize)]
+pub struct Page<T> {
+    /// Number of results across all pages
+    pub total: usize,
+
+    /// Number of results before this page
+    pub offset: usize,
+
+    /// Results on this page, ordered by file path, line and column
+    pub results: Vec<T>,
+
+    /// Cursor of the next page, if more results remain
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+/// A page of search results grouped by file
+#[derive(Debug, Serialize)]
+pub struct GroupedPage {
+    /// Number of results across all pages
+    pub total: usize,
+
+    /// Number of results before this page
+    pub offset: usize,
+
+    /// Files with results on this page, ordered by path
+    pub files: Vec<FileResults>,
+
+    /// Cursor of the next page, if more results remain
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+/// The results on a page from one file
+#[derive(Debug, Serialize)]
+pub struct FileResults {
+    /// File path shared by the results
+    pub file_path: String,
+
+    /// Name of the root the file belongs to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub root: Option<String>,
+
+    /// Results without their `file_path`
+    pub results: Vec<serde_json::Value>,
+}
+
+/// A result tagged with the workspace root it came from
+///
+/// `root` is only set when the server has more than one root, so results
+/// from a single-root workspace are unchanged.
+#[derive(Debug, Serialize)]
+pub struct RootResult<T> {
+    /// Name of the root the result came from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub root: Option<String>,
+
+    #[serde(flatten)]
+    pub item: T,
+}
+
+impl<T: Located> Located for RootResult<T> {
+    fn location(&self) -> (&str, usize, usize) {
+        self.item.location()
+    }
+}
+
+/// Parameters for add_root tool
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct AddRootParams {
+    /// Root directory, absolute or relative to the first root
+    pub path: String,
+
+    /// Root name used by the `roots` parameter (default: the directory name)
+    pub name: Option<String>,
+}
+
+/// A workspace root, as returned by list_roots and add_root
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RootInfo {
+    /// Root name
+    pub name: String,
+    /// Root directory
+    pub path: String,
+    /// Problems loading the root's languages, e.g. invalid query overrides.
+    /// Search tools fail on the root until they are fixed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
 }
 
 /// Parameters for get_code_at_location tool
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct CodeAtLocationParams {
-    /// File path to read from
+    /// File to read, absolute or relative to a workspace root. Files outside
+    /// the roots and denied files such as `.env` and `*.pem` are refused.
     pub file_path: String,
 
     /// Line number (1-indexed)
@@ -120,16 +413,23 @@ pub struct CodeAtLocationParams {
 
     /// Lines to include after the target line (default: 3)
     pub context_after: Option<usize>,
+
+    /// Output format: "json" (default) or "compact_json"
+    pub output_format: Option<OutputFormat>,
 }
 
 /// Parameters for get_symbol_at_location tool
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SymbolAtLocationParams {
-    /// File path to read from
+    /// File to read, absolute or relative to a workspace root. Files outside
+    /// the roots and denied files such as `.env` and `*.pem` are refused.
     pub file_path: String,
 
     /// Line number (1-indexed)
     pub line: usize,
+
+    /// Output format: "json" (default) or "compact_json"
+    pub output_format: Option<OutputFormat>,
 }
 
 /// Response for get_symbol_at_location tool
@@ -146,9 +446,15 @@ pub struct StatsParams {
     /// Directories to exclude from analysis (e.g., ["dist", "node_modules"])
     pub exclude_dirs: Option<Vec<String>>,
 
+    /// Workspace roots to search, by name (default: all roots)
+    pub roots: Option<Vec<String>>,
+
     /// Filter by language (e.g., "typescript", "rust")
     /// If not specified, analyzes all supported languages
     pub language: Option<String>,
+
+    /// Output format: "json" (default) or "compact_json"
+    pub output_format: Option<OutputFormat>,
 }
 
 /// Response for codebase_stats tool
@@ -0,0 +1,721 @@
+//! Workspace roots
+//!
+//! The server searches one or more named roots, e.g. a service repository
+//! and the shared library it depends on. Each root has its own `.mcp.json`
+//! and language registry, so excludes, rules, plugins, query overrides and
+//! file types apply only to the root that declares them. A root can list
+//! further roots in the `roots` section of its `.mcp.json`.
+//!
+//! Language plugins are native code shipped with the repository, and roots
+//! come from the client and from other roots' `roots` sections, so opening a
+//! root must not run them by default. A root's `language_plugins` are only
+//! loaded when the server was started with [`ALLOW_PLUGINS_ENV`] set.
+//!
+//! A root whose `.mcp.json` cannot be read or parsed is refused rather than
+//! served with the default configuration, which would ignore its deny list
+//! and redaction settings.
+
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::config::CodeScopeConfig;
+use crate::language::LanguageRegistry;
+use crate::symbol::redact::Redactor;
+
+/// Environment variable that lets roots load their language plugins (`1` or `true`)
+pub const ALLOW_PLUGINS_ENV: &str = "CODESCOPE_ALLOW_PLUGINS";
+
+/// Whether the server was started with language plugins allowed
+pub fn plugins_allowed_by_env() -> bool {
+    std::env::var(ALLOW_PLUGINS_ENV).is_ok_and(|value| matches!(value.as_str(), "1" | "true"))
+}
+
+/// A named workspace root
+pub struct WorkspaceRoot {
+    /// Name used by the `roots` tool parameter and in tagged results
+    pub name: String,
+    /// Root directory
+    pub path: PathBuf,
+    /// Configuration loaded from the root's `.mcp.json`
+    pub config: CodeScopeConfig,
+    /// Languages of the root, including its plugins and overrides
+    pub registry: Arc<LanguageRegistry>,
+    /// Secret redaction, if enabled in the root's configuration
+    pub redactor: Option<Arc<Redactor>>,
+    /// Problems loading the root's languages, e.g. invalid query overrides
+    pub errors: Vec<String>,
+}
+
+impl WorkspaceRoot {
+    /// Load a root's configuration and languages
+    ///
+    /// The root's `language_plugins` are ignored unless `allow_plugins` is
+    /// set. If the root's languages fail to load, the built-in registry is
+    /// used; if its configuration fails to load, the root is refused. Query
+    /// overrides that fail to load are recorded in `errors`.
+    pub fn load(
+        name: String,
+        path: PathBuf,
+        builtin: &Arc<LanguageRegistry>,
+        allow_plugins: bool,
+    ) -> Result<Self> {
+        let mut config = CodeScopeConfig::load(&path)?;
+        if !allow_plugins {
+            if let Some(dir) = config.language_plugins.take() {
+                tracing::warn!(
+                    "Ignoring language plugins '{}' of root '{}': set {}=1 to load plugins",
+                    dir,
+                    name,
+                    ALLOW_PLUGINS_ENV
+                );
+            }
+        }
+        let mut errors = Vec::new();
+        let registry = match LanguageRegistry::for_workspace(&path, &config) {
+            Ok(registry) => {
+                errors.extend(registry.override_errors().iter().cloned());
+                Arc::new(registry)
+            }
+            Err(e) => {
+                tracing::warn!("Failed to load languages for root '{}': {:#}", name, e);
+                errors.push(format!("Failed to load languages: {:#}", e));
+                builtin.clone()
+            }
+        };
+        let redactor = config
+            .redaction
+            .enabled
+            .then(|| Arc::new(Redactor::new(&config.redaction)));
+
+        Ok(Self {
+            name,
+            path,
+            config,
+            registry,
+            redactor,
+            errors,
+        })
+    }
+}
+
+/// The roots searched by the server, in the order they were added
+#[derive(Clone, Default)]
+pub struct Workspace {
+    roots: Vec<Arc<WorkspaceRoot>>,
+    /// Whether roots load their language plugins
+    allow_plugins: bool,
+    /// Roots whose configuration failed to load, with the reason
+    refused: Vec<(PathBuf, String)>,
+    /// Directories given by the client or the server, which roots added by
+    /// a tool call or declared in `.mcp.json` must lie inside
+    bounds: Vec<PathBuf>,
+}
+
+impl Workspace {
+    /// Create an empty workspace
+    pub fn new(allow_plugins: bool) -> Self {
+        Self {
+            roots: Vec::new(),
+            allow_plugins,
+            refused: Vec::new(),
+            bounds: Vec::new(),
+        }
+    }
+
+    /// Load roots, each followed by the roots its configuration declares
+    ///
+    /// Roots that fail to load are skipped and listed by [`Self::refused`].
+    pub fn load(
+        roots: Vec<(Option<String>, PathBuf)>,
+        builtin: &Arc<LanguageRegistry>,
+        allow_plugins: bool,
+    ) -> Self {
+        let mut workspace = Self::new(allow_plugins);
+        for (name, path) in roots {
+            // The error is logged and recorded by `insert`
+            let _ = workspace.add_base(name, path, builtin);
+        }
+        workspace
+    }
+
+    /// Add a base root, given by the client or the server, along with the
+    /// roots its configuration declares
+    ///
+    /// Roots added later with [`Self::add`] or declared in `.mcp.json` must
+    /// lie inside a base root. See [`Self::add`] for naming and errors.
+    pub fn add_base(
+        &mut self,
+        name: Option<String>,
+        path: PathBuf,
+        builtin: &Arc<LanguageRegistry>,
+    ) -> Result<Arc<WorkspaceRoot>> {
+        self.bounds.push(path.clone());
+        self.add_with_declared(name, path, builtin)
+    }
+
+    /// Add a root requested through a tool call, along with the roots its
+    /// configuration declares
+    ///
+    /// The root must lie inside a base root, so a tool call cannot widen the
+    /// directories the server reads. A root already in the workspace is
+    /// reloaded in place, keeping its name unless a new one is given. New
+    /// roots are named after their directory by default, with a numeric
+    /// suffix if the name is taken. Declared roots are resolved against the
+    /// declaring root, must also lie inside a base root, and do not add roots
+    /// of their own. Returns the added root, or the error if it is outside
+    /// the base roots or its configuration failed to load; a declared root
+    /// that is refused is skipped.
+    pub fn add(
+        &mut self,
+        name: Option<String>,
+        path: PathBuf,
+        builtin: &Arc<LanguageRegistry>,
+    ) -> Result<Arc<WorkspaceRoot>> {
+        if !self.is_within_bounds(&path) {
+            bail!("{} is outside the workspace roots", path.display());
+        }
+        self.add_with_declared(name, path, builtin)
+    }
+
+    fn add_with_declared(
+        &mut self,
+        name: Option<String>,
+        path: PathBuf,
+        builtin: &Arc<LanguageRegistry>,
+    ) -> Result<Arc<WorkspaceRoot>> {
+        let root = self.insert(name, path, builtin)?;
+        for declared in &root.config.roots {
+            let path = resolve_root_path(&root.path, &declared.path);
+            if self.roots.iter().any(|existing| existing.path == path) {
+                continue;
+            }
+            if !self.is_within_bounds(&path) {
+                let reason = format!(
+                    "Refusing root '{}' declared by '{}': {} is outside the workspace roots",
+                    declared.path,
+                    root.name,
+                    path.display()
+                );
+                tracing::error!("{}", reason);
+                self.refused.push((path, reason));
+                continue;
+            }
+            // The error is logged and recorded by `insert`
+            let _ = self.insert(declared.name.clone(), path, builtin);
+        }
+        Ok(root)
+    }
+
+    /// Whether a directory lies inside a base root, after resolving symlinks
+    fn is_within_bounds(&self, path: &Path) -> bool {
+        let Ok(canonical) = path.canonicalize() else {
+            return false;
+        };
+        self.bounds.iter().any(|bound| {
+            bound
+                .canonicalize()
+                .is_ok_and(|bound| canonical.starts_with(bound))
+        })
+    }
+
+    fn insert(
+        &mut self,
+        name: Option<String>,
+        path: PathBuf,
+        builtin: &Arc<LanguageRegistry>,
+    ) -> Result<Arc<WorkspaceRoot>> {
+        let existing = self.roots.iter().position(|root| root.path == path);
+        let name = match existing {
+            Some(i) if name.as_ref().is_none_or(|name| *name == self.roots[i].name) => {
+                self.roots[i].name.clone()
+            }
+            _ => self.unique_name(&name.unwrap_or_else(|| root_name(&path))),
+        };
+
+        self.refused.retain(|(refused, _)| *refused != path);
+        let root =
+            match WorkspaceRoot::load(name.clone(), path.clone(), builtin, self.allow_plugins) {
+                Ok(root) => Arc::new(root),
+                Err(e) => {
+                    let reason = format!("Refusing root '{}': {:#}", name, e);
+                    tracing::error!("{}", reason);
+                    if let Some(i) = existing {
+                        self.roots.remove(i);
+                    }
+                    self.refused.push((path, reason));
+                    return Err(e.context(format!("Refusing root '{}'", name)));
+                }
+            };
+        match existing {
+            Some(i) => self.roots[i] = root.clone(),
+            None => self.roots.push(root.clone()),
+        }
+        Ok(root)
+    }
+
+    fn unique_name(&self, name: &str) -> String {
+        let taken = |candidate: &str| self.roots.iter().any(|root| root.name == candidate);
+        if !taken(name) {
+            return name.to_string();
+        }
+        (2..)
+            .map(|n| format!("{}-{}", name, n))
+            .find(|candidate| !taken(candidate))
+            .expect("unbounded suffixes")
+    }
+
+    /// All roots
+    pub fn roots(&self) -> &[Arc<WorkspaceRoot>] {
+        &self.roots
+    }
+
+    /// Whether the workspace has no roots
+    pub fn is_empty(&self) -> bool {
+        self.roots.is_empty()
+    }
+
+    /// Why roots were refused, for roots whose configuration failed to load
+    pub fn refused(&self) -> impl Iterator<Item = &str> {
+        self.refused.iter().map(|(_, reason)| reason.as_str())
+    }
+
+    /// Select roots by name, or all roots if no names are given
+    pub fn select(&self, names: Option<&[String]>) -> Result<Vec<Arc<WorkspaceRoot>>> {
+        let Some(names) = names.filter(|names| !names.is_empty()) else {
+            return Ok(self.roots.clone());
+        };
+
+        if let Some(unknown) = names
+            .iter()
+            .find(|name| !self.roots.iter().any(|root| &root.name == *name))
+        {
+            let available: Vec<_> = self.roots.iter().map(|root| root.name.as_str()).collect();
+            bail!(
+                "Unknown root '{}' (available: {})",
+                unknown,
+                available.join(", ")
+            );
+        }
+
+        Ok(self
+            .roots
+            .iter()
+            .filter(|root| names.contains(&root.name))
+            .cloned()
+            .collect())
+    }
+
+    /// Resolve a file path given to a tool
+    ///
+    /// Relative paths are tried against each root in order. The path is
+    /// canonicalised, so `..` and symlinks cannot leave the roots, and must be
+    /// inside a root (the innermost one if roots are nested) and not on that
+    /// root's deny list. Returns the path under the root's directory, as
+    /// reported by the search tools, along with the root.
+    pub fn resolve_file(&self, file_path: &str) -> Result<(PathBuf, &Arc<WorkspaceRoot>)> {
+        // Missing files and files outside the roots are reported alike, so
+        // the error does not reveal which files exist elsewhere
+        let outside = || anyhow!("{} is not a file in the workspace roots", file_path);
+
+        let path = Path::new(file_path);
+        let canonical = if path.is_absolute() {
+            path.canonicalize().ok()
+        } else {
+            self.roots
+                .iter()
+                .find_map(|root| root.path.join(path).canonicalize().ok())
+        }
+        .filter(|canonical| canonical.is_file())
+        .ok_or_else(outside)?;
+
+        let (root, relative) = self
+            .roots
+            .iter()
+            .filter_map(|root| {
+                let root_path = root.path.canonicalize().ok()?;
+                let relative = canonical.strip_prefix(&root_path).ok()?.to_path_buf();
+                Some((root, relative))
+            })
+            .min_by_key(|(_, relative)| relative.components().count())
+            .ok_or_else(outside)?;
+
+        if root.config.is_denied_file(&relative) {
+            bail!("Access to {} is denied", file_path);
+        }
+        Ok((root.path.join(relative), root))
+    }
+}
+
+/// Resolve a root path against another root
+///
+/// `.` and `..` are resolved lexically, so `../shared-lib` names a sibling
+/// directory in reported file paths.
+pub fn resolve_root_path(base: &Path, path: impl AsRef<Path>) -> PathBuf {
+    let mut resolved = PathBuf::new();
+    for component in base.join(path).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            component => resolved.push(component),
+        }
+    }
+    resolved
+}
+
+/// Convert a root URI sent by the client to a path
+///
+/// Returns `None` for URIs other than local `file://` URIs.
+pub fn root_path_from_uri(uri: &str) -> Option<PathBuf> {
+    let rest = uri.strip_prefix("file://")?;
+    let (host, path) = rest.split_at(rest.find('/')?);
+    if !host.is_empty() && !host.eq_ignore_ascii_case("localhost") {
+        return None;
+    }
+
+    let path = percent_decode(path)?;
+    // file:///C:/src -> C:/src
+    #[cfg(windows)]
+    let path = match path.strip_prefix('/') {
+        Some(rest) if rest.as_bytes().get(1) == Some(&b':') => rest.to_string(),
+        _ => path,
+    };
+    Some(PathBuf::from(path))
+}
+
+/// Decode `%XX` escapes in a URI path
+fn percent_decode(input: &str) -> Option<String> {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = input.get(i + 1..i + 3)?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+/// Default name of a root: its directory name
+fn root_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn builtin() -> Arc<LanguageRegistry> {
+        Arc::new(LanguageRegistry::new().unwrap())
+    }
+
+    fn names(workspace: &Workspace) -> Vec<&str> {
+        workspace
+            .roots()
+            .iter()
+            .map(|root| root.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_declared_roots() {
+        let dir = TempDir::new().unwrap();
+        let service = dir.path().join("service");
+        std::fs::create_dir_all(service.join("packages/shared-lib")).unwrap();
+        std::fs::write(
+            service.join(".mcp.json"),
+            r#"{"roots": [{"name": "shared", "path": "packages/../packages/shared-lib"}]}"#,
+        )
+        .unwrap();
+
+        let workspace = Workspace::load(vec![(None, service.clone())], &builtin(), false);
+
+        assert_eq!(names(&workspace), ["service", "shared"]);
+        assert_eq!(
+            workspace.roots()[1].path,
+            service.join("packages/shared-lib"),
+            "declared roots resolve against the declaring root"
+        );
+    }
+
+    #[test]
+    fn test_roots_stay_inside_base_roots() {
+        let dir = TempDir::new().unwrap();
+        let service = dir.path().join("service");
+        let outside = dir.path().join("outside");
+        std::fs::create_dir_all(service.join("vendor")).unwrap();
+        std::fs::create_dir_all(&outside).unwrap();
+        std::fs::write(
+            service.join(".mcp.json"),
+            r#"{"roots": [{"path": "../outside"}, {"path": "../../.."}]}"#,
+        )
+        .unwrap();
+
+        let builtin = builtin();
+        let mut workspace = Workspace::load(vec![(None, service.clone())], &builtin, false);
+        assert_eq!(names(&workspace), ["service"]);
+        assert_eq!(workspace.refused().count(), 2);
+
+        assert!(workspace.add(None, outside.clone(), &builtin).is_err());
+        assert!(workspace.add(None, PathBuf::from("/"), &builtin).is_err());
+        assert!(workspace
+            .add(None, service.join("../outside"), &builtin)
+            .is_err());
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(&outside, service.join("link")).unwrap();
+            assert!(workspace.add(None, service.join("link"), &builtin).is_err());
+        }
+        assert_eq!(names(&workspace), ["service"]);
+
+        workspace
+            .add(None, service.join("vendor"), &builtin)
+            .unwrap();
+        assert_eq!(names(&workspace), ["service", "vendor"]);
+    }
+
+    #[test]
+    fn test_plugins_need_opt_in() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join(".mcp.json"),
+            r#"{"language_plugins": "languages", "file_types": {"extensions": {"tpl": "html"}}}"#,
+        )
+        .unwrap();
+
+        let workspace = Workspace::load(vec![(None, dir.path().to_path_buf())], &builtin(), false);
+        let root = &workspace.roots()[0];
+
+        assert!(root.config.language_plugins.is_none());
+        assert!(
+            root.registry.get_for_path(Path::new("page.tpl")).is_some(),
+            "the root's other settings still apply"
+        );
+    }
+
+    #[test]
+    fn test_invalid_config_refuses_root() {
+        let dir = TempDir::new().unwrap();
+        let service = dir.path().join("service");
+        let broken = dir.path().join("broken");
+        std::fs::create_dir_all(&service).unwrap();
+        std::fs::create_dir_all(&broken).unwrap();
+        std::fs::write(
+            service.join(".mcp.json"),
+            r#"{"roots": [{"path": "../broken"}]}"#,
+        )
+        .unwrap();
+        std::fs::write(broken.join(".mcp.json"), r#"{"deny_files": ["*.db"],}"#).unwrap();
+
+        let workspace = Workspace::load(vec![(None, service.clone())], &builtin(), false);
+        assert_eq!(names(&workspace), ["service"]);
+        assert_eq!(workspace.refused().count(), 1);
+
+        let mut workspace = Workspace::default();
+        let error = workspace
+            .add_base(None, broken.clone(), &builtin())
+            .err()
+            .unwrap();
+        assert!(
+            format!("{:#}", error).contains("Failed to parse"),
+            "{:#}",
+            error
+        );
+        assert!(workspace.is_empty());
+        assert!(workspace
+            .resolve_file(broken.join(".mcp.json").to_str().unwrap())
+            .is_err());
+    }
+
+    #[test]
+    fn test_invalid_query_overrides_are_recorded() {
+        let dir = TempDir::new().unwrap();
+        let queries = dir
+            .path()
+            .join(crate::language::QUERY_OVERRIDE_DIR)
+            .join("rust");
+        std::fs::create_dir_all(&queries).unwrap();
+        std::fs::write(
+            queries.join("definitions.scm"),
+            "(no_such_node) @definition.function",
+        )
+        .unwrap();
+
+        let workspace = Workspace::load(vec![(None, dir.path().to_path_buf())], &builtin(), false);
+        let errors = &workspace.roots()[0].errors;
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(
+            errors[0].contains("definitions.scm:1:")
+                && errors[0].contains("in pattern: (no_such_node)"),
+            "{}",
+            errors[0]
+        );
+    }
+
+    #[test]
+    fn test_unique_names() {
+        let dir = TempDir::new().unwrap();
+        let builtin = builtin();
+        let mut workspace = Workspace::default();
+
+        workspace
+            .add_base(None, dir.path().join("api/src"), &builtin)
+            .unwrap();
+        workspace
+            .add_base(None, dir.path().join("web/src"), &builtin)
+            .unwrap();
+        workspace
+            .add_base(Some("docs".to_string()), dir.path().join("docs"), &builtin)
+            .unwrap();
+        // Re-adding a root reloads it under its existing name
+        workspace
+            .add_base(None, dir.path().join("web/src"), &builtin)
+            .unwrap();
+
+        assert_eq!(names(&workspace), ["src", "src-2", "docs"]);
+    }
+
+    #[test]
+    fn test_select() {
+        let dir = TempDir::new().unwrap();
+        let builtin = builtin();
+        let mut workspace = Workspace::default();
+        workspace
+            .add_base(Some("service".to_string()), dir.path().join("a"), &builtin)
+            .unwrap();
+        workspace
+            .add_base(Some("shared".to_string()), dir.path().join("b"), &builtin)
+            .unwrap();
+
+        assert_eq!(workspace.select(None).unwrap().len(), 2);
+        assert_eq!(workspace.select(Some(&[])).unwrap().len(), 2);
+
+        let selected = workspace.select(Some(&["shared".to_string()])).unwrap();
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].name, "shared");
+
+        let error = workspace
+            .select(Some(&["other".to_string()]))
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Unknown root 'other' (available: service, shared)"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_root_path_from_uri() {
+        assert_eq!(
+            root_path_from_uri("file:///home/me/my%20project"),
+            Some(PathBuf::from("/home/me/my project"))
+        );
+        assert_eq!(
+            root_path_from_uri("file://localhost/srv/app"),
+            Some(PathBuf::from("/srv/app"))
+        );
+        assert_eq!(root_path_from_uri("file://server/share"), None);
+        assert_eq!(root_path_from_uri("https://example.com/repo"), None);
+        assert_eq!(root_path_from_uri("file:///bad%zz"), None);
+    }
+
+    #[test]
+    fn test_resolve_file() {
+        let dir = TempDir::new().unwrap();
+        let service = dir.path().join("service");
+        std::fs::create_dir_all(service.join("src")).unwrap();
+        std::fs::create_dir_all(service.join("vendor")).unwrap();
+        std::fs::write(service.join("src/main.ts"), "").unwrap();
+        std::fs::write(service.join("vendor/lib.ts"), "").unwrap();
+        std::fs::write(dir.path().join("outside.ts"), "").unwrap();
+
+        let builtin = builtin();
+        let mut workspace = Workspace::default();
+        workspace
+            .add_base(Some("service".to_string()), service.clone(), &builtin)
+            .unwrap();
+        workspace
+            .add(Some("vendor".to_string()), service.join("vendor"), &builtin)
+            .unwrap();
+
+        let resolve = |file_path: &str| {
+            workspace
+                .resolve_file(file_path)
+                .map(|(path, root)| (path, root.name.clone()))
+        };
+
+        let (path, root) = resolve("src/main.ts").unwrap();
+        assert_eq!(path, service.join("src/main.ts"));
+        assert_eq!(root, "service");
+
+        // Nested roots take precedence over the roots containing them
+        let (path, root) = resolve(&service.join("vendor/lib.ts").to_string_lossy()).unwrap();
+        assert_eq!(path, service.join("vendor/lib.ts"));
+        assert_eq!(root, "vendor");
+
+        let (path, _) = resolve("src/../src/main.ts").unwrap();
+        assert_eq!(path, service.join("src/main.ts"));
+
+        for escaping in [
+            "../outside.ts",
+            &dir.path().join("outside.ts").to_string_lossy(),
+            "/etc/passwd",
+            "src/missing.ts",
+        ] {
+            let error = resolve(escaping).unwrap_err();
+            assert!(
+                error
+                    .to_string()
+                    .contains("is not a file in the workspace roots"),
+                "{}: {}",
+                escaping,
+                error
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_file_symlink_escape() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().join("root");
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(dir.path().join("secret.txt"), "").unwrap();
+        std::os::unix::fs::symlink(dir.path().join("secret.txt"), root.join("link.txt")).unwrap();
+
+        let workspace = Workspace::load(vec![(None, root)], &builtin(), false);
+        assert!(workspace.resolve_file("link.txt").is_err());
+    }
+
+    #[test]
+    fn test_resolve_file_deny_list() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join(".env"), "TOKEN=x").unwrap();
+        std::fs::write(dir.path().join("server.pem"), "").unwrap();
+        std::fs::write(dir.path().join("internal.txt"), "").unwrap();
+        std::fs::write(
+            dir.path().join(".mcp.json"),
+            r#"{"deny_files": ["internal.*"]}"#,
+        )
+        .unwrap();
+
+        let workspace = Workspace::load(vec![(None, dir.path().to_path_buf())], &builtin(), false);
+        for denied in [".env", "server.pem", "internal.txt"] {
+            let error = workspace.resolve_file(denied).err().unwrap();
+            assert_eq!(error.to_string(), format!("Access to {} is denied", denied));
+        }
+        assert!(workspace.resolve_file(".mcp.json").is_ok());
+    }
+}
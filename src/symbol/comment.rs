@@ -1,309 +1,178 @@
 use std::path::Path;
 
 use anyhow::Result;
+use streaming_iterator::StreamingIterator;
+use tree_sitter::{Query, Tree};
 
+use crate::symbol::matcher::TextMatcher;
+use crate::symbol::redact::{source_text, Secrets};
 use crate::symbol::types::{CodeSnippet, CommentMatch, CommentType};
 
-/// Find comments containing the specified text in a file (C-style: // and /* */)
-pub fn find_comments_in_file(file_path: &Path, search_text: &str) -> Result<Vec<CommentMatch>> {
-    find_comments_with_prefix(file_path, search_text, "//")
-}
-
-/// Find comments containing the specified text in a SQL file (-- and /* */)
-pub fn find_comments_in_sql_file(file_path: &Path, search_text: &str) -> Result<Vec<CommentMatch>> {
-    find_comments_with_prefix(file_path, search_text, "--")
-}
-
-/// State of the comment scanner
-#[derive(Default)]
-enum ScanState {
-    /// Not inside a block comment
-    #[default]
-    Normal,
-    /// Inside a block comment
-    InBlockComment {
-        start_line: usize,
-        start_col: usize,
-        content: String,
-    },
-}
-
-/// Scanner for finding comments in source code
-struct CommentScanner<'a> {
-    file_path_str: String,
-    search_text: &'a str,
-    single_line_prefix: &'a str,
-    state: ScanState,
-    matches: Vec<CommentMatch>,
-}
-
-impl<'a> CommentScanner<'a> {
-    fn new(file_path: &Path, search_text: &'a str, single_line_prefix: &'a str) -> Self {
-        Self {
-            file_path_str: file_path.to_string_lossy().to_string(),
-            search_text,
-            single_line_prefix,
-            state: ScanState::Normal,
-            matches: Vec::new(),
-        }
-    }
-
-    /// Process all lines in the source
-    fn scan(mut self, source: &str) -> Vec<CommentMatch> {
-        for (line_num, line) in source.lines().enumerate() {
-            let line_1indexed = line_num + 1;
-            self.process_line(line, line_1indexed);
-        }
+/// Find comments matching the given matcher in a parsed syntax tree
+///
+/// Uses the language's comments query, so only real comment nodes (and
+/// docstrings, where the language has them) are searched. Text inside
+/// string literals or URLs is never mistaken for a comment.
+///
+/// By default one result is returned per matching comment, positioned at the
+/// comment start. With `match_positions`, one result is returned per match,
+/// positioned at the match and carrying the matched text alongside the full
+/// comment content.
+///
+/// Secrets found in the file, if given, are masked in the returned content
+/// and matched text.
+pub fn find_comments_in_tree(
+    tree: &Tree,
+    source: &str,
+    query: &Query,
+    file_path: &Path,
+    matcher: &TextMatcher,
+    match_positions: bool,
+    secrets: Option<&Secrets>,
+) -> Vec<CommentMatch> {
+    let file_path_str = file_path.to_string_lossy().to_string();
+    let mut matches = Vec::new();
 
-        // Handle case where file ends while still in a block comment
-        self.finalize();
-        self.matches
-    }
+    let mut cursor = tree_sitter::QueryCursor::new();
+    let mut query_matches = cursor.matches(query, tree.root_node(), source.as_bytes());
 
-    /// Process a single line
-    fn process_line(&mut self, line: &str, line_1indexed: usize) {
-        match &mut self.state {
-            ScanState::InBlockComment { .. } => {
-                self.process_block_comment_continuation(line, line_1indexed);
-            }
-            ScanState::Normal => {
-                self.scan_for_comments(line, line_1indexed);
+    while let Some(m) = query_matches.next() {
+        for capture in m.captures {
+            let capture_name = query.capture_names()[capture.index as usize];
+            if capture_name != "comment" && capture_name != "comment.doc" {
+                continue;
             }
-        }
-    }
 
-    /// Continue processing a block comment that started on a previous line
-    fn process_block_comment_continuation(&mut self, line: &str, line_1indexed: usize) {
-        // Check if block comment ends on this line
-        let end_pos = line.find("*/");
-
-        if let Some(end_pos) = end_pos {
-            // Block comment ends - take ownership of state to avoid clone
-            let old_state = std::mem::take(&mut self.state);
-            if let ScanState::InBlockComment {
-                start_line,
-                start_col,
-                mut content,
-            } = old_state
-            {
-                content.push_str(&line[..end_pos + 2]);
-
-                if content.contains(self.search_text) {
-                    self.add_match(start_line, start_col, CommentType::Block, content);
-                }
-
-                // Check for more comments after the block comment ends
-                let remaining = &line[end_pos + 2..];
-                let after_col = end_pos + 2;
-                self.check_remaining_after_block(remaining, line_1indexed, after_col);
-            }
-        } else {
-            // Block comment continues - mutate content in-place
-            if let ScanState::InBlockComment { content, .. } = &mut self.state {
-                content.push_str(line);
-                content.push('\n');
-            }
-        }
-    }
+            let node = capture.node;
+            let content = node
+                .utf8_text(source.as_bytes())
+                .unwrap_or("")
+                .trim_end_matches(['\r', '\n']);
 
-    /// Check for new comments after a block comment ends on the same line
-    fn check_remaining_after_block(
-        &mut self,
-        remaining: &str,
-        line_1indexed: usize,
-        col_offset: usize,
-    ) {
-        if let Some(new_start) = remaining.find("/*") {
-            self.state = ScanState::InBlockComment {
-                start_line: line_1indexed,
-                start_col: col_offset + new_start,
-                content: format!("{}\n", &remaining[new_start..]),
+            let comment_type = if capture_name == "comment.doc" {
+                CommentType::Block
+            } else {
+                classify_comment(content)
             };
-        } else if let Some(single_start) = remaining.find(self.single_line_prefix) {
-            let comment_content = remaining[single_start..].to_string();
-            if comment_content.contains(self.search_text) {
-                self.add_match(
-                    line_1indexed,
-                    col_offset + single_start,
-                    CommentType::SingleLine,
-                    comment_content,
-                );
-            }
-        }
-    }
-
-    /// Scan a line (not in block comment) for comments
-    fn scan_for_comments(&mut self, line: &str, line_1indexed: usize) {
-        let mut pos = 0;
-
-        while pos < line.len() {
-            let remaining = &line[pos..];
-            let block_start = remaining.find("/*");
-            let single_start = remaining.find(self.single_line_prefix);
 
-            match (block_start, single_start) {
-                // Both found - handle whichever comes first
-                (Some(bs), Some(ss)) if ss < bs => {
-                    // Single-line comment comes first, rest of line is comment
-                    self.handle_single_line_comment(remaining, line_1indexed, pos, ss);
-                    break;
-                }
-                (Some(bs), _) => {
-                    // Block comment comes first
-                    if let Some(new_pos) =
-                        self.handle_block_comment_start(remaining, line_1indexed, pos, bs)
-                    {
-                        pos = new_pos;
-                    } else {
-                        // Block comment continues to next line
-                        break;
-                    }
-                }
-                (None, Some(ss)) => {
-                    // Only single-line comment
-                    self.handle_single_line_comment(remaining, line_1indexed, pos, ss);
-                    break;
-                }
-                (None, None) => {
-                    // No more comments on this line
-                    break;
+            let start_line = node.start_position().row + 1;
+            let start_column = node.start_position().column;
+            let start_byte = node.start_byte();
+
+            if match_positions {
+                for found in matcher.find_iter(content) {
+                    let (line, column) =
+                        offset_to_position(content, found.start(), start_line, start_column);
+                    matches.push(CommentMatch {
+                        file_path: file_path_str.clone(),
+                        line,
+                        column,
+                        comment_type,
+                        content: source_text(
+                            source,
+                            start_byte..start_byte + content.len(),
+                            secrets,
+                        ),
+                        matched_text: Some(source_text(
+                            source,
+                            start_byte + found.start()..start_byte + found.end(),
+                            secrets,
+                        )),
+                    });
                 }
+            } else if matcher.is_match(content) {
+                matches.push(CommentMatch {
+                    file_path: file_path_str.clone(),
+                    line: start_line,
+                    column: start_column,
+                    comment_type,
+                    content: source_text(source, start_byte..start_byte + content.len(), secrets),
+                    matched_text: None,
+                });
             }
         }
     }
 
-    /// Handle a single-line comment
-    fn handle_single_line_comment(
-        &mut self,
-        remaining: &str,
-        line_1indexed: usize,
-        pos: usize,
-        offset: usize,
-    ) {
-        let comment_content = remaining[offset..].to_string();
-        if comment_content.contains(self.search_text) {
-            self.add_match(
-                line_1indexed,
-                pos + offset,
-                CommentType::SingleLine,
-                comment_content,
-            );
-        }
-    }
-
-    /// Handle the start of a block comment. Returns the new position if the comment ends on the same line.
-    fn handle_block_comment_start(
-        &mut self,
-        remaining: &str,
-        line_1indexed: usize,
-        pos: usize,
-        block_start: usize,
-    ) -> Option<usize> {
-        let after_start = &remaining[block_start + 2..];
-
-        if let Some(end_offset) = after_start.find("*/") {
-            // Block comment ends on the same line
-            let comment_content =
-                remaining[block_start..block_start + 2 + end_offset + 2].to_string();
-            if comment_content.contains(self.search_text) {
-                self.add_match(
-                    line_1indexed,
-                    pos + block_start,
-                    CommentType::Block,
-                    comment_content,
-                );
-            }
-            Some(pos + block_start + 2 + end_offset + 2)
-        } else {
-            // Block comment continues to next line
-            self.state = ScanState::InBlockComment {
-                start_line: line_1indexed,
-                start_col: pos + block_start,
-                content: format!("{}\n", &remaining[block_start..]),
-            };
-            None
-        }
-    }
+    // Query matches are not guaranteed to be in document order when a
+    // query has several patterns, so sort by position for stable output.
+    matches.sort_by_key(|m| (m.line, m.column));
+    matches
+}
 
-    /// Add a match to the results
-    fn add_match(
-        &mut self,
-        line: usize,
-        column: usize,
-        comment_type: CommentType,
-        content: String,
-    ) {
-        self.matches.push(CommentMatch {
-            file_path: self.file_path_str.clone(),
-            line,
-            column,
-            comment_type,
-            content,
-        });
-    }
-
-    /// Finalize scanning - check for unclosed block comment at end of file
-    fn finalize(&mut self) {
-        if let ScanState::InBlockComment {
-            start_line,
-            start_col,
-            ref content,
-        } = self.state
-        {
-            if content.contains(self.search_text) {
-                self.add_match(start_line, start_col, CommentType::Block, content.clone());
-            }
-        }
+/// Convert a byte offset within a comment to a (1-indexed line, 0-indexed column)
+/// position in the file, given where the comment starts
+fn offset_to_position(
+    content: &str,
+    offset: usize,
+    start_line: usize,
+    start_column: usize,
+) -> (usize, usize) {
+    let before = &content[..offset];
+    match before.rfind('\n') {
+        Some(newline) => (
+            start_line + before.matches('\n').count(),
+            offset - newline - 1,
+        ),
+        None => (start_line, start_column + offset),
     }
 }
 
-/// Internal helper that finds comments with configurable single-line prefix.
-///
-/// Supports:
-/// - Single-line comments with the specified prefix (e.g., "//" or "--")
-/// - Block comments with /* */
-fn find_comments_with_prefix(
-    file_path: &Path,
-    search_text: &str,
-    single_line_prefix: &str,
-) -> Result<Vec<CommentMatch>> {
-    let source = std::fs::read_to_string(file_path)?;
-    let scanner = CommentScanner::new(file_path, search_text, single_line_prefix);
-    Ok(scanner.scan(&source))
+/// Classify a comment node's text as a single-line or block comment
+fn classify_comment(content: &str) -> CommentType {
+    if content.starts_with("/*") || content.starts_with("<!--") || content.contains('\n') {
+        CommentType::Block
+    } else {
+        CommentType::SingleLine
+    }
 }
 
-/// Search for text in Markdown files (full text search)
+/// Search for text in Markdown source (full text search)
 ///
 /// Since Markdown doesn't have a concept of "comments", this function
 /// performs a full text search and treats all matches as block comments.
-pub fn find_text_in_markdown_file(
+/// Every match is reported at its own position. With `match_positions`,
+/// the matched text is included alongside the full line. Secrets found in
+/// the file, if given, are masked in both.
+pub fn find_text_in_markdown_source(
     file_path: &Path,
-    search_text: &str,
-) -> Result<Vec<CommentMatch>> {
-    let source = std::fs::read_to_string(file_path)?;
+    source: &str,
+    matcher: &TextMatcher,
+    match_positions: bool,
+    secrets: Option<&Secrets>,
+) -> Vec<CommentMatch> {
     let file_path_str = file_path.to_string_lossy().to_string();
 
     let mut matches = Vec::new();
+    let mut offset = 0;
 
-    for (line_num, line) in source.lines().enumerate() {
+    for (line_num, raw_line) in source.split_inclusive('\n').enumerate() {
         let line_1indexed = line_num + 1;
+        let line = match raw_line.strip_suffix('\n') {
+            Some(line) => line.strip_suffix('\r').unwrap_or(line),
+            None => raw_line,
+        };
 
         // Find all occurrences of the search text in this line
-        let mut search_start = 0;
-        while let Some(pos) = line[search_start..].find(search_text) {
-            let column = search_start + pos;
+        for found in matcher.find_iter(line) {
             matches.push(CommentMatch {
                 file_path: file_path_str.clone(),
                 line: line_1indexed,
-                column,
+                column: found.start(),
                 comment_type: CommentType::Block, // Treat as block for Markdown
-                content: line.to_string(),
+                content: source_text(source, offset..offset + line.len(), secrets),
+                matched_text: match_positions.then(|| {
+                    source_text(
+                        source,
+                        offset + found.start()..offset + found.end(),
+                        secrets,
+                    )
+                }),
             });
-            search_start = column + search_text.len();
         }
+        offset += raw_line.len();
     }
 
-    Ok(matches)
+    matches
 }
 
 /// Get code at a specific location with context lines before and after
@@ -314,7 +183,38 @@ pub fn get_code_at_location(
     context_after: usize,
 ) -> Result<CodeSnippet> {
     let source = std::fs::read_to_string(file_path)?;
-    let lines: Vec<&str> = source.lines().collect();
+    Ok(code_at_location(
+        &source,
+        file_path,
+        line,
+        context_before,
+        context_after,
+        None,
+    ))
+}
+
+/// Get code at a specific location of already read file content
+///
+/// Secrets found in the file, if given, are masked in the returned lines.
+pub fn code_at_location(
+    source: &str,
+    file_path: &Path,
+    line: usize, // 1-indexed
+    context_before: usize,
+    context_after: usize,
+    secrets: Option<&Secrets>,
+) -> CodeSnippet {
+    // Byte ranges of the lines, without line endings as in str::lines
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    for line in source.split_inclusive('\n') {
+        let content = match line.strip_suffix('\n') {
+            Some(content) => content.strip_suffix('\r').unwrap_or(content),
+            None => line,
+        };
+        lines.push(offset..offset + content.len());
+        offset += line.len();
+    }
     let total_lines = lines.len();
 
     // 範囲計算（1-indexed → 0-indexed）
@@ -322,14 +222,21 @@ pub fn get_code_at_location(
     let start_idx = target_idx.saturating_sub(context_before);
     let end_idx = (target_idx + context_after + 1).min(total_lines);
 
-    let code = lines[start_idx..end_idx].join("\n");
+    let code = lines[start_idx..end_idx]
+        .iter()
+        .map(|range| match secrets {
+            Some(secrets) => secrets.mask(range.clone()),
+            None => source[range.clone()].to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
 
-    Ok(CodeSnippet {
+    CodeSnippet {
         file_path: file_path.to_string_lossy().to_string(),
         start_line: start_idx + 1,
         end_line: end_idx,
         code,
-    })
+    }
 }
 
 /// Extract documentation comments (JSDoc or regular comments) immediately before a given line
@@ -402,17 +309,37 @@ pub fn extract_docs_before_line(source: &str, start_line: usize) -> Option<Strin
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::io::Write;
-    use tempfile::NamedTempFile;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    use crate::language::LanguageRegistry;
+    use crate::parser::GenericParser;
+    use crate::symbol::types::SearchMode;
+
+    fn find_in_source(file_name: &str, source: &str, search_text: &str) -> Vec<CommentMatch> {
+        let registry = Arc::new(LanguageRegistry::new().unwrap());
+        let mut parser = GenericParser::new(registry).unwrap();
+        let path = PathBuf::from(file_name);
+        let (tree, language) = parser.parse_with_language(&path, source).unwrap();
+        find_comments_in_tree(
+            &tree,
+            source,
+            language.comments_query(),
+            &path,
+            &TextMatcher::literal(search_text),
+            false,
+            None,
+        )
+    }
 
     #[test]
     fn test_find_single_line_comments() {
-        let mut file = NamedTempFile::new().unwrap();
-        writeln!(file, "// TODO: fix this").unwrap();
-        writeln!(file, "const x = 1;").unwrap();
-        writeln!(file, "// Another TODO here").unwrap();
+        let source = r#"// TODO: fix this
+const x = 1;
+// Another TODO here
+"#;
 
-        let matches = find_comments_in_file(file.path(), "TODO").unwrap();
+        let matches = find_in_source("test.js", source, "TODO");
         assert_eq!(matches.len(), 2);
         assert_eq!(matches[0].line, 1);
         assert_eq!(matches[0].comment_type, CommentType::SingleLine);
@@ -421,14 +348,14 @@ mod tests {
 
     #[test]
     fn test_find_block_comments() {
-        let mut file = NamedTempFile::new().unwrap();
-        writeln!(file, "/* FIXME: broken */").unwrap();
-        writeln!(file, "const x = 1;").unwrap();
-        writeln!(file, "/*").unwrap();
-        writeln!(file, " * Another FIXME").unwrap();
-        writeln!(file, " */").unwrap();
-
-        let matches = find_comments_in_file(file.path(), "FIXME").unwrap();
+        let source = r#"/* FIXME: broken */
+const x = 1;
+/*
+ * Another FIXME
+ */
+"#;
+
+        let matches = find_in_source("test.js", source, "FIXME");
         assert_eq!(matches.len(), 2);
         assert_eq!(matches[0].comment_type, CommentType::Block);
         assert_eq!(matches[1].comment_type, CommentType::Block);
@@ -471,16 +398,21 @@ function foo() {}"#;
         assert!(docs.is_none());
     }
 
+    fn find_in_markdown(source: &str, text: &str) -> Vec<CommentMatch> {
+        let matcher = TextMatcher::literal(text);
+        find_text_in_markdown_source(Path::new("test.md"), source, &matcher, false, None)
+    }
+
     #[test]
     fn test_find_text_in_markdown() {
-        let mut file = NamedTempFile::with_suffix(".md").unwrap();
-        writeln!(file, "# Test Heading").unwrap();
-        writeln!(file).unwrap();
-        writeln!(file, "This is a test document.").unwrap();
-        writeln!(file, "TODO: add more content").unwrap();
-        writeln!(file, "Another TODO item here").unwrap();
-
-        let matches = find_text_in_markdown_file(file.path(), "TODO").unwrap();
+        let source = "# Test Heading
+
+This is a test document.
+TODO: add more content
+Another TODO item here
+";
+
+        let matches = find_in_markdown(source, "TODO");
         assert_eq!(matches.len(), 2);
         assert_eq!(matches[0].line, 4);
         assert_eq!(matches[1].line, 5);
@@ -490,10 +422,7 @@ function foo() {}"#;
 
     #[test]
     fn test_find_text_in_markdown_multiple_per_line() {
-        let mut file = NamedTempFile::with_suffix(".md").unwrap();
-        writeln!(file, "test test test").unwrap();
-
-        let matches = find_text_in_markdown_file(file.path(), "test").unwrap();
+        let matches = find_in_markdown("test test test\n", "test");
         assert_eq!(matches.len(), 3);
         assert_eq!(matches[0].column, 0);
         assert_eq!(matches[1].column, 5);
@@ -502,12 +431,12 @@ function foo() {}"#;
 
     #[test]
     fn test_find_sql_single_line_comments() {
-        let mut file = NamedTempFile::with_suffix(".sql").unwrap();
-        writeln!(file, "-- TODO: fix this query").unwrap();
-        writeln!(file, "SELECT * FROM users;").unwrap();
-        writeln!(file, "-- Another TODO here").unwrap();
+        let source = r#"-- TODO: fix this query
+SELECT * FROM users;
+-- Another TODO here
+"#;
 
-        let matches = find_comments_in_sql_file(file.path(), "TODO").unwrap();
+        let matches = find_in_source("test.sql", source, "TODO");
         assert_eq!(matches.len(), 2);
         assert_eq!(matches[0].line, 1);
         assert_eq!(matches[0].comment_type, CommentType::SingleLine);
@@ -516,14 +445,14 @@ function foo() {}"#;
 
     #[test]
     fn test_find_sql_block_comments() {
-        let mut file = NamedTempFile::with_suffix(".sql").unwrap();
-        writeln!(file, "/* FIXME: broken query */").unwrap();
-        writeln!(file, "SELECT * FROM users;").unwrap();
-        writeln!(file, "/*").unwrap();
-        writeln!(file, " * Another FIXME").unwrap();
-        writeln!(file, " */").unwrap();
-
-        let matches = find_comments_in_sql_file(file.path(), "FIXME").unwrap();
+        let source = r#"/* FIXME: broken query */
+SELECT * FROM users;
+/*
+ * Another FIXME
+ */
+"#;
+
+        let matches = find_in_source("test.sql", source, "FIXME");
         assert_eq!(matches.len(), 2);
         assert_eq!(matches[0].comment_type, CommentType::Block);
         assert_eq!(matches[1].comment_type, CommentType::Block);
@@ -531,12 +460,12 @@ function foo() {}"#;
 
     #[test]
     fn test_find_sql_mixed_comments() {
-        let mut file = NamedTempFile::with_suffix(".sql").unwrap();
-        writeln!(file, "-- TODO: single line").unwrap();
-        writeln!(file, "/* TODO: block comment */").unwrap();
-        writeln!(file, "SELECT * FROM users; -- inline TODO").unwrap();
+        let source = r#"-- TODO: single line
+/* TODO: block comment */
+SELECT * FROM users; -- inline TODO
+"#;
 
-        let matches = find_comments_in_sql_file(file.path(), "TODO").unwrap();
+        let matches = find_in_source("test.sql", source, "TODO");
         assert_eq!(matches.len(), 3);
         assert_eq!(matches[0].line, 1);
         assert_eq!(matches[0].comment_type, CommentType::SingleLine);
@@ -546,14 +475,14 @@ function foo() {}"#;
         assert_eq!(matches[2].comment_type, CommentType::SingleLine);
     }
 
-    // ========== Edge case tests for CommentScanner ==========
+    // ========== Comment search edge case tests ==========
 
     #[test]
     fn test_multiple_block_comments_on_one_line() {
-        let mut file = NamedTempFile::new().unwrap();
-        writeln!(file, "/* TODO: first */ code /* TODO: second */").unwrap();
+        let source = r#"/* TODO: first */ code /* TODO: second */
+"#;
 
-        let matches = find_comments_in_file(file.path(), "TODO").unwrap();
+        let matches = find_in_source("test.js", source, "TODO");
         assert_eq!(matches.len(), 2);
         assert_eq!(matches[0].line, 1);
         assert_eq!(matches[0].column, 0);
@@ -567,10 +496,10 @@ function foo() {}"#;
 
     #[test]
     fn test_block_comment_followed_by_single_line_on_same_line() {
-        let mut file = NamedTempFile::new().unwrap();
-        writeln!(file, "/* TODO: block */ code // TODO: single").unwrap();
+        let source = r#"/* TODO: block */ code // TODO: single
+"#;
 
-        let matches = find_comments_in_file(file.path(), "TODO").unwrap();
+        let matches = find_in_source("test.js", source, "TODO");
         assert_eq!(matches.len(), 2);
         assert_eq!(matches[0].comment_type, CommentType::Block);
         assert!(matches[0].content.contains("block"));
@@ -580,12 +509,12 @@ function foo() {}"#;
 
     #[test]
     fn test_multiline_block_followed_by_single_line_on_end_line() {
-        let mut file = NamedTempFile::new().unwrap();
-        writeln!(file, "/*").unwrap();
-        writeln!(file, " * TODO: multiline block").unwrap();
-        writeln!(file, " */ // TODO: trailing single").unwrap();
+        let source = r#"/*
+ * TODO: multiline block
+ */ // TODO: trailing single
+"#;
 
-        let matches = find_comments_in_file(file.path(), "TODO").unwrap();
+        let matches = find_in_source("test.js", source, "TODO");
         assert_eq!(matches.len(), 2);
         assert_eq!(matches[0].line, 1); // Block starts on line 1
         assert_eq!(matches[0].comment_type, CommentType::Block);
@@ -597,12 +526,12 @@ function foo() {}"#;
 
     #[test]
     fn test_multiline_block_followed_by_new_block_on_end_line() {
-        let mut file = NamedTempFile::new().unwrap();
-        writeln!(file, "/*").unwrap();
-        writeln!(file, " * TODO: first block").unwrap();
-        writeln!(file, " */ /* TODO: second block */").unwrap();
+        let source = r#"/*
+ * TODO: first block
+ */ /* TODO: second block */
+"#;
 
-        let matches = find_comments_in_file(file.path(), "TODO").unwrap();
+        let matches = find_in_source("test.js", source, "TODO");
         assert_eq!(matches.len(), 2);
         assert_eq!(matches[0].line, 1);
         assert_eq!(matches[0].comment_type, CommentType::Block);
@@ -614,26 +543,27 @@ function foo() {}"#;
 
     #[test]
     fn test_unclosed_block_comment_at_end_of_file() {
-        let mut file = NamedTempFile::new().unwrap();
-        writeln!(file, "code").unwrap();
-        writeln!(file, "/* TODO: unclosed block").unwrap();
-        writeln!(file, " * more content").unwrap();
-        // No closing */
-        let matches = find_comments_in_file(file.path(), "TODO").unwrap();
+        // No closing */: the parser does not produce a comment node for it,
+        // but comments before it are still found
+        let source = r#"// TODO: closed
+code
+/* TODO: unclosed block
+ * more content
+"#;
+        let matches = find_in_source("test.js", source, "TODO");
         assert_eq!(matches.len(), 1);
-        assert_eq!(matches[0].line, 2);
-        assert_eq!(matches[0].comment_type, CommentType::Block);
-        assert!(matches[0].content.contains("unclosed block"));
-        assert!(matches[0].content.contains("more content"));
+        assert_eq!(matches[0].line, 1);
+        assert_eq!(matches[0].comment_type, CommentType::SingleLine);
+        assert!(matches[0].content.contains("closed"));
     }
 
     #[test]
     fn test_single_line_comment_before_block_on_same_line() {
-        let mut file = NamedTempFile::new().unwrap();
         // Single-line comment comes before block comment syntax
-        writeln!(file, "// TODO: single /* not a block */").unwrap();
+        let source = r#"// TODO: single /* not a block */
+"#;
 
-        let matches = find_comments_in_file(file.path(), "TODO").unwrap();
+        let matches = find_in_source("test.js", source, "TODO");
         assert_eq!(matches.len(), 1);
         assert_eq!(matches[0].comment_type, CommentType::SingleLine);
         // The entire rest of line is part of single-line comment
@@ -642,15 +572,14 @@ function foo() {}"#;
 
     #[test]
     fn test_large_multiline_block_comment_no_quadratic_behavior() {
-        let mut file = NamedTempFile::new().unwrap();
-        writeln!(file, "/* TODO: start").unwrap();
+        let mut source = String::from("/* TODO: start\n");
         // Add many lines to verify no O(n²) behavior
         for i in 0..100 {
-            writeln!(file, " * line {}", i).unwrap();
+            source.push_str(&format!(" * line {}\n", i));
         }
-        writeln!(file, " */").unwrap();
+        source.push_str(" */\n");
 
-        let matches = find_comments_in_file(file.path(), "TODO").unwrap();
+        let matches = find_in_source("test.js", &source, "TODO");
         assert_eq!(matches.len(), 1);
         assert_eq!(matches[0].line, 1);
         assert_eq!(matches[0].comment_type, CommentType::Block);
@@ -660,15 +589,15 @@ function foo() {}"#;
 
     #[test]
     fn test_mixed_block_and_single_line_complex() {
-        let mut file = NamedTempFile::new().unwrap();
-        writeln!(file, "/* MARKER: a */ x /* MARKER: b */ // MARKER: c").unwrap();
-        writeln!(file, "// MARKER: d").unwrap();
-        writeln!(file, "/*").unwrap();
-        writeln!(file, " * MARKER: e").unwrap();
-        writeln!(file, " */ /* MARKER: f").unwrap();
-        writeln!(file, " */").unwrap();
-
-        let matches = find_comments_in_file(file.path(), "MARKER").unwrap();
+        let source = r#"/* MARKER: a */ x /* MARKER: b */ // MARKER: c
+// MARKER: d
+/*
+ * MARKER: e
+ */ /* MARKER: f
+ */
+"#;
+
+        let matches = find_in_source("test.js", source, "MARKER");
         assert_eq!(matches.len(), 6);
 
         // Line 1: block a, block b, single c
@@ -748,4 +677,128 @@ function foo() {}"#;
         // Should NOT contain the file comment
         assert!(!docs.contains("File comment"));
     }
+
+    // ========== AST-based comment search tests ==========
+
+    #[test]
+    fn test_tree_ignores_comment_syntax_in_strings() {
+        let source = r#"const url = "http://example.com/TODO";
+const s = "/* TODO: not a comment */";
+// TODO: real comment
+"#;
+
+        let matches = find_in_source("test.ts", source, "TODO");
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].line, 3);
+        assert_eq!(matches[0].comment_type, CommentType::SingleLine);
+        assert_eq!(matches[0].content, "// TODO: real comment");
+    }
+
+    #[test]
+    fn test_tree_block_comment_position() {
+        let source = "let x = 1; /* TODO: inline */\n/*\n * TODO: multiline\n */\n";
+
+        let matches = find_in_source("test.js", source, "TODO");
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].line, 1);
+        assert_eq!(matches[0].column, 11);
+        assert_eq!(matches[0].comment_type, CommentType::Block);
+        assert_eq!(matches[1].line, 2);
+        assert_eq!(matches[1].comment_type, CommentType::Block);
+        assert!(matches[1].content.contains("multiline"));
+    }
+
+    #[test]
+    fn test_tree_python_comments_and_docstrings() {
+        let source = r##""""Module docstring with TODO."""
+
+# TODO: hash comment
+def foo():
+    """TODO: function docstring."""
+    s = "# TODO: not a comment"
+    return s
+"##;
+
+        let matches = find_in_source("test.py", source, "TODO");
+        assert_eq!(matches.len(), 3);
+        assert_eq!(matches[0].line, 1);
+        assert_eq!(matches[0].comment_type, CommentType::Block);
+        assert_eq!(matches[1].line, 3);
+        assert_eq!(matches[1].comment_type, CommentType::SingleLine);
+        assert_eq!(matches[2].line, 5);
+        assert_eq!(matches[2].comment_type, CommentType::Block);
+    }
+
+    #[test]
+    fn test_tree_html_comments() {
+        let source = "<div>TODO in text</div>\n<!-- TODO: html comment -->\n";
+
+        let matches = find_in_source("test.html", source, "TODO");
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].line, 2);
+        assert_eq!(matches[0].comment_type, CommentType::Block);
+    }
+
+    #[test]
+    fn test_tree_rust_doc_comment_has_no_trailing_newline() {
+        let source = "/// TODO: document this\nfn foo() {}\n";
+
+        let matches = find_in_source("test.rs", source, "TODO");
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].content, "/// TODO: document this");
+        assert_eq!(matches[0].comment_type, CommentType::SingleLine);
+    }
+
+    #[test]
+    fn test_tree_sql_comments() {
+        let source = "-- TODO: line\nSELECT '-- TODO: string' FROM t; /* TODO: block */\n";
+
+        let matches = find_in_source("test.sql", source, "TODO");
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].comment_type, CommentType::SingleLine);
+        assert_eq!(matches[1].comment_type, CommentType::Block);
+    }
+
+    #[test]
+    fn test_tree_match_positions() {
+        let source = "let x = 1; /* see TODO(alice) */\n/*\n * TODO(bob) and TODO(carol)\n */\n";
+        let registry = Arc::new(LanguageRegistry::new().unwrap());
+        let mut parser = GenericParser::new(registry).unwrap();
+        let path = PathBuf::from("test.js");
+        let (tree, language) = parser.parse_with_language(&path, source).unwrap();
+        let matcher = TextMatcher::new(r"TODO\(\w+\)", SearchMode::Regex, true).unwrap();
+
+        let matches = find_comments_in_tree(
+            &tree,
+            source,
+            language.comments_query(),
+            &path,
+            &matcher,
+            true,
+            None,
+        );
+
+        assert_eq!(matches.len(), 3);
+        assert_eq!((matches[0].line, matches[0].column), (1, 18));
+        assert_eq!(matches[0].matched_text.as_deref(), Some("TODO(alice)"));
+        assert_eq!(matches[0].content, "/* see TODO(alice) */");
+        assert_eq!((matches[1].line, matches[1].column), (3, 3));
+        assert_eq!(matches[1].matched_text.as_deref(), Some("TODO(bob)"));
+        assert_eq!((matches[2].line, matches[2].column), (3, 17));
+        assert_eq!(matches[2].matched_text.as_deref(), Some("TODO(carol)"));
+        // Full comment block is kept for context
+        assert!(matches[2].content.starts_with("/*\n"));
+    }
+
+    #[test]
+    fn test_markdown_source_with_word_matcher() {
+        let source = "TODO list\nTODOS are not matched\n";
+        let matcher = TextMatcher::new("todo", SearchMode::Word, false).unwrap();
+
+        let matches =
+            find_text_in_markdown_source(Path::new("test.md"), source, &matcher, true, None);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].line, 1);
+        assert_eq!(matches[0].matched_text.as_deref(), Some("TODO"));
+    }
 }
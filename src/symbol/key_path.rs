@@ -0,0 +1,168 @@
+//! Dotted key paths for configuration files
+//!
+//! JSON, YAML and TOML keys are reported under the path of keys leading to
+//! them (e.g., `dependencies.serde` or `spec.template.metadata.labels`).
+//! Array and sequence items do not add a segment, so `containers.name`
+//! refers to the `name` key of every item in `containers`.
+
+use tree_sitter::Node;
+
+use crate::symbol::types::SymbolKind;
+
+/// Dotted key path of a configuration key definition
+///
+/// Returns `None` for other kinds, which keep their captured name.
+pub fn definition_key_path(kind: SymbolKind, node: Node, source: &str) -> Option<String> {
+    if kind == SymbolKind::Key {
+        key_path(node, source)
+    } else {
+        None
+    }
+}
+
+/// Build the dotted key path for a node
+///
+/// Collects the key of every keyed node from `node` up to the document root.
+/// Returns `None` if neither the node nor any ancestor has a key.
+pub fn key_path(node: Node, source: &str) -> Option<String> {
+    let mut segments = Vec::new();
+    let mut current = Some(node);
+
+    while let Some(n) = current {
+        if let Some(key) = key_node(n) {
+            segments.push(key_text(key, source));
+        }
+        current = n.parent();
+    }
+
+    if segments.is_empty() {
+        return None;
+    }
+    segments.reverse();
+    Some(segments.join("."))
+}
+
+/// Get the key of a keyed node
+///
+/// JSON and TOML pairs, YAML mapping pairs, and TOML table headers.
+fn key_node(node: Node) -> Option<Node> {
+    match node.kind() {
+        "pair" | "block_mapping_pair" | "flow_pair" => node
+            .child_by_field_name("key")
+            .or_else(|| node.named_child(0)),
+        "table" | "table_array_element" => node.named_child(0),
+        _ => None,
+    }
+}
+
+/// Get the text of a key without quotes
+///
+/// TOML dotted keys (`a."b".c`) are unquoted segment by segment.
+pub fn key_text(key: Node, source: &str) -> String {
+    if key.kind() == "dotted_key" {
+        let mut cursor = key.walk();
+        return key
+            .named_children(&mut cursor)
+            .map(|segment| key_text(segment, source))
+            .collect::<Vec<_>>()
+            .join(".");
+    }
+
+    let text = key.utf8_text(source.as_bytes()).unwrap_or("").trim();
+    unquote(text).to_string()
+}
+
+/// Strip matching single or double quotes around a key
+fn unquote(text: &str) -> &str {
+    ['"', '\'']
+        .iter()
+        .find_map(|quote| {
+            text.strip_prefix(*quote)
+                .and_then(|rest| rest.strip_suffix(*quote))
+        })
+        .unwrap_or(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tree_sitter::{Parser, Tree};
+
+    fn parse(language: tree_sitter::Language, source: &str) -> Tree {
+        let mut parser = Parser::new();
+        parser.set_language(&language).unwrap();
+        parser.parse(source, None).unwrap()
+    }
+
+    /// Key paths of every keyed node, in document order
+    fn all_key_paths(tree: &Tree, source: &str) -> Vec<String> {
+        fn walk(node: Node, source: &str, paths: &mut Vec<String>) {
+            if key_node(node).is_some() {
+                paths.push(key_path(node, source).unwrap());
+            }
+            let mut cursor = node.walk();
+            for child in node.named_children(&mut cursor) {
+                walk(child, source, paths);
+            }
+        }
+
+        let mut paths = Vec::new();
+        walk(tree.root_node(), source, &mut paths);
+        paths
+    }
+
+    #[test]
+    fn test_unquote() {
+        assert_eq!(unquote("\"a.b\""), "a.b");
+        assert_eq!(unquote("'a'"), "a");
+        assert_eq!(unquote("plain"), "plain");
+        assert_eq!(unquote("\"mismatched'"), "\"mismatched'");
+    }
+
+    #[test]
+    fn test_json_key_paths() {
+        let source = r#"{"scripts": {"build": "tsc"}, "files": [{"nested": true}]}"#;
+        let tree = parse(tree_sitter_json::LANGUAGE.into(), source);
+
+        assert_eq!(
+            all_key_paths(&tree, source),
+            ["scripts", "scripts.build", "files", "files.nested"]
+        );
+    }
+
+    #[test]
+    fn test_yaml_key_paths() {
+        let source = "spec:\n  template:\n    labels: {app: web}\n  containers:\n    - name: web\n";
+        let tree = parse(tree_sitter_yaml::LANGUAGE.into(), source);
+
+        assert_eq!(
+            all_key_paths(&tree, source),
+            [
+                "spec",
+                "spec.template",
+                "spec.template.labels",
+                "spec.template.labels.app",
+                "spec.containers",
+                "spec.containers.name"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_toml_key_paths() {
+        let source = "[profile.release]\nlto = true\n\n[dependencies]\nserde = { version = \"1\" }\ntokio.\"rt\" = 1\n";
+        let tree = parse(tree_sitter_toml_ng::LANGUAGE.into(), source);
+
+        assert_eq!(
+            all_key_paths(&tree, source),
+            [
+                "profile.release",
+                "profile.release.lto",
+                "dependencies",
+                "dependencies.serde",
+                "dependencies.serde.version",
+                "dependencies.tokio.rt"
+            ]
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use regex::{Regex, RegexBuilder};
+
+use crate::symbol::types::SearchMode;
+
+/// Text matcher used by comment search
+///
+/// All search modes compile down to a single regular expression so that
+/// literal, whole-word, and regex searches share one matching path.
+#[derive(Debug, Clone)]
+pub struct TextMatcher {
+    regex: Regex,
+}
+
+impl TextMatcher {
+    /// Create a matcher for the given pattern and mode
+    ///
+    /// Returns an error if `mode` is [`SearchMode::Regex`] and the pattern
+    /// is not a valid regular expression.
+    pub fn new(
+        pattern: &str,
+        mode: SearchMode,
+        case_sensitive: bool,
+    ) -> Result<Self, regex::Error> {
+        let source = match mode {
+            SearchMode::Literal => regex::escape(pattern),
+            SearchMode::Word => word_pattern(pattern),
+            SearchMode::Regex => pattern.to_string(),
+        };
+
+        let regex = RegexBuilder::new(&source)
+            .case_insensitive(!case_sensitive)
+            .build()?;
+
+        Ok(Self { regex })
+    }
+
+    /// Create a case-sensitive literal matcher
+    pub fn literal(text: &str) -> Self {
+        Self::new(text, SearchMode::Literal, true).expect("escaped literal is a valid regex")
+    }
+
+    /// Check whether the text contains a match
+    pub fn is_match(&self, text: &str) -> bool {
+        self.find_iter(text).next().is_some()
+    }
+
+    /// Iterate over non-empty matches in the text as byte ranges
+    pub fn find_iter<'a>(&'a self, text: &'a str) -> impl Iterator<Item = regex::Match<'a>> + 'a {
+        self.regex.find_iter(text).filter(|m| !m.is_empty())
+    }
+}
+
+/// Build a whole-word regex for a literal pattern
+///
+/// `\b` only asserts a boundary next to a word character, so it is emitted
+/// on an edge only when the pattern starts or ends with one. Patterns such as
+/// `@Deprecated`, `TODO:` or `->` would otherwise never match.
+fn word_pattern(pattern: &str) -> String {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let start = if pattern.chars().next().is_some_and(is_word) {
+        r"\b"
+    } else {
+        ""
+    };
+    let end = if pattern.chars().next_back().is_some_and(is_word) {
+        r"\b"
+    } else {
+        ""
+    };
+    format!("{}{}{}", start, regex::escape(pattern), end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_literal_escapes_regex_syntax() {
+        let matcher = TextMatcher::new("TODO(", SearchMode::Literal, true).unwrap();
+        assert!(matcher.is_match("// TODO(alice): fix"));
+        assert!(!matcher.is_match("// TODO: fix"));
+    }
+
+    #[test]
+    fn test_case_insensitive() {
+        let matcher = TextMatcher::new("todo", SearchMode::Literal, false).unwrap();
+        assert!(matcher.is_match("// TODO: fix"));
+
+        let matcher = TextMatcher::new("todo", SearchMode::Literal, true).unwrap();
+        assert!(!matcher.is_match("// TODO: fix"));
+    }
+
+    #[test]
+    fn test_word_mode() {
+        let matcher = TextMatcher::new("TODO", SearchMode::Word, true).unwrap();
+        assert!(matcher.is_match("// TODO: fix"));
+        assert!(!matcher.is_match("// TODOS are done"));
+    }
+
+    #[test]
+    fn test_word_mode_with_non_word_edges() {
+        let matcher = TextMatcher::new("@Deprecated", SearchMode::Word, true).unwrap();
+        assert!(matcher.is_match("/** @Deprecated use bar */"));
+        assert!(!matcher.is_match("/** @DeprecatedSince 2.0 */"));
+
+        let matcher = TextMatcher::new("TODO:", SearchMode::Word, true).unwrap();
+        assert!(matcher.is_match("// TODO: fix"));
+        assert!(!matcher.is_match("// XTODO: fix"));
+
+        let matcher = TextMatcher::new("->", SearchMode::Word, true).unwrap();
+        assert!(matcher.is_match("// a -> b"));
+        assert!(matcher.is_match("// a->b"));
+    }
+
+    #[test]
+    fn test_regex_mode() {
+        let matcher = TextMatcher::new(r"TODO\(\w+\)", SearchMode::Regex, true).unwrap();
+        let found: Vec<&str> = matcher
+            .find_iter("// TODO(alice) and TODO(bob), not TODO")
+            .map(|m| m.as_str())
+            .collect();
+        assert_eq!(found, vec!["TODO(alice)", "TODO(bob)"]);
+    }
+
+    #[test]
+    fn test_invalid_regex() {
+        assert!(TextMatcher::new("TODO(", SearchMode::Regex, true).is_err());
+    }
+
+    #[test]
+    fn test_empty_matches_are_skipped() {
+        let matcher = TextMatcher::new("x*", SearchMode::Regex, true).unwrap();
+        assert_eq!(matcher.find_iter("abc").count(), 0);
+    }
+}
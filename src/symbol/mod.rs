@@ -1,5 +1,8 @@
 pub mod comment;
 pub mod definition;
+pub mod key_path;
+pub mod matcher;
+pub mod redact;
 pub mod sql_comment;
 pub mod types;
 pub mod usage;
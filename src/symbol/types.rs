@@ -48,6 +48,17 @@ pub enum SymbolKind {
     Index,
     Trigger,
     Column,
+    // C++
+    Namespace,
+    // Ruby/PHP
+    StaticMethod,
+    // JSON/YAML/TOML
+    Key,
+    // Makefile/Dockerfile
+    Target,
+    Stage,
+    // Kotlin/Scala
+    Object,
 }
 
 impl std::fmt::Display for SymbolKind {
@@ -90,6 +101,12 @@ impl std::fmt::Display for SymbolKind {
             SymbolKind::Index => write!(f, "Index"),
             SymbolKind::Trigger => write!(f, "Trigger"),
             SymbolKind::Column => write!(f, "Column"),
+            SymbolKind::Namespace => write!(f, "Namespace"),
+            SymbolKind::StaticMethod => write!(f, "StaticMethod"),
+            SymbolKind::Key => write!(f, "Key"),
+            SymbolKind::Target => write!(f, "Target"),
+            SymbolKind::Stage => write!(f, "Stage"),
+            SymbolKind::Object => write!(f, "Object"),
         }
     }
 }
@@ -223,6 +240,19 @@ impl std::fmt::Display for CommentType {
     }
 }
 
+/// Text search mode for comment search
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchMode {
+    /// Match the text exactly as given
+    #[default]
+    Literal,
+    /// Treat the text as a regular expression
+    Regex,
+    /// Match the text only as a whole word
+    Word,
+}
+
 /// Comment match result
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CommentMatch {
@@ -236,6 +266,9 @@ pub struct CommentMatch {
     pub comment_type: CommentType,
     /// Comment content
     pub content: String,
+    /// Matched text within the comment (only when match positions are requested)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matched_text: Option<String>,
 }
 
 /// Code snippet at a specific location
@@ -5,6 +5,7 @@ use anyhow::Result;
 use streaming_iterator::StreamingIterator;
 
 use crate::context::extractor::extract_contexts;
+use crate::language::LanguageId;
 use crate::parser::typescript::TypeScriptParser;
 use crate::symbol::types::{SymbolUsage, UsageKind};
 
@@ -51,7 +52,7 @@ pub fn find_usages_in_file(
                 }
 
                 // Check if this is an import statement
-                let is_import = is_in_import_statement(node);
+                let is_import = is_in_import_statement(node, LanguageId::TypeScript);
                 if !include_imports && is_import {
                     continue;
                 }
@@ -97,11 +98,19 @@ pub fn find_usages_in_file(
 }
 
 /// Check if a node is within an import statement
-fn is_in_import_statement(node: tree_sitter::Node) -> bool {
+///
+/// `import_declaration` is only an import in Swift and Java; other grammars
+/// (such as Go) keep their existing classification.
+pub fn is_in_import_statement(node: tree_sitter::Node, language: LanguageId) -> bool {
+    let declares_imports = matches!(language, LanguageId::Swift | LanguageId::Java);
     let mut current = node;
     while let Some(parent) = current.parent() {
         let kind = parent.kind();
-        if kind == "import_statement" || kind == "import_specifier" || kind == "import_clause" {
+        if matches!(
+            kind,
+            "import_statement" | "import_specifier" | "import_clause" | "using_directive"
+        ) || (declares_imports && kind == "import_declaration")
+        {
             return true;
         }
         current = parent;
@@ -0,0 +1,54 @@
+/* Sample C file for testing */
+#include <stdio.h>
+#include <stdlib.h>
+
+#define MAX_USERS 1000
+#define SQUARE(x) ((x) * (x))
+
+/* User represents a user in the system */
+struct User {
+    int id;
+    char *name;
+};
+
+union Value {
+    int i;
+    double d;
+};
+
+enum Status {
+    STATUS_ACTIVE,
+    STATUS_INACTIVE
+};
+
+typedef struct User User;
+typedef unsigned long UserId;
+typedef void (*UserCallback)(User *user);
+
+int global_counter = 0;
+
+/* Function prototype */
+User *create_user(int id, const char *name);
+
+/**
+ * Create a new user on the heap.
+ */
+User *create_user(int id, const char *name) {
+    User *user = malloc(sizeof(User));
+    user->id = id;
+    user->name = (char *)name;
+    global_counter++;
+    return user;
+}
+
+static void print_user(const User *user) {
+    printf("User %d: %s\n", user->id, user->name);
+}
+
+// TODO: Add validation
+int main(void) {
+    User *user = create_user(1, "alice");
+    print_user(user);
+    free(user);
+    return 0;
+}
@@ -0,0 +1,58 @@
+// Sample C++ file for testing
+#include <string>
+#include <vector>
+
+#define APP_VERSION "1.0"
+
+namespace app {
+
+using UserId = int;
+typedef std::vector<int> IdList;
+
+int instance_count = 0;
+
+enum class Role { Admin, Member };
+
+struct Point {
+    int x;
+    int y;
+};
+
+/// UserService manages users
+class UserService {
+public:
+    UserService();
+    ~UserService();
+
+    void addUser(const std::string &name);
+
+    int count() const {
+        return static_cast<int>(users_.size());
+    }
+
+private:
+    std::vector<std::string> users_;
+};
+
+UserService::UserService() {}
+
+UserService::~UserService() {}
+
+void UserService::addUser(const std::string &name) {
+    users_.push_back(name);
+    instance_count++;
+}
+
+template <typename T>
+T maxValue(T a, T b) {
+    return a > b ? a : b;
+}
+
+}  // namespace app
+
+// FIXME: Handle errors
+int main() {
+    app::UserService service;
+    service.addUser("alice");
+    return app::maxValue(service.count(), 0);
+}
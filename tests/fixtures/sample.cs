@@ -0,0 +1,59 @@
+// Sample C# file for testing
+using System;
+using System.Collections.Generic;
+using static System.Math;
+using Json = System.Text.Json.JsonSerializer;
+
+namespace Sample.Users
+{
+    /// <summary>
+    /// Represents a user in the system.
+    /// </summary>
+    public class User
+    {
+        public int Id { get; set; }
+        public string Name { get; }
+        private readonly List<string> _roles = new List<string>();
+
+        public User(int id, string name)
+        {
+            Id = id;
+            Name = name;
+        }
+
+        public string DisplayName() => $"{Name} ({Id})";
+    }
+
+    public record UserDto(int Id, string Name);
+
+    public struct Point
+    {
+        public int X;
+        public int Y;
+    }
+
+    public interface IUserRepository
+    {
+        User FindById(int id);
+    }
+
+    public enum Role
+    {
+        Admin,
+        Member
+    }
+
+    public delegate void UserChanged(User user);
+
+    // TODO: Add caching
+    public class UserService : IUserRepository
+    {
+        public event UserChanged OnChanged;
+
+        public User FindById(int id)
+        {
+            Console.WriteLine("Finding user");
+            return new User(id, "alice");
+        }
+    }
+}
@@ -0,0 +1,17 @@
+// Sample C++ header for testing
+#pragma once
+
+#include <string>
+
+namespace geometry {
+
+class Shape {
+public:
+    virtual ~Shape() = default;
+    virtual double area() const = 0;
+    std::string name() const;
+};
+
+double totalArea(const Shape *shapes, int count);
+
+}  // namespace geometry
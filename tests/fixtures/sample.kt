@@ -0,0 +1,48 @@
+// Sample Kotlin file for testing
+package com.example.users
+
+import kotlin.collections.List
+
+/** Maximum number of retries */
+const val MAX_RETRIES = 3
+
+/** A type that can check itself */
+interface Validatable {
+    fun validate(): Boolean
+}
+
+/** Represents a user account */
+data class User(val name: String, var age: Int) : Validatable {
+    override fun validate(): Boolean = name.isNotEmpty()
+
+    companion object Factory {
+        fun guest(): User = User("Guest", 0)
+    }
+}
+
+enum class Role { ADMIN, GUEST }
+
+typealias UserList = List<User>
+
+object UserRegistry {
+    private val users = mutableListOf<User>()
+
+    fun register(user: User) {
+        // TODO: reject duplicate users
+        users.add(user)
+    }
+}
+
+class UserService(private val registry: UserRegistry) {
+    constructor() : this(UserRegistry)
+
+    companion object {
+        val instance = UserService()
+    }
+}
+
+/* Formats a user's name for display */
+fun formatName(user: User): String {
+    val count = user.name.length
+    return "${user.name} ($count)"
+}
@@ -0,0 +1,65 @@
+<?php
+// Sample PHP file for testing
+
+namespace App\Billing;
+
+use App\Models\Customer;
+use Illuminate\Support\Facades\Log;
+
+const DEFAULT_CURRENCY = 'USD';
+
+/**
+ * Shared logging helpers
+ */
+trait LogsActivity
+{
+    public function logActivity(string $message): void
+    {
+        Log::info($message);
+    }
+}
+
+interface Payable
+{
+    public function pay(): bool;
+}
+
+enum Status: string
+{
+    case Paid = 'paid';
+    case Open = 'open';
+}
+
+/**
+ * Represents a customer invoice
+ */
+class Invoice implements Payable
+{
+    use LogsActivity;
+
+    const MAX_RETRIES = 3;
+
+    private float $total;
+
+    public function __construct(float $total)
+    {
+        $this->total = $total;
+    }
+
+    public static function fromArray(array $data): self
+    {
+        return new self($data['total']);
+    }
+
+    public function pay(): bool
+    {
+        $this->logActivity('paid');
+        return true;
+    }
+}
+
+// TODO: Move to a helper class
+function format_amount(float $amount): string
+{
+    return number_format($amount, 2);
+}
@@ -0,0 +1,36 @@
+# Sample Ruby file for testing
+require 'json'
+
+# Utilities shared across the app
+module Billing
+  MAX_RETRIES = 3
+
+  # Represents a customer invoice
+  class Invoice < ApplicationRecord
+    attr_reader :total
+
+    def initialize(total)
+      @total = total
+    end
+
+    # Build an invoice from JSON
+    def self.from_json(payload)
+      new(JSON.parse(payload)['total'])
+    end
+
+    def paid?
+      total.zero?
+    end
+
+    class << self
+      def default
+        new(0)
+      end
+    end
+  end
+end
+
+# TODO: Move to a service object
+def format_amount(amount)
+  format('%.2f', amount)
+end
@@ -0,0 +1,48 @@
+// Sample Scala file for testing
+package com.example.users
+
+import scala.collection.mutable
+
+/** Maximum number of retries */
+val MaxRetries = 3
+
+/** A type that can check itself */
+trait Validatable {
+  def validate(): Boolean
+  val strict: Boolean
+}
+
+/** Represents a user account */
+case class User(name: String, age: Int) extends Validatable {
+  def validate(): Boolean = name.nonEmpty
+  val strict = false
+}
+
+object User {
+  def guest(): User = User("Guest", 0)
+}
+
+sealed trait Shape
+case object Empty extends Shape
+
+enum Role {
+  case Admin, Guest
+}
+
+type UserList = List[User]
+
+class UserService(val prefix: String) {
+  def this() = this("")
+  private var users = mutable.ListBuffer.empty[User]
+
+  def register(user: User): Unit = {
+    // TODO: reject duplicate users
+    users += user
+  }
+}
+
+/* Formats a user's name for display */
+def formatName(user: User): String = {
+  val count = user.name.length
+  s"${user.name} ($count)"
+}
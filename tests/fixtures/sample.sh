@@ -0,0 +1,24 @@
+#!/usr/bin/env bash
+# Sample shell script for testing
+set -euo pipefail
+
+export APP_ENV="production"
+export PATH="$HOME/bin:$PATH"
+readonly MAX_RETRIES=3
+LOG_DIR="/var/log/app"
+
+# Print a message with a timestamp
+log() {
+  echo "[$(date)] $*" >> "$LOG_DIR/app.log"
+}
+
+function deploy {
+  local target="$1"
+  log "Deploying to $target"
+  # TODO: Add rollback support
+  for i in $(seq 1 "$MAX_RETRIES"); do
+    rsync -a ./dist/ "$target" && break
+  done
+}
+
+deploy "$APP_ENV"
@@ -0,0 +1,58 @@
+// Sample Swift file for testing
+import Foundation
+import UIKit
+
+/// Maximum number of retries
+let maxRetries = 3
+
+/// A user of the app
+protocol Identifiable {
+    var id: String { get }
+    func describe() -> String
+}
+
+/// Represents a user account
+struct User: Identifiable {
+    let id: String
+    var name: String
+
+    init(id: String, name: String) {
+        self.id = id
+        self.name = name
+    }
+
+    func describe() -> String {
+        return "User(\(name))"
+    }
+
+    static func guest() -> User {
+        return User(id: "guest", name: "Guest")
+    }
+}
+
+enum Role {
+    case admin
+    case member
+}
+
+/// Manages users
+class UserService {
+    private var users: [User] = []
+
+    func add(_ user: User) {
+        users.append(user)
+    }
+}
+
+extension UserService {
+    var count: Int {
+        return users.count
+    }
+}
+
+typealias UserId = String
+
+// TODO: Move to a separate file
+func formatName(_ user: User) -> String {
+    return user.name.capitalized
+}
@@ -1,2 +1,15 @@
+use std::sync::{Arc, OnceLock};
+
+use codescope_mcp::language::LanguageRegistry;
+
+mod query_test;
 mod stats_test;
 mod symbol_test;
+
+/// Registry of the built-in languages, shared by all tests
+fn registry() -> Arc<LanguageRegistry> {
+    static REGISTRY: OnceLock<Arc<LanguageRegistry>> = OnceLock::new();
+    REGISTRY
+        .get_or_init(|| Arc::new(LanguageRegistry::new().expect("Failed to create registry")))
+        .clone()
+}
@@ -12,6 +12,8 @@ use codescope_mcp::pipeline::stats::{aggregate_statistics, count_lines, FileStat
 use codescope_mcp::pipeline::{ResultCollector, StatsCollector};
 use codescope_mcp::symbol::types::SymbolKind;
 
+use crate::registry;
+
 fn fixtures_path() -> PathBuf {
     PathBuf::from(env!("CARGO_MANIFEST_DIR"))
         .join("tests")
@@ -19,7 +21,7 @@ fn fixtures_path() -> PathBuf {
 }
 
 fn create_test_parser() -> (CachedParser, Arc<LanguageRegistry>) {
-    let registry = Arc::new(LanguageRegistry::new().expect("Failed to create registry"));
+    let registry = registry();
     let parser_cache = Arc::new(ParserCache::new());
     let parser =
         CachedParser::new(registry.clone(), parser_cache).expect("Failed to create parser");
@@ -122,6 +124,27 @@ fn test_stats_collector_typescript() {
     assert!(total_symbols > 0, "Should count symbols");
 }
 
+#[test]
+fn test_stats_collector_makefile() {
+    let (mut parser, _registry) = create_test_parser();
+    let file_path = fixtures_path().join("Makefile");
+
+    let source = std::fs::read_to_string(&file_path).expect("Failed to read file");
+    let cached_content = CachedContent {
+        content: Arc::new(source),
+        modified_time: SystemTime::now(),
+    };
+
+    let results = StatsCollector
+        .process_file(&mut parser, &file_path, &cached_content)
+        .expect("Failed to process file");
+
+    let stats = &results[0];
+    assert_eq!(stats.language_id, LanguageId::Makefile);
+    assert_eq!(stats.symbol_counts.get(&SymbolKind::Target), Some(&3));
+    assert_eq!(stats.symbol_counts.get(&SymbolKind::Variable), Some(&3));
+}
+
 #[test]
 fn test_aggregate_statistics_single_file() {
     let stats = vec![FileStatistics {